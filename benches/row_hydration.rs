@@ -0,0 +1,132 @@
+//! Row hydration benchmarks
+//!
+//! Compares building models straight from row values (`de::from_row`, what
+//! the derived `from_map` uses) with the previous conversion through a
+//! `serde_json::Value` per row, and times `find_all` end to end against an
+//! in-memory database.
+//!
+//! Run with `cargo bench --bench row_hydration`.
+
+use chrono::{DateTime, Utc};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use libsql_orm::testing::TestDatabase;
+use libsql_orm::{de, Model, Row, Value};
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Debug, Clone, Serialize, Deserialize)]
+#[table_name("users")]
+struct User {
+    #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
+    id: Option<i64>,
+    name: String,
+    email: String,
+    #[orm_column(type = "INTEGER")]
+    age: Option<i32>,
+    score: f64,
+    is_active: bool,
+    created_at: DateTime<Utc>,
+}
+
+fn user(i: usize) -> User {
+    User {
+        id: Some(i as i64),
+        name: format!("user-{i}"),
+        email: format!("user-{i}@example.com"),
+        age: (!i.is_multiple_of(3)).then_some((i % 80) as i32),
+        score: i as f64 * 0.5,
+        is_active: i.is_multiple_of(2),
+        created_at: Utc::now(),
+    }
+}
+
+fn rows(n: usize) -> Vec<Row> {
+    (0..n)
+        .map(|i| {
+            let mut row = user(i).to_map().unwrap();
+            // SQLite hands booleans back as integers
+            row.insert("is_active".to_string(), Value::Integer(i as i64 % 2));
+            row
+        })
+        .collect()
+}
+
+/// The conversion `from_map` did before `de::from_row`
+fn via_json(row: Row) -> User {
+    let map: serde_json::Map<String, serde_json::Value> = row
+        .into_iter()
+        .map(|(k, v)| {
+            let json = match v {
+                Value::Null => serde_json::Value::Null,
+                Value::Boolean(b) => serde_json::Value::Bool(b),
+                Value::Integer(i) if k == "is_active" => serde_json::Value::Bool(i != 0),
+                Value::Integer(i) => serde_json::Value::from(i),
+                Value::Real(f) => serde_json::Value::from(f),
+                Value::Text(s) => serde_json::Value::String(s),
+                Value::Blob(b) => serde_json::Value::from(b),
+            };
+            (k, json)
+        })
+        .collect();
+    serde_json::from_value(serde_json::Value::Object(map)).unwrap()
+}
+
+fn hydrate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hydrate");
+    group.sample_size(20);
+    for n in [10_000, 50_000] {
+        let input = rows(n);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(
+            BenchmarkId::new("json_intermediate", n),
+            &input,
+            |b, input| {
+                b.iter_batched(
+                    || input.clone(),
+                    |rows| rows.into_iter().map(via_json).collect::<Vec<_>>(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("direct", n), &input, |b, input| {
+            b.iter_batched(
+                || input.clone(),
+                |rows| {
+                    rows.into_iter()
+                        .map(|row| de::from_row::<User>(row).unwrap())
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn find_all(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let n = 10_000;
+    let db = runtime.block_on(async {
+        let db = TestDatabase::new::<(User,)>().await.unwrap();
+        let users: Vec<User> = (0..n)
+            .map(|i| User {
+                id: None,
+                ..user(i)
+            })
+            .collect();
+        User::bulk_create(&users, &*db).await.unwrap();
+        db
+    });
+
+    let mut group = c.benchmark_group("find_all");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(n as u64));
+    group.bench_function(BenchmarkId::from_parameter(n), |b| {
+        b.iter(|| runtime.block_on(User::find_all(&*db)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, hydrate, find_all);
+criterion_main!(benches);
@@ -14,7 +14,7 @@
 //! use libsql_orm::Model;
 //! use serde::{Serialize, Deserialize};
 //!
-//! #[derive(Model, Serialize, Deserialize)]
+//! #[derive(Model, Clone, Serialize, Deserialize)]
 //! struct User {
 //!     pub id: Option<i64>,
 //!     pub name: String,
@@ -33,7 +33,7 @@
 //! use libsql_orm::Model;
 //! use serde::{Serialize, Deserialize};
 //!
-//! #[derive(Model, Serialize, Deserialize)]
+//! #[derive(Model, Clone, Serialize, Deserialize)]
 //! #[table_name("custom_users")]
 //! struct User {
 //!     pub id: Option<i64>,
@@ -49,7 +49,7 @@
 //! use libsql_orm::Model;
 //! use serde::{Serialize, Deserialize};
 //!
-//! #[derive(Model, Serialize, Deserialize)]
+//! #[derive(Model, Clone, Serialize, Deserialize)]
 //! struct User {
 //!     #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
 //!     pub id: Option<i64>,
@@ -62,22 +62,62 @@
 //! }
 //! ```
 //!
+//! ## `#[orm_validate(...)]`
+//!
+//! Declares validation rules checked before `create` and `update` run any SQL.
+//!
+//! ```rust
+//! use libsql_orm::Model;
+//! use serde::{Serialize, Deserialize};
+//! # fn check_age(age: &Option<i64>) -> Result<(), String> { Ok(()) }
+//!
+//! #[derive(Model, Clone, Serialize, Deserialize)]
+//! struct User {
+//!     pub id: Option<i64>,
+//!
+//!     #[orm_validate(length(min = 3, max = 80))]
+//!     pub name: String,
+//!
+//!     #[orm_validate(email)]
+//!     pub email: String,
+//!
+//!     #[orm_validate(range(min = 0), custom = "check_age")]
+//!     pub age: Option<i64>,
+//! }
+//! ```
+//!
 //! # Function-like Macros
 //!
 //! ## `generate_migration!(Model)`
 //!
 //! Generates a database migration from a model definition.
 //!
-//! ```rust
-//! use libsql_orm::{generate_migration, MigrationManager};
+//! ```no_run
+//! use libsql_orm::{generate_migration, Database, MigrationManager, Model, Result};
+//! # #[derive(Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
 //!
+//! # async fn example(db: Database) -> Result<()> {
 //! let migration = generate_migration!(User);
 //! let manager = MigrationManager::new(db);
 //! manager.execute_migration(&migration).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## `embed_migrations!("dir")`
+//!
+//! Bakes a directory of SQL migrations into the binary at compile time.
+//!
+//! ```ignore
+//! use libsql_orm::{embed_migrations, MigrationManager};
+//!
+//! let manager = MigrationManager::new(db);
+//! manager.run_migrations(embed_migrations!("migrations/")).await?;
 //! ```
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, Lit, Type};
 
 /// Column attribute macro for defining SQL column properties
@@ -94,7 +134,10 @@ use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, Lit, T
 /// # Examples:
 ///
 /// ```rust
-/// #[derive(Model)]
+/// use libsql_orm::Model;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Model, Clone, Serialize, Deserialize)]
 /// struct User {
 ///     #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
 ///     pub id: Option<i64>,
@@ -122,22 +165,37 @@ pub fn orm_column(_args: TokenStream, input: TokenStream) -> TokenStream {
 /// # Attributes:
 /// - `#[table_name("custom_name")]` - Specify custom table name
 /// - `#[orm_column(...)]` - Configure column properties
+/// - `#[orm_validate(...)]` - Validation rules: `email`, `length(min, max)`,
+///   `range(min, max)`, and `custom = "path::to::fn"`
+///
+/// # Column constants:
+///
+/// Each field also gets an associated constant holding its column name, e.g.
+/// `User::EMAIL == "email"`. Use these in filters, sorts, and selections so
+/// that renaming a field is caught at compile time.
+///
+/// # JSON Schema:
+///
+/// `Model::json_schema()` describes the struct's serialized form, built from
+/// the field types, `Option`s, `#[orm_validate]` rules, and doc comments.
 ///
 /// # Examples:
 ///
 /// ```rust
-/// use libsql_orm::Model;
+/// use libsql_orm::{Filter, Model};
 /// use serde::{Serialize, Deserialize};
 ///
-/// #[derive(Model, Serialize, Deserialize)]
+/// #[derive(Model, Clone, Serialize, Deserialize)]
 /// #[table_name("users")]
 /// struct User {
 ///     pub id: Option<i64>,
 ///     pub name: String,
 ///     pub email: String,
 /// }
+///
+/// let filter = Filter::eq(User::EMAIL, "alice@example.com");
 /// ```
-#[proc_macro_derive(Model, attributes(table_name, orm_column))]
+#[proc_macro_derive(Model, attributes(table_name, orm_column, orm_validate))]
 pub fn derive_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
@@ -146,42 +204,106 @@ pub fn derive_model(input: TokenStream) -> TokenStream {
     let table_name =
         extract_table_name(&input.attrs).unwrap_or_else(|| name.to_string().to_lowercase());
 
+    let quoted_table_name = quote_identifier(&table_name);
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Extract field names and column metadata for columns
-    let (field_names, column_definitions, boolean_field_names, boolean_flags) =
-        if let Data::Struct(data) = &input.data {
-            if let Fields::Named(fields) = &data.fields {
-                let mut field_names = Vec::new();
-                let mut column_defs = Vec::new();
-                let mut bool_field_names = Vec::new();
-                let mut bool_flags = Vec::new();
-
-                for field in &fields.named {
-                    let field_name = &field.ident;
-                    let field_name_str = quote! { stringify!(#field_name) };
-                    field_names.push(field_name_str);
-
-                    // Parse column attributes to get SQL definition
-                    let column_def = parse_column_definition(field);
-                    column_defs.push(column_def);
-
-                    // Extract field type information for conversion
-                    let field_type = &field.ty;
-                    let is_bool = is_boolean_type(field_type);
-                    bool_field_names.push(quote! { stringify!(#field_name) });
-                    bool_flags.push(is_bool);
+    let mut validations = Vec::new();
+    let mut schema_properties = Vec::new();
+    let mut schema_required = Vec::new();
+    let (field_names, column_definitions, column_consts) = if let Data::Struct(data) = &input.data {
+        if let Fields::Named(fields) = &data.fields {
+            let mut field_names = Vec::new();
+            let mut column_defs = Vec::new();
+            let mut column_consts = Vec::new();
+
+            for field in &fields.named {
+                let field_name = &field.ident;
+                let field_name_str = quote! { stringify!(#field_name) };
+                field_names.push(field_name_str);
+
+                // `User::EMAIL`-style constant holding the column name
+                if let Some(ident) = field_name {
+                    let plain = ident.to_string().trim_start_matches("r#").to_string();
+                    let const_name = format_ident!("{}", plain.to_uppercase());
+                    let doc = format!("Column name of the `{plain}` field");
+                    column_consts.push(quote! {
+                        #[doc = #doc]
+                        pub const #const_name: &'static str = stringify!(#ident);
+                    });
                 }
 
-                (field_names, column_defs, bool_field_names, bool_flags)
-            } else {
-                (vec![], vec![], vec![], vec![])
+                let (mut keywords, optional) = field_json_schema(&field.ty);
+                match parse_validations(field, &mut keywords) {
+                    Ok(rules) => validations.extend(rules),
+                    Err(err) => return err.to_compile_error().into(),
+                }
+                if let Some(ident) = field_name {
+                    let plain = ident.to_string().trim_start_matches("r#").to_string();
+                    if let Some(doc) = doc_comment(&field.attrs) {
+                        keywords.insert(0, ("description".to_string(), json_string(&doc)));
+                    }
+                    schema_properties.push(format!(
+                        "{}: {}",
+                        json_string(&plain),
+                        json_object(&keywords)
+                    ));
+                    if !optional {
+                        schema_required.push(json_string(&plain));
+                    }
+                }
+
+                // Parse column attributes to get SQL definition
+                let column_def = parse_column_definition(field);
+                column_defs.push(column_def);
             }
+
+            (field_names, column_defs, column_consts)
         } else {
-            (vec![], vec![], vec![], vec![])
-        };
+            (vec![], vec![], vec![])
+        }
+    } else {
+        (vec![], vec![], vec![])
+    };
+
+    let mut schema = vec![
+        (
+            "$schema".to_string(),
+            json_string("https://json-schema.org/draft/2020-12/schema"),
+        ),
+        ("title".to_string(), json_string(&name.to_string())),
+    ];
+    if let Some(doc) = doc_comment(&input.attrs) {
+        schema.push(("description".to_string(), json_string(&doc)));
+    }
+    schema.push(("type".to_string(), json_string("object")));
+    schema.push((
+        "properties".to_string(),
+        format!("{{{}}}", schema_properties.join(", ")),
+    ));
+    schema.push((
+        "required".to_string(),
+        format!("[{}]", schema_required.join(", ")),
+    ));
+    let schema = json_object(&schema);
+    let validate_fn = if validations.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn validate(&self) -> std::result::Result<(), libsql_orm::ValidationErrors> {
+                let mut errors = libsql_orm::ValidationErrors::new();
+                #(#validations)*
+                errors.into_result()
+            }
+        }
+    };
 
     let expanded = quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#column_consts)*
+        }
+
         impl #impl_generics libsql_orm::Model for #name #ty_generics #where_clause {
             fn table_name() -> &'static str {
                 #table_name
@@ -204,11 +326,21 @@ pub fn derive_model(input: TokenStream) -> TokenStream {
                 let columns = vec![#(#column_definitions),*];
                 format!(
                     "CREATE TABLE IF NOT EXISTS {} (\n    {}\n)",
-                    Self::table_name(),
+                    #quoted_table_name,
                     columns.join(",\n    ")
                 )
             }
 
+            fn column_definitions() -> Vec<String> {
+                vec![#(#column_definitions),*].into_iter().map(String::from).collect()
+            }
+
+            fn json_schema() -> serde_json::Value {
+                serde_json::from_str(#schema).expect("derived JSON Schema is valid JSON")
+            }
+
+            #validate_fn
+
             fn to_map(&self) -> libsql_orm::Result<std::collections::HashMap<String, libsql_orm::Value>> {
                 use serde_json;
                 let json = serde_json::to_value(self)?;
@@ -238,60 +370,207 @@ pub fn derive_model(input: TokenStream) -> TokenStream {
             }
 
             fn from_map(map: std::collections::HashMap<String, libsql_orm::Value>) -> libsql_orm::Result<Self> {
-                use serde_json;
-                let mut json_map = serde_json::Map::new();
+                libsql_orm::de::from_row(map)
+            }
+        }
 
-                for (k, v) in map {
-                    let json_value = match v {
-                        libsql_orm::Value::Null => serde_json::Value::Null,
-                        libsql_orm::Value::Boolean(b) => serde_json::Value::Bool(b),
-                        libsql_orm::Value::Integer(i) => {
-                            // Convert integers to booleans for known boolean fields
-                            let field_name = k.as_str();
-                            let mut is_boolean_field = false;
-                            #(
-                                if field_name == #boolean_field_names {
-                                    is_boolean_field = #boolean_flags;
-                                }
-                            )*
-
-                            if is_boolean_field {
-                                serde_json::Value::Bool(i != 0)
-                            } else {
-                                serde_json::Value::Number(serde_json::Number::from(i))
-                            }
-                        }
-                        libsql_orm::Value::Real(f) => {
-                            if let Some(n) = serde_json::Number::from_f64(f) {
-                                serde_json::Value::Number(n)
-                            } else {
-                                serde_json::Value::String(f.to_string())
-                            }
-                        }
-                        libsql_orm::Value::Text(s) => serde_json::Value::String(s),
-                        libsql_orm::Value::Blob(b) => {
-                            serde_json::Value::Array(b.into_iter().map(|byte| serde_json::Value::Number(serde_json::Number::from(byte))).collect())
-                        }
-                    };
-                    json_map.insert(k, json_value);
+        impl #impl_generics libsql_orm::FromRow for #name #ty_generics #where_clause {
+            fn from_row(row: libsql_orm::Row) -> libsql_orm::Result<Self> {
+                <Self as libsql_orm::Model>::from_map(row)
+            }
+        }
+
+        // Note: Clone is already derived in the struct definition
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derive macro for the FromRow trait
+///
+/// Maps a result row onto a plain `Deserialize` struct by column name, so
+/// projections, joins, and aggregates can be read without defining a full
+/// model. Values are fed straight into the struct's `Deserialize` impl (see
+/// `libsql_orm::de`), so integer columns read into `bool` fields.
+///
+/// Types deriving `Model` already implement `FromRow`.
+///
+/// # Examples:
+///
+/// ```rust
+/// use libsql_orm::FromRow;
+/// use serde::Deserialize;
+///
+/// #[derive(FromRow, Deserialize)]
+/// struct ActiveCount {
+///     pub is_active: bool,
+///     pub n: i64,
+/// }
+/// ```
+#[proc_macro_derive(FromRow)]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics libsql_orm::FromRow for #name #ty_generics #where_clause {
+            fn from_row(row: libsql_orm::Row) -> libsql_orm::Result<Self> {
+                libsql_orm::de::from_row(row)
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derive macro for test data factories
+///
+/// Implements `libsql_orm::testing::FactoryDefaults` and adds a
+/// `{Name}Factory` alias for `libsql_orm::testing::Factory<{Name}>`. Each
+/// built instance gets a sequence number `n`, unique within the process,
+/// and fields default by type: `Option`s to `None`, strings to
+/// `"{field}-{n}"`, numbers to `n`, `bool`s to `false`, and anything else
+/// to `Default::default()`.
+///
+/// # Attributes:
+/// - `#[factory(sequence = "user{n}@example.com")]` - format the field with `n`
+/// - `#[factory(default = "expression")]` - use an expression instead
+///
+/// # Examples:
+///
+/// ```no_run
+/// use libsql_orm::{Database, Factory, Model, Result};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Model, Factory, Clone, Serialize, Deserialize)]
+/// struct User {
+///     pub id: Option<i64>,
+///     pub name: String,
+///     #[factory(sequence = "user{n}@example.com")]
+///     pub email: String,
+///     pub age: Option<i64>,
+///     #[factory(default = "true")]
+///     pub is_active: bool,
+/// }
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// let adults = UserFactory::new().with(|u| u.age = Some(30)).create_many(50, db).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_derive(Factory, attributes(factory))]
+pub fn derive_factory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let vis = &input.vis;
+    let alias = format_ident!("{}Factory", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut fields = Vec::new();
+    if let Data::Struct(data) = &input.data {
+        if let Fields::Named(named) = &data.fields {
+            for field in &named.named {
+                match factory_default(field) {
+                    Ok(value) => {
+                        let ident = &field.ident;
+                        fields.push(quote! { #ident: #value });
+                    }
+                    Err(err) => return err.to_compile_error().into(),
                 }
+            }
+        }
+    }
 
-                let json_value = serde_json::Value::Object(json_map);
-                let result: Self = serde_json::from_value(json_value)?;
-                Ok(result)
+    let alias_doc = format!("Builds `{name}` test data");
+    let alias_decl = if input.generics.params.is_empty() {
+        quote! {
+            #[doc = #alias_doc]
+            #[allow(dead_code)]
+            #vis type #alias = libsql_orm::testing::Factory<#name>;
+        }
+    } else {
+        quote! {}
+    };
+
+    let expanded = quote! {
+        impl #impl_generics libsql_orm::testing::FactoryDefaults for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn factory_default(n: u64) -> Self {
+                Self {
+                    #(#fields),*
+                }
             }
         }
 
-        // Note: Clone is already derived in the struct definition
+        #alias_decl
     };
 
     TokenStream::from(expanded)
 }
 
+/// The default value of a field in `FactoryDefaults::factory_default`
+fn factory_default(field: &Field) -> syn::Result<proc_macro2::TokenStream> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("factory") {
+            continue;
+        }
+        let mut value = None;
+        attr.parse_nested_meta(|meta| {
+            let lit: syn::LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("sequence") {
+                value = Some(quote! { format!(#lit, n = n).into() });
+            } else if meta.path.is_ident("default") {
+                let expr: syn::Expr = lit.parse()?;
+                value = Some(quote! { #expr });
+            } else {
+                return Err(meta.error("expected `sequence` or `default`"));
+            }
+            Ok(())
+        })?;
+        if let Some(value) = value {
+            return Ok(value);
+        }
+    }
+
+    let plain = field
+        .ident
+        .as_ref()
+        .map(|ident| ident.to_string().trim_start_matches("r#").to_string())
+        .unwrap_or_default();
+    let type_name = match &field.ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let ty = &field.ty;
+    Ok(match type_name.as_str() {
+        "Option" => quote! { None },
+        "String" => {
+            let pattern = format!("{plain}-{{n}}");
+            quote! { format!(#pattern, n = n) }
+        }
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64" => {
+            quote! { n as #ty }
+        }
+        "bool" => quote! { false },
+        _ => quote! { <#ty as Default>::default() },
+    })
+}
+
+/// Quote a table or column name for generated SQL
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
 /// Parse column definition from field attributes
 fn parse_column_definition(field: &Field) -> proc_macro2::TokenStream {
     let field_name = &field.ident;
-    let field_name_str = field_name.as_ref().unwrap().to_string();
+    let field_name_str = quote_identifier(&field_name.as_ref().unwrap().to_string());
 
     // Default column definitions based on field type
     let default_def = match &field.ty {
@@ -364,6 +643,204 @@ fn parse_column_definition(field: &Field) -> proc_macro2::TokenStream {
     quote! { #default_def }
 }
 
+/// Turn a field's `#[orm_validate(...)]` attributes into checks for `Model::validate`,
+/// adding the matching JSON Schema keywords to `keywords`
+fn parse_validations(
+    field: &Field,
+    keywords: &mut Vec<(String, String)>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let Some(ident) = &field.ident else {
+        return Ok(Vec::new());
+    };
+    let field_name = ident.to_string().trim_start_matches("r#").to_string();
+
+    let mut checks = Vec::new();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("orm_validate") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("email") {
+                keywords.push(("format".to_string(), json_string("email")));
+                checks.push(quote! {
+                    libsql_orm::validation::email(&mut errors, #field_name, &self.#ident);
+                });
+            } else if meta.path.is_ident("length") {
+                let (mut min, mut max) = (quote! { None }, quote! { None });
+                meta.parse_nested_meta(|bound| {
+                    let value: syn::LitInt = bound.value()?.parse()?;
+                    let value = value.base10_parse::<usize>()?;
+                    if bound.path.is_ident("min") {
+                        keywords.push(("minLength".to_string(), value.to_string()));
+                        min = quote! { Some(#value) };
+                    } else if bound.path.is_ident("max") {
+                        keywords.push(("maxLength".to_string(), value.to_string()));
+                        max = quote! { Some(#value) };
+                    } else {
+                        return Err(bound.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                checks.push(quote! {
+                    libsql_orm::validation::length(&mut errors, #field_name, &self.#ident, #min, #max);
+                });
+            } else if meta.path.is_ident("range") {
+                let (mut min, mut max) = (quote! { None }, quote! { None });
+                meta.parse_nested_meta(|bound| {
+                    let input = bound.value()?;
+                    let negative = input.parse::<Option<syn::Token![-]>>()?.is_some();
+                    let value = match input.parse::<Lit>()? {
+                        Lit::Int(lit) => lit.base10_parse::<f64>()?,
+                        Lit::Float(lit) => lit.base10_parse::<f64>()?,
+                        lit => return Err(syn::Error::new(lit.span(), "expected a number")),
+                    };
+                    let value = if negative { -value } else { value };
+                    if bound.path.is_ident("min") {
+                        keywords.push(("minimum".to_string(), value.to_string()));
+                        min = quote! { Some(#value) };
+                    } else if bound.path.is_ident("max") {
+                        keywords.push(("maximum".to_string(), value.to_string()));
+                        max = quote! { Some(#value) };
+                    } else {
+                        return Err(bound.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                checks.push(quote! {
+                    libsql_orm::validation::range(&mut errors, #field_name, &self.#ident, #min, #max);
+                });
+            } else if meta.path.is_ident("custom") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                let path: syn::Path = path.parse()?;
+                checks.push(quote! {
+                    libsql_orm::validation::custom(&mut errors, #field_name, #path(&self.#ident));
+                });
+            } else {
+                return Err(meta.error(
+                    "unknown validation rule, expected `email`, `length`, `range`, or `custom`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(checks)
+}
+
+/// JSON Schema keywords describing a field's serialized form, and whether
+/// the field is an `Option`
+fn field_json_schema(ty: &Type) -> (Vec<(String, String)>, bool) {
+    if let Some(inner) = generic_argument(ty, "Option") {
+        let mut keywords = type_json_schema(inner);
+        for (key, value) in &mut keywords {
+            if key == "type" {
+                *value = format!("[{value}, \"null\"]");
+            }
+        }
+        return (keywords, true);
+    }
+    (type_json_schema(ty), false)
+}
+
+fn type_json_schema(ty: &Type) -> Vec<(String, String)> {
+    let simple = |json_type: &str, format: Option<&str>| {
+        let mut keywords = vec![("type".to_string(), json_string(json_type))];
+        if let Some(format) = format {
+            keywords.push(("format".to_string(), json_string(format)));
+        }
+        keywords
+    };
+    if let Type::Reference(reference) = ty {
+        return type_json_schema(&reference.elem);
+    }
+    if let Some(item) = generic_argument(ty, "Vec") {
+        return vec![
+            ("type".to_string(), json_string("array")),
+            ("items".to_string(), json_object(&type_json_schema(item))),
+        ];
+    }
+    let Type::Path(type_path) = ty else {
+        return Vec::new();
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return Vec::new();
+    };
+    match segment.ident.to_string().as_str() {
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => {
+            simple("integer", None)
+        }
+        "f32" | "f64" => simple("number", None),
+        "bool" => simple("boolean", None),
+        "String" | "str" | "char" => simple("string", None),
+        "DateTime" | "NaiveDateTime" => simple("string", Some("date-time")),
+        "NaiveDate" => simple("string", Some("date")),
+        "NaiveTime" => simple("string", Some("time")),
+        "Uuid" => simple("string", Some("uuid")),
+        // Anything else (JSON values, custom types) is left unconstrained
+        _ => Vec::new(),
+    }
+}
+
+/// The type argument of `Wrapper<T>`, when `ty` is one
+fn generic_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    arguments.args.iter().find_map(|argument| match argument {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// The `///` comment on an item, with each line trimmed
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: Lit::Str(doc), ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn json_object(keywords: &[(String, String)]) -> String {
+    let members: Vec<String> = keywords
+        .iter()
+        .map(|(key, value)| format!("{}: {value}", json_string(key)))
+        .collect();
+    format!("{{{}}}", members.join(", "))
+}
+
 /// Extract table name from struct attributes
 fn extract_table_name(attrs: &[Attribute]) -> Option<String> {
     for attr in attrs {
@@ -376,17 +853,6 @@ fn extract_table_name(attrs: &[Attribute]) -> Option<String> {
     None
 }
 
-/// Check if a type is a boolean type
-fn is_boolean_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.last() {
-            let type_name = &segment.ident;
-            return type_name == "bool";
-        }
-    }
-    false
-}
-
 /// Macro to generate migration from a model
 ///
 /// Creates a migration instance from a model's schema definition. The migration
@@ -394,15 +860,20 @@ fn is_boolean_type(ty: &Type) -> bool {
 ///
 /// # Examples:
 ///
-/// ```rust
-/// use libsql_orm::{generate_migration, MigrationManager};
+/// ```no_run
+/// use libsql_orm::{generate_migration, Database, MigrationManager, Model, Result};
+/// # #[derive(Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct User { id: Option<i64>, name: String }
 ///
+/// # async fn example(db: Database) -> Result<()> {
 /// // Generate migration for User model
 /// let user_migration = generate_migration!(User);
 ///
 /// // Execute the migration
 /// let manager = MigrationManager::new(db);
 /// manager.execute_migration(&user_migration).await?;
+/// # Ok(())
+/// # }
 /// ```
 #[proc_macro]
 pub fn generate_migration(input: TokenStream) -> TokenStream {
@@ -420,3 +891,124 @@ pub fn generate_migration(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Macro to embed a directory of SQL migrations at compile time
+///
+/// Reads the directory (relative to the invoking crate's `Cargo.toml`) while
+/// compiling and expands to a `Vec<Migration>` sorted by name, so migrations
+/// can run where there is no filesystem, such as Cloudflare Workers. Each
+/// migration's id is its name, which lets `MigrationManager::run_migrations`
+/// skip the ones already applied.
+///
+/// Recognized layouts, which may be mixed:
+/// - `NAME.sql` — up SQL only
+/// - `NAME.up.sql` with an optional `NAME.down.sql`
+/// - `NAME/up.sql` with an optional `NAME/down.sql`
+///
+/// File contents are tracked by the compiler, but adding a new file requires a
+/// rebuild of the crate that invokes the macro (for example `touch src/lib.rs`).
+///
+/// # Examples:
+///
+/// ```ignore
+/// use libsql_orm::{embed_migrations, MigrationManager};
+///
+/// // migrations/20240101_create_users.up.sql
+/// // migrations/20240101_create_users.down.sql
+/// // migrations/20240102_add_posts/up.sql
+/// let migrations = embed_migrations!("migrations/");
+///
+/// let manager = MigrationManager::new(db);
+/// manager.init().await?;
+/// manager.run_migrations(migrations).await?;
+/// ```
+#[proc_macro]
+pub fn embed_migrations(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as syn::LitStr);
+
+    match collect_embedded_migrations(&dir.value()) {
+        Ok(migrations) => {
+            let entries = migrations.iter().map(|(name, up, down)| {
+                let down = match down {
+                    Some(path) => quote! { ::std::option::Option::Some(include_str!(#path)) },
+                    None => quote! { ::std::option::Option::None },
+                };
+                quote! {
+                    libsql_orm::Migration::embedded(#name, include_str!(#up), #down)
+                }
+            });
+            TokenStream::from(quote! {
+                {
+                    let migrations: ::std::vec::Vec<libsql_orm::Migration> = vec![#(#entries),*];
+                    migrations
+                }
+            })
+        }
+        Err(message) => syn::Error::new(dir.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Find `(name, up path, down path)` for every migration in `dir`
+fn collect_embedded_migrations(dir: &str) -> Result<Vec<(String, String, Option<String>)>, String> {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .map_err(|_| "CARGO_MANIFEST_DIR is not set".to_string())?
+        .join(dir);
+    let entries = std::fs::read_dir(&root).map_err(|e| {
+        format!(
+            "Failed to read migrations directory {}: {e}",
+            root.display()
+        )
+    })?;
+
+    let mut ups: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut downs: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read migrations directory: {e}"))?
+            .path();
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if path.is_dir() {
+            let up = path.join("up.sql");
+            let down = path.join("down.sql");
+            if up.is_file() {
+                ups.insert(file_name.clone(), up);
+            }
+            if down.is_file() {
+                downs.insert(file_name, down);
+            }
+        } else if let Some(name) = file_name.strip_suffix(".down.sql") {
+            downs.insert(name.to_string(), path);
+        } else if let Some(name) = file_name.strip_suffix(".up.sql") {
+            ups.insert(name.to_string(), path);
+        } else if let Some(name) = file_name.strip_suffix(".sql") {
+            ups.insert(name.to_string(), path);
+        }
+    }
+
+    if let Some(orphan) = downs.keys().find(|name| !ups.contains_key(*name)) {
+        return Err(format!("Migration {orphan} has down SQL but no up SQL"));
+    }
+
+    let path_string = |path: &PathBuf| {
+        path.to_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Migration path {} is not valid UTF-8", path.display()))
+    };
+    ups.iter()
+        .map(|(name, up)| {
+            let down = downs.get(name).map(path_string).transpose()?;
+            Ok((name.clone(), path_string(up)?, down))
+        })
+        .collect()
+}
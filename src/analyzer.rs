@@ -0,0 +1,200 @@
+//! N+1 query detection for libsql-orm
+//!
+//! The [`QueryAnalyzer`] watches the statements a [`Database`](crate::Database)
+//! runs and warns when the same parameterized SQL is executed many times in
+//! quick succession — the classic N+1 pattern where a loop issues one query
+//! per parent row instead of a single `IN (...)` or join.
+//!
+//! The analyzer is opt-in and only records in debug builds; in release builds
+//! enabling it is a no-op, so it can stay in development setup code.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{Database, QueryAnalyzer};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let db = Database::new_local(":memory:").await?;
+//! db.enable_query_analyzer(QueryAnalyzer::new().threshold(10));
+//!
+//! // ... run the code under test ...
+//!
+//! for repeated in db.repeated_queries() {
+//!     println!("{} ran {} times: {}", repeated.table.as_deref().unwrap_or("?"), repeated.count, repeated.sql);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Statements are tracked per `Database` handle. On Cloudflare Workers a
+//! handle usually lives for one request, which makes the report per request;
+//! elsewhere call [`Database::reset_query_analyzer`](crate::Database::reset_query_analyzer)
+//! between units of work.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Detects statements that are repeated within a sliding window
+///
+/// A statement is reported once it appears `threshold` times among the last
+/// `window` statements. Parameter values are ignored, so
+/// `SELECT * FROM posts WHERE user_id = ?` run for 20 different users counts
+/// as 20 repetitions.
+#[derive(Debug, Clone)]
+pub struct QueryAnalyzer {
+    threshold: usize,
+    window: usize,
+    recent: VecDeque<String>,
+    counts: HashMap<String, usize>,
+    reported: HashMap<String, RepeatedQuery>,
+}
+
+/// A statement flagged by the [`QueryAnalyzer`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatedQuery {
+    /// The parameterized SQL
+    pub sql: String,
+    /// Table the statement reads from or writes to, when it can be determined
+    pub table: Option<String>,
+    /// Highest number of repetitions observed within the window
+    pub count: usize,
+}
+
+impl Default for QueryAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryAnalyzer {
+    /// Create an analyzer that flags 5 repetitions within 50 statements
+    pub fn new() -> Self {
+        Self {
+            threshold: 5,
+            window: 50,
+            recent: VecDeque::new(),
+            counts: HashMap::new(),
+            reported: HashMap::new(),
+        }
+    }
+
+    /// Set how many repetitions trigger a warning (minimum 2)
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold.max(2);
+        self
+    }
+
+    /// Set how many recent statements are considered
+    pub fn window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Statements flagged so far
+    pub fn repeated_queries(&self) -> Vec<RepeatedQuery> {
+        let mut repeated: Vec<RepeatedQuery> = self.reported.values().cloned().collect();
+        repeated.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.sql.cmp(&b.sql)));
+        repeated
+    }
+
+    /// Forget all recorded statements and reports
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.counts.clear();
+        self.reported.clear();
+    }
+
+    /// Record a statement, returning it when it first crosses the threshold
+    pub(crate) fn record(&mut self, sql: &str) -> Option<RepeatedQuery> {
+        if is_transaction_control(sql) {
+            return None;
+        }
+
+        self.recent.push_back(sql.to_string());
+        let count = {
+            let count = self.counts.entry(sql.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+        if self.recent.len() > self.window {
+            if let Some(oldest) = self.recent.pop_front() {
+                if let Some(c) = self.counts.get_mut(&oldest) {
+                    *c -= 1;
+                    if *c == 0 {
+                        self.counts.remove(&oldest);
+                    }
+                }
+            }
+        }
+
+        if count < self.threshold {
+            return None;
+        }
+
+        match self.reported.get_mut(sql) {
+            Some(existing) => {
+                existing.count = existing.count.max(count);
+                None
+            }
+            None => {
+                let repeated = RepeatedQuery {
+                    sql: sql.to_string(),
+                    table: statement_table(sql),
+                    count,
+                };
+                self.reported.insert(sql.to_string(), repeated.clone());
+                Some(repeated)
+            }
+        }
+    }
+}
+
+/// Log a warning for a newly flagged statement
+pub(crate) fn warn_repeated(repeated: &RepeatedQuery) {
+    let message = format!(
+        "Possible N+1 query on table {}: statement ran {} times in quick succession; consider a join or an IN (...) filter. SQL: {}",
+        repeated.table.as_deref().unwrap_or("<unknown>"),
+        repeated.count,
+        repeated.sql
+    );
+    #[cfg(target_arch = "wasm32")]
+    {
+        #[cfg(feature = "web-sys")]
+        web_sys::console::warn_1(&format!("[WARN] {message}").into());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        log::warn!("{message}");
+    }
+}
+
+fn is_transaction_control(sql: &str) -> bool {
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    matches!(
+        keyword.as_str(),
+        "BEGIN" | "COMMIT" | "END" | "ROLLBACK" | "SAVEPOINT" | "RELEASE"
+    )
+}
+
+/// Best-effort extraction of the table a statement targets
+fn statement_table(sql: &str) -> Option<String> {
+    let tokens: Vec<&str> = sql.split_whitespace().collect();
+    tokens
+        .windows(2)
+        .find(|pair| {
+            matches!(
+                pair[0].to_ascii_uppercase().as_str(),
+                "FROM" | "INTO" | "UPDATE"
+            )
+        })
+        .map(|pair| {
+            pair[1]
+                .trim_matches(|c: char| c == '(' || c == ')' || c == ';' || c == ',')
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|table| !table.is_empty())
+}
@@ -0,0 +1,235 @@
+//! axum integration, behind the `axum` feature
+//!
+//! - [`Db`] extracts the shared [`Database`] from router state
+//! - [`transaction`] is a middleware that wraps each request in a
+//!   transaction, committed when the handler answers with a success or
+//!   redirect status and rolled back otherwise; handlers take it with the
+//!   [`Tx`] extractor
+//! - [`Error`] implements `IntoResponse`, answering with
+//!   [`Error::status_code`] and a JSON body
+//!
+//! The same router runs on Cloudflare Workers through the `worker` crate's
+//! `axum` feature.
+//!
+//! A `Database` wraps a single connection, so the middleware holds a lock on
+//! the handle for the whole request and requests through it run one at a
+//! time. Statements that bypass it, such as a handler using [`Db`] on a route
+//! without the middleware, run inside whichever request transaction is open;
+//! layer the middleware over the whole router, or give such routes their own
+//! handle.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use axum::routing::{get, post};
+//! use axum::{middleware, Json, Router};
+//! use libsql_orm::axum::{transaction, Db, Tx};
+//! use libsql_orm::{Database, Model, Result};
+//! use std::sync::Arc;
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//!
+//! async fn list_users(tx: Tx) -> Result<Json<Vec<User>>> {
+//!     Ok(Json(User::find_all(&tx).await?))
+//! }
+//!
+//! async fn count_users(Db(db): Db) -> Result<Json<u64>> {
+//!     Ok(Json(User::count(&*db).await?))
+//! }
+//!
+//! async fn create_user(tx: Tx, Json(user): Json<User>) -> Result<Json<User>> {
+//!     Ok(Json(user.create(&tx).await?))
+//! }
+//!
+//! # async fn example(db: Database) {
+//! let db = Arc::new(db);
+//! let app: Router = Router::new()
+//!     .route("/users", get(list_users).post(create_user))
+//!     .route("/users/count", get(count_users))
+//!     .layer(middleware::from_fn_with_state(db.clone(), transaction))
+//!     .with_state(db);
+//! # }
+//! ```
+
+use crate::{ChangeEvent, Database, Error, Executor, Result, Row, Value};
+use ::axum::extract::{FromRef, FromRequestParts, Request, State};
+use ::axum::http::request::Parts;
+use ::axum::http::StatusCode;
+use ::axum::middleware::Next;
+use ::axum::response::{IntoResponse, Response};
+use ::axum::Json;
+use std::sync::{Arc, Mutex};
+
+/// The shared [`Database`], extracted from any state that holds an `Arc<Database>`
+#[derive(Clone)]
+pub struct Db(pub Arc<Database>);
+
+impl<S> FromRequestParts<S> for Db
+where
+    Arc<Database>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        _parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(Db(Arc::<Database>::from_ref(state)))
+    }
+}
+
+/// The request's transaction, opened by the [`transaction`] middleware
+///
+/// An [`Executor`]: pass `&tx` to model and builder methods. Change events
+/// are delivered once the middleware commits.
+#[derive(Clone)]
+pub struct Tx {
+    db: Arc<Database>,
+    events: Arc<Mutex<Vec<ChangeEvent>>>,
+}
+
+impl Tx {
+    /// The database the transaction runs on
+    pub fn database(&self) -> &Database {
+        &self.db
+    }
+
+    fn events_guard(&self) -> std::sync::MutexGuard<'_, Vec<ChangeEvent>> {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Tx {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts.extensions.get::<Tx>().cloned().ok_or_else(|| {
+            Error::Generic(
+                "No transaction for this request; add the transaction middleware".to_string(),
+            )
+        })
+    }
+}
+
+impl Executor for Tx {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        self.db.execute_statement(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        self.db.query_statement(table, sql, params).await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        if self.db.has_subscribers() {
+            self.events_guard().extend(events());
+        }
+    }
+
+    fn in_transaction(&self) -> bool {
+        true
+    }
+}
+
+/// Middleware running each request in a transaction
+///
+/// Install with `axum::middleware::from_fn_with_state(db, transaction)`.
+/// The transaction commits when the response status is below 400 and rolls
+/// back otherwise; a failed commit rolls back and turns the response into
+/// that error. Requests wait for the previous request on the same
+/// `Database` to finish. If the handler panics or the request is dropped,
+/// the transaction is rolled back before the next statement runs.
+pub async fn transaction(
+    State(db): State<Arc<Database>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let _serialized = db.request_lock.lock().await;
+    if let Err(e) = db.execute_for(None, "BEGIN", vec![]).await {
+        return e.into_response();
+    }
+    // Declared after the lock so it is dropped first
+    let open = OpenTransaction(Some(&db));
+    let tx = Tx {
+        db: db.clone(),
+        events: Arc::default(),
+    };
+    request.extensions_mut().insert(tx.clone());
+
+    let response = next.run(request).await;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        match db.execute_for(None, "ROLLBACK", vec![]).await {
+            Ok(_) => open.finish(),
+            Err(e) => log::error!("Failed to roll back request transaction: {e}"),
+        }
+        return response;
+    }
+
+    if let Err(e) = db.execute_for(None, "COMMIT", vec![]).await {
+        if !db.in_transaction() || db.execute_for(None, "ROLLBACK", vec![]).await.is_ok() {
+            open.finish();
+        }
+        return e.into_response();
+    }
+    open.finish();
+    let events = std::mem::take(&mut *tx.events_guard());
+    db.emit_changes(|| events);
+    response
+}
+
+/// Has the database roll back the request's transaction before its next
+/// statement unless [`finish`](Self::finish) is called, covering handler
+/// panics and dropped requests
+struct OpenTransaction<'a>(Option<&'a Database>);
+
+impl OpenTransaction<'_> {
+    fn finish(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for OpenTransaction<'_> {
+    fn drop(&mut self) {
+        if let Some(db) = self.0 {
+            db.mark_dropped_transaction();
+        }
+    }
+}
+
+/// Answers with [`Error::status_code`] and `{"error": message}`
+///
+/// Validation failures add `"fields"` with the per-field errors. Server
+/// errors are logged and answered with a generic message, so SQL and
+/// parameter details never reach the client.
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = match &self {
+            Error::InvalidFields(fields) => serde_json::json!({
+                "error": "Validation failed",
+                "fields": fields,
+            }),
+            _ if status.is_server_error() => {
+                log::error!("{self}");
+                serde_json::json!({ "error": status.canonical_reason().unwrap_or("Server error") })
+            }
+            Error::Statement { source, .. } => serde_json::json!({ "error": source.to_string() }),
+            _ => serde_json::json!({ "error": self.to_string() }),
+        };
+        (status, Json(body)).into_response()
+    }
+}
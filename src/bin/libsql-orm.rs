@@ -0,0 +1,150 @@
+//! Command-line migration runner for libsql-orm
+//!
+//! Build with `cargo install libsql-orm --features cli`, then:
+//!
+//! ```text
+//! libsql-orm migrate up              apply pending migrations
+//! libsql-orm migrate down [STEPS]    roll back the last STEPS migrations (default 1)
+//! libsql-orm migrate status          list applied, pending, and unknown migrations
+//! libsql-orm migrate baseline <VER>  mark migrations up to VER as applied
+//! libsql-orm migrate new <NAME>      create NAME.up.sql / NAME.down.sql
+//! ```
+//!
+//! The database is read from `LIBSQL_ORM_DATABASE_URL` (or `TURSO_DATABASE_URL`):
+//! `libsql://`, `https://`, and `http://` URLs connect remotely with
+//! `TURSO_AUTH_TOKEN`, anything else is opened as a local file. Migrations
+//! are read from `./migrations`, or the directory given with `--dir`.
+
+use libsql_orm::{Database, Error, MigrationManager};
+
+const USAGE: &str = "Usage: libsql-orm migrate <up | down [STEPS] | status | baseline VERSION | new NAME> [--dir DIR]";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    if let Err(e) = run(std::env::args().skip(1).collect()).await {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Vec<String>) -> Result<(), Error> {
+    let mut dir = "migrations".to_string();
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => {
+                dir = args
+                    .next()
+                    .ok_or_else(|| Error::Validation("--dir needs a value".to_string()))?;
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+    match positional.as_slice() {
+        ["migrate", "new", name] => new_migration(&dir, name),
+        ["migrate", "up"] => {
+            let manager = connect().await?;
+            let before = manager.get_executed_migrations().await?.len();
+            manager
+                .run_migrations(MigrationManager::load_migrations_dir(&dir)?)
+                .await?;
+            let executed = manager.get_executed_migrations().await?;
+            if executed.len() == before {
+                println!("nothing to apply");
+            }
+            for migration in executed.iter().skip(before) {
+                println!("applied {}", migration.name);
+            }
+            Ok(())
+        }
+        ["migrate", "down", rest @ ..] => {
+            let steps = match rest {
+                [] => 1,
+                [steps] => steps
+                    .parse()
+                    .map_err(|_| Error::Validation(format!("invalid step count: {steps}")))?,
+                _ => return Err(Error::Validation(USAGE.to_string())),
+            };
+            let manager = connect().await?;
+            for migration in manager.rollback(steps).await? {
+                println!("rolled back {}", migration.name);
+            }
+            Ok(())
+        }
+        ["migrate", "baseline", version] => {
+            let version = version
+                .parse()
+                .map_err(|_| Error::Validation(format!("invalid version: {version}")))?;
+            let manager = connect().await?;
+            let migrations = MigrationManager::load_migrations_dir(&dir)?;
+            for migration in manager.baseline(&migrations, version).await? {
+                println!("marked {}", migration.name);
+            }
+            Ok(())
+        }
+        ["migrate", "status"] => {
+            let manager = connect().await?;
+            let status = manager
+                .status(&MigrationManager::load_migrations_dir(&dir)?)
+                .await?;
+            for migration in &status.applied {
+                println!("applied  {}", migration.name);
+            }
+            for migration in &status.pending {
+                println!("pending  {}", migration.name);
+            }
+            for migration in &status.unknown {
+                println!("unknown  {}", migration.name);
+            }
+            Ok(())
+        }
+        _ => Err(Error::Validation(USAGE.to_string())),
+    }
+}
+
+async fn connect() -> Result<MigrationManager, Error> {
+    let url = std::env::var("LIBSQL_ORM_DATABASE_URL")
+        .or_else(|_| std::env::var("TURSO_DATABASE_URL"))
+        .map_err(|_| {
+            Error::Connection("set LIBSQL_ORM_DATABASE_URL or TURSO_DATABASE_URL".to_string())
+        })?;
+
+    let db = if ["libsql://", "https://", "http://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        let token = std::env::var("TURSO_AUTH_TOKEN").unwrap_or_default();
+        Database::new_connect(&url, &token)
+            .await
+            .map_err(|e| Error::Connection(e.to_string()))?
+    } else {
+        Database::new_local(&url)
+            .await
+            .map_err(|e| Error::Connection(e.to_string()))?
+    };
+
+    let manager = MigrationManager::new(db);
+    manager.init().await?;
+    Ok(manager)
+}
+
+fn new_migration(dir: &str, name: &str) -> Result<(), Error> {
+    let name = MigrationManager::generate_migration_name(name);
+    std::fs::create_dir_all(dir)?;
+    for (suffix, contents) in [
+        ("up", "-- Write the migration here\n"),
+        ("down", "-- Reverse the migration here\n"),
+    ] {
+        let path = std::path::Path::new(dir).join(format!("{name}.{suffix}.sql"));
+        std::fs::write(&path, contents)?;
+        println!("created {}", path.display());
+    }
+    Ok(())
+}
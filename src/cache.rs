@@ -0,0 +1,375 @@
+//! Caching of query results
+//!
+//! [`CachedExecutor`] wraps any [`Executor`] and answers repeated reads from
+//! a [`CacheStore`] instead of the database. Entries are keyed by the
+//! whitespace-normalized SQL and its parameters, and tagged with the table
+//! of the model or builder that issued them. Any write through the same
+//! executor to a table drops that table's entries, so models read after a
+//! `create`, `update`, or `delete` are never stale.
+//!
+//! Only single-table reads are safe to cache this way: a query joining
+//! `posts` from a `users` builder is tagged `users` and survives writes to
+//! `posts`. Give such tables a short TTL, or call
+//! [`CachedExecutor::invalidate`] after writing to them. Raw SQL run without
+//! a table (such as [`Database::execute`](crate::Database::execute)) and
+//! writes from other processes don't invalidate anything either; TTLs bound
+//! how long those stay unnoticed.
+//!
+//! Reads inside a transaction bypass the cache, since they can see changes
+//! that are later rolled back.
+//!
+//! The in-memory [`MemoryCache`] is the default store. Implement
+//! [`CacheStore`] to keep entries elsewhere, such as Workers KV shared
+//! across isolates.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::cache::CachedExecutor;
+//! use libsql_orm::{Database, Model, Result};
+//! use std::time::Duration;
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct AuditLog { id: Option<i64>, message: String }
+//!
+//! # async fn example(db: Database) -> Result<()> {
+//! let db = CachedExecutor::new(db, 1_000)
+//!     .default_ttl(Duration::from_secs(30))
+//!     .ttl::<User>(Duration::from_secs(300))
+//!     .ttl::<AuditLog>(Duration::ZERO);
+//!
+//! let user = User::find_by_id(1, &db).await?; // from the database
+//! let user = User::find_by_id(1, &db).await?; // from the cache
+//! if let Some(mut user) = user {
+//!     user.name = "Alice".to_string();
+//!     user.update(&db).await?; // drops the cached users
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ChangeEvent, Executor, Model, Result, Row, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// How long entries live unless a model has its own TTL
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Where a [`CachedExecutor`] keeps query results
+///
+/// Stores must drop an entry once its TTL has passed, and drop every entry
+/// tagged with a table on [`invalidate`](Self::invalidate). A store shared
+/// between processes can implement the latter by keeping a generation
+/// number per table in the store and making it part of each key.
+#[allow(async_fn_in_trait)]
+pub trait CacheStore {
+    /// The rows cached under `key`, if present and not expired
+    async fn get(&self, key: &str) -> Option<Vec<Row>>;
+
+    /// Cache `rows` under `key` for `ttl`, tagged with `table`
+    async fn put(&self, key: String, table: &str, rows: Vec<Row>, ttl: Duration);
+
+    /// Drop every entry tagged with `table`
+    async fn invalidate(&self, table: &str);
+}
+
+/// In-memory least-recently-used [`CacheStore`]
+///
+/// Holds up to `capacity` results; inserting beyond that evicts the entry
+/// read or written longest ago.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, Entry>,
+    /// Entry keys by the tick they were last used at, oldest first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    table: String,
+    rows: Vec<Row>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    used_at: u64,
+}
+
+impl LruState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.used_at);
+        }
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.used_at);
+            entry.used_at = self.tick;
+            self.recency.insert(self.tick, key.to_string());
+        }
+    }
+}
+
+impl MemoryCache {
+    /// Create a cache holding up to `capacity` results
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Number of cached results, including expired ones not yet dropped
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every entry
+    pub fn clear(&self) {
+        *self.state() = LruState::default();
+    }
+
+    fn state(&self) -> MutexGuard<'_, LruState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl CacheStore for MemoryCache {
+    async fn get(&self, key: &str) -> Option<Vec<Row>> {
+        let mut state = self.state();
+        let expires_at = state.entries.get(key)?.expires_at;
+        if expires_at <= chrono::Utc::now() {
+            state.remove(key);
+            return None;
+        }
+        state.touch(key);
+        state.entries.get(key).map(|entry| entry.rows.clone())
+    }
+
+    async fn put(&self, key: String, table: &str, rows: Vec<Row>, ttl: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+        let mut state = self.state();
+        state.remove(&key);
+        state.entries.insert(
+            key.clone(),
+            Entry {
+                table: table.to_string(),
+                rows,
+                expires_at: chrono::Utc::now()
+                    .checked_add_signed(ttl)
+                    .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+                used_at: 0,
+            },
+        );
+        state.touch(&key);
+        while state.entries.len() > self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+    }
+
+    async fn invalidate(&self, table: &str) {
+        let mut state = self.state();
+        let stale: Vec<String> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.table == table)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            state.remove(&key);
+        }
+    }
+}
+
+/// Hit and miss counts of a [`CachedExecutor`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads answered from the cache
+    pub hits: u64,
+    /// Cacheable reads that went to the database
+    pub misses: u64,
+}
+
+/// An [`Executor`] that caches the results of reads
+///
+/// See the [module documentation](self) for what gets cached and when it
+/// is invalidated.
+pub struct CachedExecutor<E, S = MemoryCache> {
+    inner: E,
+    store: S,
+    default_ttl: Duration,
+    ttls: HashMap<&'static str, Duration>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<E: Executor> CachedExecutor<E> {
+    /// Cache up to `capacity` results in memory
+    pub fn new(inner: E, capacity: usize) -> Self {
+        Self::with_store(inner, MemoryCache::new(capacity))
+    }
+}
+
+impl<E: Executor, S: CacheStore> CachedExecutor<E, S> {
+    /// Cache results in the given store
+    pub fn with_store(inner: E, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            default_ttl: DEFAULT_TTL,
+            ttls: HashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// TTL for tables without their own; [`DEFAULT_TTL`] unless set
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// TTL for the table of `M`; `Duration::ZERO` turns caching off for it
+    pub fn ttl<M: Model>(mut self, ttl: Duration) -> Self {
+        self.ttls.insert(M::table_name(), ttl);
+        self
+    }
+
+    /// Drop the cached results of `M`'s table
+    pub async fn invalidate<M: Model>(&self) {
+        self.store.invalidate(M::table_name()).await;
+    }
+
+    /// Hits and misses so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The wrapped executor
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// The cache store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Unwrap the executor
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    fn ttl_for(&self, table: &str) -> Duration {
+        self.ttls.get(table).copied().unwrap_or(self.default_ttl)
+    }
+}
+
+/// Whether a statement only reads; writes with `RETURNING` also come in
+/// through `query_statement`
+fn is_read(sql: &str) -> bool {
+    let keyword: String = sql
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    keyword.eq_ignore_ascii_case("SELECT") || keyword.eq_ignore_ascii_case("WITH")
+}
+
+/// Cache key for a statement: its table and a hash of the normalized SQL and
+/// parameters
+pub(crate) fn cache_key(table: &str, sql: &str, params: &[Value]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(crate::testing::normalize_sql(sql));
+    hasher.update([0]);
+    hasher.update(serde_json::to_vec(params).unwrap_or_default());
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("{table}:{digest}")
+}
+
+impl<E: Executor, S: CacheStore> Executor for CachedExecutor<E, S> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        let affected = self.inner.execute_statement(table, sql, params).await?;
+        if let Some(table) = table {
+            self.store.invalidate(table).await;
+        }
+        Ok(affected)
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        let Some(table) = table else {
+            return self.inner.query_statement(table, sql, params).await;
+        };
+        if !is_read(sql) {
+            let rows = self.inner.query_statement(Some(table), sql, params).await?;
+            self.store.invalidate(table).await;
+            return Ok(rows);
+        }
+        let ttl = self.ttl_for(table);
+        if ttl.is_zero() || self.inner.in_transaction() {
+            return self.inner.query_statement(Some(table), sql, params).await;
+        }
+
+        let key = cache_key(table, sql, &params);
+        if let Some(rows) = self.store.get(&key).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(rows);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let rows = self.inner.query_statement(Some(table), sql, params).await?;
+        self.store.put(key, table, rows.clone(), ttl).await;
+        Ok(rows)
+    }
+
+    async fn execute_transaction_control(&self, sql: &str) -> Result<()> {
+        self.inner.execute_transaction_control(sql).await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        self.inner.emit_changes(events)
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.inner.in_transaction()
+    }
+}
@@ -0,0 +1,148 @@
+//! Splitting large batches to fit statement limits
+//!
+//! SQLite rejects statements binding more parameters than its compile-time
+//! `SQLITE_MAX_VARIABLE_NUMBER` with "too many SQL variables", and remote
+//! databases reached over Hrana cap the size of each request. The bulk
+//! model methods ([`Model::bulk_create`](crate::Model::bulk_create),
+//! [`Model::bulk_delete`](crate::Model::bulk_delete), and
+//! [`Model::find_by_ids`](crate::Model::find_by_ids)) split their work with
+//! [`ChunkLimits::split`] and combine the results, so callers can pass
+//! batches of any size. `IN` lists in filters take a different route: past
+//! [`MAX_INLINE_LIST_PARAMS`](crate::query::MAX_INLINE_LIST_PARAMS) values,
+//! or when a statement's lists together would pass [`MAX_PARAMETERS`], they
+//! are bound as one JSON array each.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use libsql_orm::chunking::ChunkLimits;
+//!
+//! let ids: Vec<i64> = (1..=2500).collect();
+//! let chunks = ChunkLimits::default().split(&ids, |_| (1, 8));
+//! assert_eq!(chunks.len(), 3);
+//! assert_eq!(chunks[0].len(), 999);
+//! ```
+
+use crate::query::quote_name;
+use crate::Value;
+
+/// Parameters per statement; SQLite's limit before 3.32, and still the
+/// lowest one in common builds
+pub const MAX_PARAMETERS: usize = 999;
+
+/// Approximate bytes of parameter data per statement, well under the
+/// request size limits of hosted databases
+pub const MAX_PAYLOAD_BYTES: usize = 1 << 20;
+
+/// How much one statement may carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLimits {
+    /// Most bound parameters in one statement
+    pub max_parameters: usize,
+    /// Most bytes of parameter data in one statement, as estimated by
+    /// [`value_size`]
+    pub max_payload_bytes: usize,
+}
+
+impl Default for ChunkLimits {
+    fn default() -> Self {
+        Self {
+            max_parameters: MAX_PARAMETERS,
+            max_payload_bytes: MAX_PAYLOAD_BYTES,
+        }
+    }
+}
+
+impl ChunkLimits {
+    /// Split `items` into consecutive chunks within the limits
+    ///
+    /// `cost` gives the parameters and payload bytes one item adds. Every
+    /// chunk holds at least one item, so an item over the limits on its own
+    /// still gets a chunk (and the statement may then fail as before).
+    pub fn split<'a, T>(
+        &self,
+        items: &'a [T],
+        cost: impl Fn(&T) -> (usize, usize),
+    ) -> Vec<&'a [T]> {
+        let mut chunks = Vec::new();
+        let (mut start, mut parameters, mut bytes) = (0, 0, 0);
+        for (i, item) in items.iter().enumerate() {
+            let (item_parameters, item_bytes) = cost(item);
+            let over = parameters + item_parameters > self.max_parameters
+                || bytes + item_bytes > self.max_payload_bytes;
+            if over && i > start {
+                chunks.push(&items[start..i]);
+                start = i;
+                parameters = 0;
+                bytes = 0;
+            }
+            parameters += item_parameters;
+            bytes += item_bytes;
+        }
+        if start < items.len() {
+            chunks.push(&items[start..]);
+        }
+        chunks
+    }
+}
+
+/// Approximate encoded size of a parameter, in bytes
+pub fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Null | Value::Boolean(_) => 1,
+        Value::Integer(_) | Value::Real(_) => 8,
+        Value::Text(text) => text.len(),
+        Value::Blob(blob) => blob.len(),
+    }
+}
+
+/// Multi-row `INSERT` statements for `rows` of column values, in order
+///
+/// Consecutive rows with the same columns share a statement, as far as the
+/// limits allow.
+pub(crate) fn insert_statements(
+    table: &str,
+    rows: &[Vec<(String, Value)>],
+    limits: ChunkLimits,
+) -> Vec<(String, Vec<Value>)> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    while start < rows.len() {
+        let columns: Vec<&str> = rows[start].iter().map(|(c, _)| c.as_str()).collect();
+        let quoted: Vec<String> = columns.iter().map(|c| quote_name(c)).collect();
+        let same = rows[start..]
+            .iter()
+            .take_while(|row| {
+                row.iter()
+                    .map(|(c, _)| c.as_str())
+                    .eq(columns.iter().copied())
+            })
+            .count();
+        let group = &rows[start..start + same];
+        start += same;
+
+        if columns.is_empty() {
+            let sql = format!("INSERT INTO {} DEFAULT VALUES", quote_name(table));
+            statements.extend(group.iter().map(|_| (sql.clone(), Vec::new())));
+            continue;
+        }
+        let tuple = format!("({})", vec!["?"; columns.len()].join(", "));
+        let chunks = limits.split(group, |row| {
+            (row.len(), row.iter().map(|(_, v)| value_size(v)).sum())
+        });
+        for chunk in chunks {
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                quote_name(table),
+                quoted.join(", "),
+                vec![tuple.as_str(); chunk.len()].join(", ")
+            );
+            let params = chunk
+                .iter()
+                .flat_map(|row| row.iter().map(|(_, v)| v.clone()))
+                .collect();
+            statements.push((sql, params));
+        }
+    }
+    statements
+}
@@ -121,3 +121,28 @@ pub fn blob_value(b: Vec<u8>) -> LibsqlValue {
     #[cfg(not(feature = "turso"))]
     return LibsqlValue::Blob(b);
 }
+
+impl From<LibsqlValue> for crate::Value {
+    fn from(value: LibsqlValue) -> Self {
+        match value {
+            LibsqlValue::Null => crate::Value::Null,
+            LibsqlValue::Integer(i) => crate::Value::Integer(i),
+            LibsqlValue::Real(f) => crate::Value::Real(f),
+            LibsqlValue::Text(s) => crate::Value::Text(s),
+            LibsqlValue::Blob(b) => crate::Value::Blob(b),
+        }
+    }
+}
+
+impl From<crate::Value> for LibsqlValue {
+    fn from(value: crate::Value) -> Self {
+        match value {
+            crate::Value::Null => null_value(),
+            crate::Value::Integer(i) => integer_value(i),
+            crate::Value::Real(f) => real_value(f),
+            crate::Value::Text(s) => text_value(s),
+            crate::Value::Blob(b) => blob_value(b),
+            crate::Value::Boolean(b) => integer_value(if b { 1 } else { 0 }),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Minimal RFC 4180 CSV support for exports and imports
+
+use crate::Value;
+use std::io::{BufRead, Write};
+
+/// Write one record, quoting fields that contain separators, quotes, or
+/// line breaks
+pub(crate) fn write_record<W, I, S>(writer: &mut W, fields: I) -> std::io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\r\n")
+}
+
+/// Render a value as a CSV field: `NULL` as an empty field, booleans as
+/// `1`/`0`, and blobs as lowercase hex
+pub(crate) fn field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Integer(i) => i.to_string(),
+        Value::Real(f) => f.to_string(),
+        Value::Text(text) => text.clone(),
+        Value::Blob(blob) => blob.iter().map(|byte| format!("{byte:02x}")).collect(),
+        Value::Boolean(b) => if *b { "1" } else { "0" }.to_string(),
+    }
+}
+
+/// Reads records one at a time, allowing quoted fields to span lines
+pub(crate) struct Records<R> {
+    reader: R,
+    line: u64,
+}
+
+impl<R: BufRead> Records<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self { reader, line: 0 }
+    }
+
+    /// The next non-blank record, with the line it starts on
+    pub(crate) fn next_record(&mut self) -> std::io::Result<Option<(u64, Vec<String>)>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            if !line.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        let start = self.line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                match (quoted, c) {
+                    (true, '"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    (true, '"') => quoted = false,
+                    (true, c) => field.push(c),
+                    (false, '"') => quoted = true,
+                    (false, ',') => fields.push(std::mem::take(&mut field)),
+                    (false, '\r' | '\n') => {}
+                    (false, c) => field.push(c),
+                }
+            }
+            if !quoted {
+                break;
+            }
+            // The quoted field continues on the next line
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            self.line += 1;
+        }
+        fields.push(field);
+        Ok(Some((start, fields)))
+    }
+}
@@ -8,10 +8,37 @@ enum DatabaseInner {
 pub struct Database {
     _db: DatabaseInner,
     pub inner: turso::Connection,
+    analyzer: std::sync::Mutex<Option<crate::QueryAnalyzer>>,
+    param_values_in_errors: std::sync::atomic::AtomicBool,
+    pub(crate) changes: crate::events::ChangeFeed,
+    /// Set when a [`Transaction`](crate::Transaction) is dropped unfinished
+    dropped_transaction: std::sync::atomic::AtomicBool,
+    /// Held by the axum [`transaction`](crate::axum::transaction) middleware
+    /// for the whole request
+    #[cfg(feature = "axum")]
+    pub(crate) request_lock: tokio::sync::Mutex<()>,
 }
 
 #[cfg(feature = "turso")]
 impl Database {
+    /// Whether a transaction is open on the connection
+    pub fn in_transaction(&self) -> bool {
+        !self.inner.is_autocommit().unwrap_or(true)
+    }
+
+    /// Roll back the transaction of a dropped [`Transaction`](crate::Transaction)
+    /// before running the next statement
+    async fn roll_back_dropped_transaction(&self) {
+        let dropped = self
+            .dropped_transaction
+            .swap(false, std::sync::atomic::Ordering::AcqRel);
+        if dropped && self.in_transaction() {
+            if let Err(e) = self.inner.execute("ROLLBACK", ()).await {
+                log::warn!("Failed to roll back a dropped transaction: {e}");
+            }
+        }
+    }
+
     fn keep_alive(&self) {
         match &self._db {
             DatabaseInner::Local(db) => {
@@ -29,6 +56,12 @@ impl Database {
         Ok(Self {
             _db: DatabaseInner::Local(db),
             inner: conn,
+            analyzer: std::sync::Mutex::new(None),
+            param_values_in_errors: std::sync::atomic::AtomicBool::new(false),
+            changes: Default::default(),
+            dropped_transaction: std::sync::atomic::AtomicBool::new(false),
+            #[cfg(feature = "axum")]
+            request_lock: Default::default(),
         })
     }
 
@@ -46,6 +79,12 @@ impl Database {
         Ok(Self {
             _db: DatabaseInner::Sync(db),
             inner: conn,
+            analyzer: std::sync::Mutex::new(None),
+            param_values_in_errors: std::sync::atomic::AtomicBool::new(false),
+            changes: Default::default(),
+            dropped_transaction: std::sync::atomic::AtomicBool::new(false),
+            #[cfg(feature = "axum")]
+            request_lock: Default::default(),
         })
     }
 
@@ -55,6 +94,8 @@ impl Database {
         params: Vec<crate::compat::LibsqlValue>,
     ) -> Result<crate::compat::LibsqlRows, crate::compat::LibsqlError> {
         self.keep_alive();
+        self.roll_back_dropped_transaction().await;
+        self.record_query(sql);
         if params.is_empty() {
             self.inner.query(sql, ()).await
         } else {
@@ -68,40 +109,459 @@ impl Database {
         params: Vec<crate::compat::LibsqlValue>,
     ) -> Result<u64, crate::compat::LibsqlError> {
         self.keep_alive();
+        self.roll_back_dropped_transaction().await;
+        self.record_query(sql);
         if params.is_empty() {
             self.inner.execute(sql, ()).await
         } else {
             self.inner.execute(sql, params).await
         }
     }
+
+    /// Execute one or more `;`-separated statements without parameters
+    ///
+    /// Used for migration scripts, which often contain several statements.
+    pub async fn execute_batch(&self, sql: &str) -> Result<(), crate::compat::LibsqlError> {
+        self.keep_alive();
+        self.roll_back_dropped_transaction().await;
+        self.record_query(sql);
+        self.inner.execute_batch(sql).await
+    }
+
+    async fn collect_rows(
+        &self,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+        options: Option<&crate::QueryOptions>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        self.keep_alive();
+        self.roll_back_dropped_transaction().await;
+        self.record_query(sql);
+        crate::driver::collect_rows(&self.inner, sql, params, options).await
+    }
 }
 
 #[cfg(not(feature = "turso"))]
 pub struct Database {
     _phantom: std::marker::PhantomData<()>,
+    analyzer: std::sync::Mutex<Option<crate::QueryAnalyzer>>,
+    param_values_in_errors: std::sync::atomic::AtomicBool,
+    changes: crate::events::ChangeFeed,
+    dropped_transaction: std::sync::atomic::AtomicBool,
 }
 
 #[cfg(not(feature = "turso"))]
 impl Database {
+    /// Whether a transaction is open on the connection
+    pub fn in_transaction(&self) -> bool {
+        false
+    }
+
     pub async fn new_connect(_url: &str, _token: &str) -> Result<Self, crate::error::Error> {
         Ok(Database {
             _phantom: std::marker::PhantomData,
+            analyzer: std::sync::Mutex::new(None),
+            param_values_in_errors: std::sync::atomic::AtomicBool::new(false),
+            changes: Default::default(),
+            dropped_transaction: std::sync::atomic::AtomicBool::new(false),
         })
     }
 
     pub async fn query(
         &self,
-        _sql: &str,
+        sql: &str,
         _params: Vec<crate::compat::LibsqlValue>,
     ) -> Result<crate::compat::LibsqlRows, crate::compat::LibsqlError> {
+        self.record_query(sql);
         Ok(crate::compat::LibsqlRows::new(vec![]))
     }
 
     pub async fn execute(
         &self,
-        _sql: &str,
+        sql: &str,
         _params: Vec<crate::compat::LibsqlValue>,
     ) -> Result<u64, crate::compat::LibsqlError> {
+        self.record_query(sql);
         Ok(0)
     }
+
+    /// Execute one or more `;`-separated statements without parameters
+    ///
+    /// Used for migration scripts, which often contain several statements.
+    pub async fn execute_batch(&self, sql: &str) -> Result<(), crate::compat::LibsqlError> {
+        self.record_query(sql);
+        Ok(())
+    }
+
+    async fn collect_rows(
+        &self,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+        options: Option<&crate::QueryOptions>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        let guard = options.map(crate::QueryOptions::row_guard);
+        let mut rows = self.query(sql, params).await?;
+        let mut results = Vec::new();
+        while let Some(row) = rows.next().await? {
+            if let Some(guard) = &guard {
+                guard.check()?;
+            }
+            let mut map = crate::Row::new();
+            for i in 0..row.column_count() {
+                if let Some(column_name) = row.column_name(i) {
+                    let value = row.get_value(i).ok().unwrap_or(crate::compat::null_value());
+                    map.insert(column_name.to_string(), value.into());
+                }
+            }
+            results.push(map);
+        }
+        Ok(results)
+    }
+}
+
+impl Database {
+    /// Start detecting repeated statements (N+1 queries) on this handle
+    ///
+    /// Replaces any analyzer that was already enabled. Statements are only
+    /// recorded in debug builds; in release builds this does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, QueryAnalyzer};
+    ///
+    /// # async fn example(db: &Database) {
+    /// db.enable_query_analyzer(QueryAnalyzer::new().threshold(3).window(20));
+    /// # }
+    /// ```
+    pub fn enable_query_analyzer(&self, analyzer: crate::QueryAnalyzer) {
+        *self.analyzer_guard() = Some(analyzer);
+    }
+
+    /// Stop detecting repeated statements and drop what was recorded
+    pub fn disable_query_analyzer(&self) {
+        *self.analyzer_guard() = None;
+    }
+
+    /// Forget recorded statements while keeping the analyzer enabled
+    pub fn reset_query_analyzer(&self) {
+        if let Some(analyzer) = self.analyzer_guard().as_mut() {
+            analyzer.reset();
+        }
+    }
+
+    /// Statements the analyzer has flagged as repeated, most frequent first
+    pub fn repeated_queries(&self) -> Vec<crate::RepeatedQuery> {
+        self.analyzer_guard()
+            .as_ref()
+            .map(|analyzer| analyzer.repeated_queries())
+            .unwrap_or_default()
+    }
+
+    fn analyzer_guard(&self) -> std::sync::MutexGuard<'_, Option<crate::QueryAnalyzer>> {
+        self.analyzer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Call `subscriber` after every successful model create, update, and delete
+    ///
+    /// See [`events`](crate::events) for which operations are reported and
+    /// when. Returns a handle for [`unsubscribe`](Self::unsubscribe).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{ChangeOperation, Database};
+    ///
+    /// # fn example(db: &Database) {
+    /// db.subscribe(|event| {
+    ///     if event.operation != ChangeOperation::Insert {
+    ///         log::info!("{} row {:?} changed", event.table, event.primary_key);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn subscribe<F>(&self, subscriber: F) -> crate::SubscriptionId
+    where
+        F: Fn(&crate::ChangeEvent) + Send + Sync + 'static,
+    {
+        self.changes.subscribe(std::sync::Arc::new(subscriber))
+    }
+
+    /// Stop calling a subscriber; returns whether it was still registered
+    pub fn unsubscribe(&self, id: crate::SubscriptionId) -> bool {
+        self.changes.unsubscribe(id)
+    }
+
+    /// Roll back the open transaction before the next statement
+    pub(crate) fn mark_dropped_transaction(&self) {
+        self.dropped_transaction
+            .store(true, std::sync::atomic::Ordering::Release);
+    }
+
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.changes.has_subscribers()
+    }
+
+    /// Record bound parameter values, not just their types, in errors
+    ///
+    /// Off by default so that failed statements can be logged without
+    /// leaking user data; see [`Error::context`](crate::Error::context).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::Database;
+    ///
+    /// # async fn example(db: &Database) {
+    /// db.include_param_values_in_errors(cfg!(debug_assertions));
+    /// # }
+    /// ```
+    pub fn include_param_values_in_errors(&self, include: bool) {
+        self.param_values_in_errors
+            .store(include, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub(crate) fn summarize_params(&self, params: &[crate::compat::LibsqlValue]) -> Vec<String> {
+        crate::error::summarize_params(
+            params,
+            self.param_values_in_errors
+                .load(std::sync::atomic::Ordering::Relaxed),
+        )
+    }
+
+    /// [`execute`](Self::execute), reporting failures with the statement and table
+    pub(crate) async fn execute_for(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+    ) -> crate::Result<u64> {
+        let summary = self.summarize_params(&params);
+        self.execute(sql, params)
+            .await
+            .map_err(|e| crate::Error::statement(e, table, sql, summary))
+    }
+
+    /// Run a query and collect every row as a column-name keyed map
+    pub(crate) async fn query_rows(
+        &self,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        self.query_rows_for(None, sql, params).await
+    }
+
+    /// [`query_rows`](Self::query_rows) for a statement issued against `table`
+    pub(crate) async fn query_rows_for(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        self.query_rows_with(table, sql, params, None).await
+    }
+
+    /// [`query_rows_for`](Self::query_rows_for), checking `options` between rows
+    async fn query_rows_with(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+        options: Option<&crate::QueryOptions>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        let summary = self.summarize_params(&params);
+        self.collect_rows(sql, params, options)
+            .await
+            .map_err(|e| crate::Error::statement(e, table, sql, summary))
+    }
+
+    /// [`query`](Self::query) with a timeout or cancellation token, collecting
+    /// every row
+    ///
+    /// See [`options`](crate::options) for when the statement is stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, QueryOptions, Result};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let options = QueryOptions::new().timeout(Duration::from_secs(1));
+    /// let rows = db
+    ///     .query_with_options("SELECT * FROM events ORDER BY at DESC", vec![], &options)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_with_options(
+        &self,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+        options: &crate::QueryOptions,
+    ) -> crate::Result<Vec<crate::Row>> {
+        options
+            .run(self.query_rows_with(None, sql, params, Some(options)))
+            .await
+    }
+
+    /// [`execute`](Self::execute) with a timeout or cancellation token
+    pub async fn execute_with_options(
+        &self,
+        sql: &str,
+        params: Vec<crate::compat::LibsqlValue>,
+        options: &crate::QueryOptions,
+    ) -> crate::Result<u64> {
+        options.run(self.execute_for(None, sql, params)).await
+    }
+
+    /// Feed a statement to the analyzer, warning when it becomes repetitive
+    pub(crate) fn record_query(&self, sql: &str) {
+        #[cfg(debug_assertions)]
+        {
+            let flagged = self
+                .analyzer_guard()
+                .as_mut()
+                .and_then(|analyzer| analyzer.record(sql));
+            if let Some(repeated) = flagged {
+                crate::analyzer::warn_repeated(&repeated);
+            }
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            let _ = sql;
+        }
+    }
+
+    /// Run hand-written SQL and map each row through [`FromRow`](crate::FromRow)
+    ///
+    /// Parameters are bound positionally to `?` placeholders. Column names
+    /// (or aliases) in the result must match the target's fields.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, FromRow, Result, Value};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(FromRow, Deserialize)]
+    /// struct DomainCount {
+    ///     domain: String,
+    ///     n: i64,
+    /// }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let counts: Vec<DomainCount> = db
+    ///     .query_as(
+    ///         "SELECT substr(email, instr(email, '@') + 1) AS domain, count(*) AS n \
+    ///          FROM users WHERE is_active = ? GROUP BY domain",
+    ///         vec![Value::Boolean(true)],
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_as<T>(&self, sql: &str, params: Vec<crate::Value>) -> crate::Result<Vec<T>>
+    where
+        T: crate::FromRow,
+    {
+        let params = params.into_iter().map(Into::into).collect();
+        self.query_rows(sql, params)
+            .await?
+            .into_iter()
+            .map(T::from_row)
+            .collect()
+    }
+
+    /// Export the schema as `CREATE` statements
+    ///
+    /// Tables come first, followed by indexes, views, and triggers, each
+    /// group sorted by name, so the output is stable enough to commit as a
+    /// canonical `schema.sql`. Internal `sqlite_*` objects are left out. The
+    /// result can be replayed with [`execute_batch`](Self::execute_batch).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Result};
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// std::fs::write("schema.sql", db.dump_schema().await?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn dump_schema(&self) -> crate::Result<String> {
+        self.dump_schema_excluding(&[]).await
+    }
+
+    /// [`dump_schema`](Self::dump_schema) without the given tables and their indexes
+    pub(crate) async fn dump_schema_excluding(&self, excluded: &[&str]) -> crate::Result<String> {
+        let rows = self
+            .query_rows(
+                "SELECT type, tbl_name, sql FROM sqlite_master \
+                 WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' \
+                 ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 WHEN 'view' THEN 2 ELSE 3 END, name",
+                vec![],
+            )
+            .await?;
+
+        let mut schema = String::new();
+        for row in rows {
+            if let Some(crate::Value::Text(table)) = row.get("tbl_name") {
+                if excluded.contains(&table.as_str()) {
+                    continue;
+                }
+            }
+            if let Some(crate::Value::Text(sql)) = row.get("sql") {
+                schema.push_str(sql.trim());
+                schema.push_str(";\n");
+            }
+        }
+        Ok(schema)
+    }
+}
+
+impl crate::Executor for Database {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::Value>,
+    ) -> crate::Result<u64> {
+        let params = params.into_iter().map(Into::into).collect();
+        self.execute_for(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::Value>,
+    ) -> crate::Result<Vec<crate::Row>> {
+        let params = params.into_iter().map(Into::into).collect();
+        self.query_rows_for(table, sql, params).await
+    }
+
+    async fn query_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<crate::Value>,
+        options: &crate::QueryOptions,
+    ) -> crate::Result<Vec<crate::Row>> {
+        let params = params.into_iter().map(Into::into).collect();
+        options
+            .run(self.query_rows_with(table, sql, params, Some(options)))
+            .await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<crate::ChangeEvent>) {
+        self.changes.emit(events);
+    }
+
+    fn in_transaction(&self) -> bool {
+        Database::in_transaction(self)
+    }
 }
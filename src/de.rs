@@ -0,0 +1,337 @@
+//! Deserializing rows straight into Rust types
+//!
+//! [`from_row`] drives a type's `Deserialize` implementation directly from
+//! the [`Value`]s of a [`Row`], without building `serde_json` values in
+//! between. This is what the derived [`Model::from_map`](crate::Model::from_map)
+//! and [`FromRow::from_row`](crate::FromRow::from_row) call, so every serde
+//! attribute (`rename`, `default`, `deserialize_with`, ...) keeps working.
+//!
+//! Conversions follow the target type rather than the column:
+//!
+//! - integers become `bool` when a `bool` is expected (`0` is `false`)
+//! - text holding JSON fills sequences, maps, and nested structs, matching
+//!   how [`Model::to_map`](crate::Model::to_map) stores them
+//! - `NULL` becomes `None`, and text becomes unit enum variants by name
+//!
+//! Aggregated children read the same way: a `json_group_array` column fills
+//! a `Vec`, and a `group_concat` column fills a [`Delimited`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use libsql_orm::{de, Row, Value};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Flag {
+//!     name: String,
+//!     enabled: bool,
+//!     tags: Vec<String>,
+//! }
+//!
+//! let mut row = Row::new();
+//! row.insert("name".to_string(), Value::Text("beta".to_string()));
+//! row.insert("enabled".to_string(), Value::Integer(1));
+//! row.insert("tags".to_string(), Value::Text(r#"["web","api"]"#.to_string()));
+//!
+//! let flag: Flag = de::from_row(row).unwrap();
+//! assert!(flag.enabled);
+//! assert_eq!(flag.tags, ["web", "api"]);
+//! ```
+
+use crate::{Error, Row, Value};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// Deserialize a row into `T`, field by column name
+pub fn from_row<T: DeserializeOwned>(row: Row) -> crate::Result<T> {
+    T::deserialize(RowDeserializer(row)).map_err(|e| Error::Serialization(e.0))
+}
+
+/// Deserialize a single column value into `T`
+pub fn from_value<T: DeserializeOwned>(value: Value) -> crate::Result<T> {
+    T::deserialize(ValueDeserializer(value)).map_err(|e| Error::Serialization(e.0))
+}
+
+/// Values read from delimited text, such as a `group_concat` column
+///
+/// `SEP` is the separator, a comma unless given; each part is parsed with
+/// `FromStr`. NULL, which `group_concat` returns for a group without
+/// values, reads as empty.
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{de, de::Delimited, Row, Value};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct PostWithTags {
+///     title: String,
+///     tag: Delimited<String, '|'>,
+/// }
+///
+/// let mut row = Row::new();
+/// row.insert("title".to_string(), Value::Text("Hello".to_string()));
+/// row.insert("tag".to_string(), Value::Text("rust|orm".to_string()));
+///
+/// let post: PostWithTags = de::from_row(row).unwrap();
+/// assert_eq!(*post.tag, ["rust", "orm"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Delimited<T, const SEP: char = ','>(pub Vec<T>);
+
+impl<T, const SEP: char> Default for Delimited<T, SEP> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T, const SEP: char> std::ops::Deref for Delimited<T, SEP> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T, const SEP: char> From<Delimited<T, SEP>> for Vec<T> {
+    fn from(delimited: Delimited<T, SEP>) -> Self {
+        delimited.0
+    }
+}
+
+impl<T: fmt::Display, const SEP: char> Serialize for Delimited<T, SEP> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let parts: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&parts.join(&SEP.to_string()))
+    }
+}
+
+impl<'de, T, const SEP: char> Deserialize<'de> for Delimited<T, SEP>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DelimitedVisitor(PhantomData))
+    }
+}
+
+struct DelimitedVisitor<T, const SEP: char>(PhantomData<T>);
+
+impl<'de, T, const SEP: char> Visitor<'de> for DelimitedVisitor<T, SEP>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = Delimited<T, SEP>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "text delimited by {SEP:?}")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+        text.split(SEP)
+            .map(|part| part.parse().map_err(E::custom))
+            .collect::<Result<_, _>>()
+            .map(Delimited)
+    }
+
+    // A group with a single numeric value comes back as that number
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Delimited::default())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Delimited::default())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+#[derive(Debug)]
+struct DeError(String);
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+struct RowDeserializer(Row);
+
+impl<'de> de::Deserializer<'de> for RowDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let mut map = MapDeserializer::new(self.0.into_iter());
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple tuple_struct map
+        struct enum identifier ignored_any
+    }
+}
+
+struct ValueDeserializer(Value);
+
+impl<'de> IntoDeserializer<'de, DeError> for Value {
+    type Deserializer = ValueDeserializer;
+
+    fn into_deserializer(self) -> ValueDeserializer {
+        ValueDeserializer(self)
+    }
+}
+
+/// Forward a compound type to the JSON stored in a text column
+///
+/// The JSON is read through `from_reader` because targets are owned, so
+/// nothing may borrow from the row.
+macro_rules! from_json_text {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, DeError> {
+                match self.0 {
+                    Value::Text(text) => {
+                        let mut json = serde_json::Deserializer::from_reader(text.as_bytes());
+                        let value = de::Deserializer::$method(&mut json, $($arg,)* visitor)
+                            .map_err(de::Error::custom)?;
+                        json.end().map_err(de::Error::custom)?;
+                        Ok(value)
+                    }
+                    other => ValueDeserializer(other).deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Boolean(b) => visitor.visit_bool(b),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::Real(f) => visitor.visit_f64(f),
+            Value::Text(text) => visitor.visit_string(text),
+            Value::Blob(blob) => {
+                let mut seq = SeqDeserializer::new(blob.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Value::Integer(i) => visitor.visit_bool(i != 0),
+            other => ValueDeserializer(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(ValueDeserializer(other)),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Value::Blob(blob) => visitor.visit_byte_buf(blob),
+            other => ValueDeserializer(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        match self.0 {
+            Value::Text(text) if text.trim_start().starts_with('{') => {
+                de::Deserializer::deserialize_enum(
+                    &mut serde_json::Deserializer::from_reader(text.as_bytes()),
+                    name,
+                    variants,
+                    visitor,
+                )
+                .map_err(de::Error::custom)
+            }
+            Value::Text(text) => visitor.visit_enum(text.into_deserializer()),
+            other => ValueDeserializer(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    from_json_text! {
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct identifier
+    }
+}
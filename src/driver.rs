@@ -0,0 +1,213 @@
+//! The connection interface beneath the ORM
+//!
+//! A [`Driver`] runs SQL with positional parameters and returns rows keyed
+//! by column name. The turso [`Connection`](turso::Connection) behind
+//! [`Database`](crate::Database) implements it; wrapping any other driver
+//! in a [`DriverExecutor`] makes it usable with models and builders, so
+//! offline tooling can run on rusqlite, or a service can talk to its own
+//! HTTP endpoint, without forking the compat layer.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::driver::{Driver, DriverExecutor};
+//! use libsql_orm::{Model, Result, Row, Value};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//!
+//! struct HttpDriver {
+//!     endpoint: String,
+//! }
+//!
+//! impl Driver for HttpDriver {
+//!     async fn query(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Row>> {
+//!         // POST { sql, params } to self.endpoint and decode the rows
+//! #       unimplemented!()
+//!     }
+//!
+//!     async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+//! #       unimplemented!()
+//!     }
+//!
+//!     async fn batch(&self, sql: &str) -> Result<()> {
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! let db = DriverExecutor::new(HttpDriver { endpoint: "https://db.internal/sql".to_string() });
+//! let users = User::find_all(&db).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Executor, Result, Row, Value};
+
+/// A connection that can run SQL
+///
+/// Parameters are bound positionally to `?` placeholders. Booleans arrive as
+/// [`Value::Boolean`]; drivers for SQLite-like stores usually bind them as
+/// `0`/`1`.
+#[allow(async_fn_in_trait)]
+pub trait Driver {
+    /// Run a query, returning each row keyed by column name
+    async fn query(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Row>>;
+
+    /// Run a statement, returning the number of affected rows
+    async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<u64>;
+
+    /// Run one or more `;`-separated statements without parameters
+    async fn batch(&self, sql: &str) -> Result<()>;
+
+    /// Start a transaction
+    async fn begin(&self) -> Result<()> {
+        self.execute("BEGIN", vec![]).await.map(drop)
+    }
+
+    /// Commit the open transaction
+    async fn commit(&self) -> Result<()> {
+        self.execute("COMMIT", vec![]).await.map(drop)
+    }
+
+    /// Roll back the open transaction
+    async fn rollback(&self) -> Result<()> {
+        self.execute("ROLLBACK", vec![]).await.map(drop)
+    }
+
+    /// Whether a transaction is open; the default is `false`
+    fn in_transaction(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "turso")]
+impl Driver for turso::Connection {
+    async fn query(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Row>> {
+        collect_rows(
+            self,
+            sql,
+            params.into_iter().map(Into::into).collect(),
+            None,
+        )
+        .await
+    }
+
+    async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<u64> {
+        let params: Vec<turso::Value> = params.into_iter().map(Into::into).collect();
+        let affected = if params.is_empty() {
+            turso::Connection::execute(self, sql, ()).await?
+        } else {
+            turso::Connection::execute(self, sql, params).await?
+        };
+        Ok(affected)
+    }
+
+    async fn batch(&self, sql: &str) -> Result<()> {
+        Ok(self.execute_batch(sql).await?)
+    }
+
+    fn in_transaction(&self) -> bool {
+        !self.is_autocommit().unwrap_or(true)
+    }
+}
+
+/// Run a query on a turso connection, keying each row by column name
+#[cfg(feature = "turso")]
+pub(crate) async fn collect_rows(
+    conn: &turso::Connection,
+    sql: &str,
+    params: Vec<crate::compat::LibsqlValue>,
+    options: Option<&crate::QueryOptions>,
+) -> Result<Vec<Row>> {
+    let guard = options.map(crate::QueryOptions::row_guard);
+    let mut stmt = conn.prepare(sql).await?;
+    let columns: Vec<String> = stmt
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+
+    let mut rows = if params.is_empty() {
+        stmt.query(()).await?
+    } else {
+        stmt.query(params).await?
+    };
+
+    let mut results = Vec::new();
+    while let Some(row) = rows.next().await? {
+        if let Some(guard) = &guard {
+            guard.check()?;
+        }
+        let mut map = Row::new();
+        for (i, column_name) in columns.iter().enumerate() {
+            let value = row.get_value(i).ok().unwrap_or(crate::compat::null_value());
+            map.insert(column_name.clone(), value.into());
+        }
+        results.push(map);
+    }
+    Ok(results)
+}
+
+/// Runs models and builders on any [`Driver`]
+///
+/// Failed statements are reported as [`Error::Statement`](crate::Error::Statement)
+/// with the SQL and parameter types, as on a [`Database`](crate::Database).
+/// Change subscriptions are a `Database` feature; a `DriverExecutor` drops
+/// change events.
+pub struct DriverExecutor<D> {
+    driver: D,
+}
+
+impl<D: Driver> DriverExecutor<D> {
+    /// Wrap a driver
+    pub fn new(driver: D) -> Self {
+        Self { driver }
+    }
+
+    /// The wrapped driver
+    pub fn driver(&self) -> &D {
+        &self.driver
+    }
+
+    /// Unwrap the driver
+    pub fn into_inner(self) -> D {
+        self.driver
+    }
+}
+
+impl<D: Driver> Executor for DriverExecutor<D> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        let summary = summarize(&params);
+        self.driver
+            .execute(sql, params)
+            .await
+            .map_err(|e| crate::Error::statement(e, table, sql, summary))
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        let summary = summarize(&params);
+        self.driver
+            .query(sql, params)
+            .await
+            .map_err(|e| crate::Error::statement(e, table, sql, summary))
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.driver.in_transaction()
+    }
+}
+
+fn summarize(params: &[Value]) -> Vec<String> {
+    let params: Vec<crate::compat::LibsqlValue> = params.iter().cloned().map(Into::into).collect();
+    crate::error::summarize_params(&params, false)
+}
@@ -9,10 +9,13 @@
 //! - **Connection Errors**: Database connection failures
 //! - **SQL Errors**: Query execution problems  
 //! - **Serialization Errors**: Data conversion issues
-//! - **Validation Errors**: Data validation failures
+//! - **Validation Errors**: Data validation failures, per field for `#[orm_validate]` rules
 //! - **Not Found Errors**: Resource not found
 //! - **Pagination Errors**: Pagination parameter issues
 //! - **Query Errors**: Query building problems
+//! - **Busy Errors**: The database is locked by another connection
+//! - **Statement Errors**: A failed query, with its SQL, parameter summary, and table
+//! - **Timeout and Cancellation Errors**: A statement stopped by its [`QueryOptions`](crate::QueryOptions)
 //!
 //! # Examples
 //!
@@ -45,6 +48,8 @@ pub enum Error {
     Serialization(String),
     /// Validation error
     Validation(String),
+    /// `#[orm_validate]` rules failed, keyed by field
+    InvalidFields(crate::validation::ValidationErrors),
     /// Not found error
     NotFound(String),
     /// Pagination error
@@ -57,9 +62,274 @@ pub enum Error {
     DatabaseError(String),
     /// Generic error
     Generic(String),
+    /// The database is locked by another connection (`SQLITE_BUSY`)
+    Busy(String),
+    /// A statement ran longer than its [`QueryOptions::timeout`](crate::QueryOptions::timeout)
+    Timeout(String),
+    /// A statement was stopped through its [`CancellationToken`](crate::CancellationToken)
+    Cancelled(String),
+    /// A statement failed; wraps the backend error with what was being run
+    Statement {
+        source: Box<Error>,
+        context: Box<StatementContext>,
+    },
 }
 
-impl std::error::Error for Error {}
+/// The statement behind an [`Error::Statement`]
+///
+/// Parameters are summarized by type and length (`text(5)`, `integer`) so
+/// errors can be logged without leaking data. Call
+/// [`Database::include_param_values_in_errors`](crate::Database::include_param_values_in_errors)
+/// to record the values themselves while debugging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementContext {
+    /// SQL text of the failed statement
+    pub sql: String,
+    /// One entry per bound parameter
+    pub params: Vec<String>,
+    /// Table of the model or builder that issued the statement, if known
+    pub table: Option<String>,
+}
+
+impl Error {
+    /// Wrap `err` with the statement that produced it
+    ///
+    /// An error that already carries a context keeps it, only gaining the
+    /// table if it had none.
+    pub(crate) fn statement(
+        err: impl Into<Error>,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<String>,
+    ) -> Self {
+        match err.into() {
+            Error::Statement {
+                source,
+                mut context,
+            } => {
+                if context.table.is_none() {
+                    context.table = table.map(str::to_string);
+                }
+                Error::Statement { source, context }
+            }
+            source => Error::Statement {
+                source: Box::new(source),
+                context: Box::new(StatementContext {
+                    sql: sql.to_string(),
+                    params,
+                    table: table.map(str::to_string),
+                }),
+            },
+        }
+    }
+
+    /// Whether running the operation again may succeed
+    ///
+    /// True for [`is_busy`](Self::is_busy) and
+    /// [`is_transient_network`](Self::is_transient_network) errors; constraint
+    /// violations, bad SQL, and other logic errors are never retryable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Model, Result};
+    ///
+    /// # async fn example<M: Model>(model: &M, db: &Database) -> Result<M> {
+    /// let mut attempts = 0;
+    /// loop {
+    ///     match model.create(db).await {
+    ///         Err(e) if e.is_retryable() && attempts < 3 => attempts += 1,
+    ///         result => return result,
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        self.is_busy() || self.is_transient_network()
+    }
+
+    /// Whether the database was locked by another connection
+    pub fn is_busy(&self) -> bool {
+        match self {
+            Error::Busy(_) => true,
+            Error::Statement { source, .. } => source.is_busy(),
+            other => other
+                .message()
+                .is_some_and(|msg| matches_any(msg, BUSY_MESSAGES)),
+        }
+    }
+
+    /// Whether a statement ran past its [`QueryOptions::timeout`](crate::QueryOptions::timeout)
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout(_) => true,
+            Error::Statement { source, .. } => source.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Whether a statement was stopped by its cancellation token
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            Error::Cancelled(_) => true,
+            Error::Statement { source, .. } => source.is_cancelled(),
+            _ => false,
+        }
+    }
+
+    /// Whether a remote database could not be reached for a temporary reason
+    ///
+    /// Covers timeouts, dropped connections, and HTTP 502, 503, and 504
+    /// responses from the server. Status codes count only when they follow
+    /// `status`, `HTTP`, or `code`, so a `503` in a value or table name does
+    /// not.
+    pub fn is_transient_network(&self) -> bool {
+        match self {
+            Error::Busy(_)
+            | Error::Timeout(_)
+            | Error::Cancelled(_)
+            | Error::Validation(_)
+            | Error::InvalidFields(_)
+            | Error::NotFound(_) => false,
+            Error::Statement { source, .. } => source.is_transient_network(),
+            other => other.message().is_some_and(is_transient_network_message),
+        }
+    }
+
+    /// The HTTP status an API should answer with for this error
+    ///
+    /// `404` for [`NotFound`](Error::NotFound), `422` for validation failures,
+    /// `409` for constraint violations, `400` for invalid queries or
+    /// pagination, `503` when the database is busy or unreachable, `504` for
+    /// [`Timeout`](Error::Timeout), and `500` otherwise.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Error::NotFound(_) => 404,
+            Error::Validation(_) | Error::InvalidFields(_) => 422,
+            Error::Pagination(_) | Error::Query(_) => 400,
+            Error::Timeout(_) => 504,
+            Error::Statement { source, .. } => source.status_code(),
+            _ if self.is_retryable() => 503,
+            Error::Connection(_) => 503,
+            other => match other.message() {
+                Some(msg) if matches_any(msg, &["constraint failed"]) => 409,
+                _ => 500,
+            },
+        }
+    }
+
+    fn message(&self) -> Option<&str> {
+        match self {
+            Error::Connection(msg)
+            | Error::Sql(msg)
+            | Error::Serialization(msg)
+            | Error::Validation(msg)
+            | Error::NotFound(msg)
+            | Error::Pagination(msg)
+            | Error::Query(msg)
+            | Error::AnyhowError(msg)
+            | Error::DatabaseError(msg)
+            | Error::Generic(msg)
+            | Error::Busy(msg)
+            | Error::Timeout(msg)
+            | Error::Cancelled(msg) => Some(msg),
+            Error::InvalidFields(_) | Error::Statement { .. } => None,
+        }
+    }
+
+    /// The statement that failed, for errors raised while running one
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Error};
+    ///
+    /// # async fn example(db: &Database, result: Result<(), Error>) {
+    /// if let Err(e) = result {
+    ///     if let Some(context) = e.context() {
+    ///         eprintln!("{} failed: {} {:?}", context.sql, e, context.params);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn context(&self) -> Option<&StatementContext> {
+        match self {
+            Error::Statement { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+}
+
+/// Backend messages for a locked database, for errors that were stringified
+const BUSY_MESSAGES: &[&str] = &["database is locked", "database is busy", "sqlite_busy"];
+
+const TRANSIENT_NETWORK_MESSAGES: &[&str] = &[
+    "bad gateway",
+    "service unavailable",
+    "gateway timeout",
+    "connection timed out",
+    "operation timed out",
+    "request timed out",
+    "connection reset",
+    "connection refused",
+    "connection aborted",
+    "connection closed",
+    "broken pipe",
+    "not connected",
+];
+
+/// Whether a stringified error reports a temporary network failure
+fn is_transient_network_message(msg: &str) -> bool {
+    // `std::io::ErrorKind::TimedOut` displays as just "timed out"
+    if msg.trim().eq_ignore_ascii_case("timed out") || matches_any(msg, TRANSIENT_NETWORK_MESSAGES)
+    {
+        return true;
+    }
+    let msg = msg.to_lowercase();
+    let words: Vec<&str> = msg
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.windows(2).any(|pair| {
+        matches!(pair[0], "status" | "http" | "code") && matches!(pair[1], "502" | "503" | "504")
+    })
+}
+
+fn matches_any(msg: &str, patterns: &[&str]) -> bool {
+    let msg = msg.to_lowercase();
+    patterns.iter().any(|pattern| msg.contains(pattern))
+}
+
+/// Describe bound parameters for [`StatementContext::params`]
+pub(crate) fn summarize_params(
+    params: &[crate::compat::LibsqlValue],
+    include_values: bool,
+) -> Vec<String> {
+    use crate::compat::LibsqlValue;
+
+    params
+        .iter()
+        .map(|param| match (param, include_values) {
+            (LibsqlValue::Null, _) => "null".to_string(),
+            (LibsqlValue::Integer(i), true) => i.to_string(),
+            (LibsqlValue::Integer(_), false) => "integer".to_string(),
+            (LibsqlValue::Real(f), true) => f.to_string(),
+            (LibsqlValue::Real(_), false) => "real".to_string(),
+            (LibsqlValue::Text(text), true) => format!("{text:?}"),
+            (LibsqlValue::Text(text), false) => format!("text({})", text.len()),
+            (LibsqlValue::Blob(blob), _) => format!("blob({})", blob.len()),
+        })
+        .collect()
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Statement { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -68,12 +338,27 @@ impl fmt::Display for Error {
             Error::Sql(msg) => write!(f, "SQL error: {msg}"),
             Error::Serialization(msg) => write!(f, "Serialization error: {msg}"),
             Error::Validation(msg) => write!(f, "Validation error: {msg}"),
+            Error::InvalidFields(errors) => write!(f, "Validation error: {errors}"),
             Error::NotFound(msg) => write!(f, "Not found: {msg}"),
             Error::Pagination(msg) => write!(f, "Pagination error: {msg}"),
             Error::Query(msg) => write!(f, "Query error: {msg}"),
             Error::AnyhowError(msg) => write!(f, "Anyhow error: {msg}"),
             Error::DatabaseError(msg) => write!(f, "Database error: {msg}"),
             Error::Generic(msg) => write!(f, "Error: {msg}"),
+            Error::Busy(msg) => write!(f, "Database busy: {msg}"),
+            Error::Timeout(msg) => write!(f, "Timeout: {msg}"),
+            Error::Cancelled(msg) => write!(f, "Cancelled: {msg}"),
+            Error::Statement { source, context } => {
+                write!(f, "{source}")?;
+                if let Some(table) = &context.table {
+                    write!(f, " [table: {table}]")?;
+                }
+                write!(f, " [sql: {}]", context.sql)?;
+                if !context.params.is_empty() {
+                    write!(f, " [params: {}]", context.params.join(", "))?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -81,7 +366,26 @@ impl fmt::Display for Error {
 #[cfg(feature = "turso")]
 impl From<turso::Error> for Error {
     fn from(err: turso::Error) -> Self {
-        Error::Sql(err.to_string())
+        use std::io::ErrorKind;
+
+        match err {
+            turso::Error::Busy(msg) | turso::Error::BusySnapshot(msg) => Error::Busy(msg),
+            turso::Error::IoError(
+                kind @ (ErrorKind::TimedOut
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe
+                | ErrorKind::NotConnected),
+            ) => Error::Connection(std::io::Error::from(kind).to_string()),
+            err => Error::Sql(err.to_string()),
+        }
+    }
+}
+
+impl From<crate::validation::ValidationErrors> for Error {
+    fn from(errors: crate::validation::ValidationErrors) -> Self {
+        Error::InvalidFields(errors)
     }
 }
 
@@ -0,0 +1,146 @@
+//! Change events for model mutations
+//!
+//! Subscribers registered with [`Database::subscribe`](crate::Database::subscribe)
+//! are called after every successful [`Model`](crate::Model) create, update,
+//! and delete on that handle, so caches, webhooks, and search indexes can
+//! react without wrapping each call site. Statements run through the query
+//! builders or raw SQL are not reported.
+//!
+//! Subscribers run synchronously on the task that made the change, after its
+//! statement (or, for bulk operations, its transaction) succeeded. Keep them
+//! cheap, e.g. push the event onto a channel drained elsewhere.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{ChangeOperation, Database};
+//! use std::sync::mpsc;
+//!
+//! # fn example(db: &Database) {
+//! let (tx, rx) = mpsc::channel();
+//! let subscription = db.subscribe(move |event| {
+//!     if event.table == "users" && event.operation == ChangeOperation::Delete {
+//!         let _ = tx.send(event.primary_key);
+//!     }
+//! });
+//!
+//! // ... later, invalidate cached users as deletions arrive ...
+//! for id in rx.try_iter() {
+//!     println!("user {id:?} deleted");
+//! }
+//! db.unsubscribe(subscription);
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The kind of change a [`ChangeEvent`] reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A committed change to one row, or to a filtered set of rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    /// Table of the model that changed
+    pub table: String,
+    /// What happened to the row
+    pub operation: ChangeOperation,
+    /// Primary key of the row, when known; `None` for inserts that let the
+    /// database assign the key and for `delete_where`
+    pub primary_key: Option<i64>,
+    /// The row before the change, for deletes of a loaded model
+    pub old: Option<crate::Row>,
+    /// The row after the change, for inserts and updates
+    pub new: Option<crate::Row>,
+}
+
+/// Handle returned by [`Database::subscribe`](crate::Database::subscribe)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Subscriber = Arc<dyn Fn(&ChangeEvent) + Send + Sync>;
+
+/// The subscribers of one `Database` handle
+#[derive(Default)]
+pub(crate) struct ChangeFeed {
+    subscribers: Mutex<(u64, Vec<(SubscriptionId, Subscriber)>)>,
+    /// Events held back while [`hold`](Self::hold) is in effect
+    held: Mutex<Option<Vec<ChangeEvent>>>,
+}
+
+impl ChangeFeed {
+    pub(crate) fn subscribe(&self, subscriber: Subscriber) -> SubscriptionId {
+        let mut guard = self.guard();
+        let (next_id, subscribers) = &mut *guard;
+        let id = SubscriptionId(*next_id);
+        *next_id += 1;
+        subscribers.push((id, subscriber));
+        id
+    }
+
+    pub(crate) fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut guard = self.guard();
+        let subscribers = &mut guard.1;
+        let before = subscribers.len();
+        subscribers.retain(|(existing, _)| *existing != id);
+        subscribers.len() != before
+    }
+
+    pub(crate) fn has_subscribers(&self) -> bool {
+        !self.guard().1.is_empty()
+    }
+
+    /// Deliver events to every subscriber
+    ///
+    /// `events` is only called when someone is listening, so callers can
+    /// build row snapshots lazily. Subscribers are called without the lock
+    /// held, so they may subscribe or unsubscribe themselves.
+    pub(crate) fn emit(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        let subscribers: Vec<Subscriber> = self
+            .guard()
+            .1
+            .iter()
+            .map(|(_, subscriber)| Arc::clone(subscriber))
+            .collect();
+        if subscribers.is_empty() {
+            return;
+        }
+        if let Some(held) = self.held_guard().as_mut() {
+            held.extend(events());
+            return;
+        }
+        for event in events() {
+            for subscriber in &subscribers {
+                subscriber(&event);
+            }
+        }
+    }
+
+    /// Buffer events instead of delivering them, like a
+    /// [`Transaction`](crate::Transaction) does, until
+    /// [`discard_held`](Self::discard_held)
+    pub(crate) fn hold(&self) {
+        self.held_guard().get_or_insert_with(Vec::new);
+    }
+
+    /// Stop holding events, dropping the ones buffered so far
+    pub(crate) fn discard_held(&self) {
+        self.held_guard().take();
+    }
+
+    fn held_guard(&self) -> MutexGuard<'_, Option<Vec<ChangeEvent>>> {
+        self.held
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn guard(&self) -> MutexGuard<'_, (u64, Vec<(SubscriptionId, Subscriber)>)> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,212 @@
+//! The statement runner behind models and builders
+//!
+//! [`Model`](crate::Model) methods and the `execute*` methods of the query
+//! and mutation builders accept any [`Executor`], not just a
+//! [`Database`](crate::Database). Service code written against
+//! `&impl Executor` can then be unit tested with
+//! [`MockDatabase`](crate::testing::MockDatabase) instead of a real SQLite
+//! file. [`Transaction`](crate::Transaction) is an executor too, and
+//! `Arc`s of executors forward to them, so handles shared across tasks can
+//! be passed directly.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{Executor, Model, Result};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//!
+//! async fn rename_user(db: &impl Executor, id: i64, name: &str) -> Result<Option<User>> {
+//!     let Some(mut user) = User::find_by_id(id, db).await? else {
+//!         return Ok(None);
+//!     };
+//!     user.name = name.to_string();
+//!     user.update(db).await.map(Some)
+//! }
+//! ```
+
+use crate::options::{QueryOptions, WithOptions};
+use crate::{ChangeEvent, Result, Row, Value};
+
+/// Runs SQL on behalf of models and builders
+///
+/// `table` names the model or builder table issuing the statement, and is
+/// used for error context; it is `None` for transaction control and other
+/// statements not tied to a table.
+#[allow(async_fn_in_trait)]
+pub trait Executor {
+    /// Run a statement, returning the number of affected rows
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64>;
+
+    /// Run a query, returning each row keyed by column name
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>>;
+
+    /// Report committed model changes to subscribers
+    ///
+    /// `events` should only be called when something is listening. The
+    /// default drops the events.
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        let _ = events;
+    }
+
+    /// Whether statements already run inside a transaction
+    ///
+    /// Bulk operations wrap themselves in `BEGIN`/`COMMIT` only when this is
+    /// `false`. The default is `false`.
+    fn in_transaction(&self) -> bool {
+        false
+    }
+
+    /// Run `BEGIN`, `COMMIT`, or `ROLLBACK`
+    ///
+    /// Wrappers forward this to the executor they wrap without applying
+    /// [`QueryOptions`], so work that was cancelled or timed out can still
+    /// be rolled back. The default runs it with
+    /// [`execute_statement`](Self::execute_statement).
+    async fn execute_transaction_control(&self, sql: &str) -> Result<()> {
+        self.execute_statement(None, sql, vec![]).await.map(drop)
+    }
+
+    /// [`execute_statement`](Self::execute_statement) under `options`
+    ///
+    /// The default stops the statement when it next waits; see
+    /// [`options`](crate::options).
+    async fn execute_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+        options: &QueryOptions,
+    ) -> Result<u64> {
+        options
+            .run(self.execute_statement(table, sql, params))
+            .await
+    }
+
+    /// [`query_statement`](Self::query_statement) under `options`
+    async fn query_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+        options: &QueryOptions,
+    ) -> Result<Vec<Row>> {
+        options.run(self.query_statement(table, sql, params)).await
+    }
+
+    /// Run every statement through this executor with a timeout or
+    /// cancellation token
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Executor, Model, QueryOptions, Result};
+    /// use std::time::Duration;
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct User { id: Option<i64>, name: String }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let db = db.with_options(QueryOptions::new().timeout(Duration::from_secs(2)));
+    /// let count = User::count(&db).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_options(&self, options: QueryOptions) -> WithOptions<'_, Self>
+    where
+        Self: Sized,
+    {
+        WithOptions::new(self, options)
+    }
+}
+
+impl<E: Executor> Executor for std::sync::Arc<E> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        (**self).execute_statement(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        (**self).query_statement(table, sql, params).await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        (**self).emit_changes(events)
+    }
+
+    async fn execute_transaction_control(&self, sql: &str) -> Result<()> {
+        (**self).execute_transaction_control(sql).await
+    }
+
+    fn in_transaction(&self) -> bool {
+        (**self).in_transaction()
+    }
+
+    async fn execute_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+        options: &QueryOptions,
+    ) -> Result<u64> {
+        (**self)
+            .execute_statement_with_options(table, sql, params, options)
+            .await
+    }
+
+    async fn query_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+        options: &QueryOptions,
+    ) -> Result<Vec<Row>> {
+        (**self)
+            .query_statement_with_options(table, sql, params, options)
+            .await
+    }
+}
+
+/// Run `work` between `BEGIN` and `COMMIT` when `own_transaction` is set,
+/// rolling back if `work` or the `COMMIT` fails
+///
+/// Callers pass `!db.in_transaction()` (or a stricter condition) so that
+/// work inside a caller's transaction joins it instead.
+pub(crate) async fn within_transaction<T>(
+    db: &impl Executor,
+    own_transaction: bool,
+    work: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    if !own_transaction {
+        return work.await;
+    }
+    db.execute_transaction_control("BEGIN").await?;
+    let result = match work.await {
+        Ok(value) => db
+            .execute_transaction_control("COMMIT")
+            .await
+            .map(|_| value),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        // Fails harmlessly when a failed COMMIT already ended the transaction
+        let _ = db.execute_transaction_control("ROLLBACK").await;
+    }
+    result
+}
@@ -0,0 +1,461 @@
+//! Typed SQL expressions for libsql-orm
+//!
+//! This module provides an expression layer on top of plain column names.
+//! Expressions compose with comparison, logical, and arithmetic operators and
+//! function calls, and turn into a [`FilterOperator`] wherever a filter is
+//! accepted. Literal values are always bound as parameters.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use libsql_orm::{col, lower, QueryBuilder};
+//!
+//! let (sql, params) = QueryBuilder::new("users")
+//!     .r#where(col("age").gt(30).and(col("is_active").eq(true)))
+//!     .r#where(lower(col("email")).like("%@example.com"))
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     sql,
+//!     r#"SELECT * FROM "users" WHERE ("age" > ?) AND ("is_active" = ?) AND lower("email") LIKE ?"#
+//! );
+//! assert_eq!(params.len(), 3);
+//! ```
+//!
+//! # Arithmetic
+//!
+//! ```rust
+//! use libsql_orm::{col, coalesce, val, QueryBuilder};
+//!
+//! let (sql, _) = QueryBuilder::new("orders")
+//!     .r#where((col("price") * col("quantity")).ge(100))
+//!     .r#where(coalesce([col("discount"), val(0.0)]).lt(0.5))
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     sql,
+//!     r#"SELECT * FROM "orders" WHERE ("price" * "quantity") >= ? AND coalesce("discount", ?) < ?"#
+//! );
+//! ```
+
+use crate::{FilterOperator, Value};
+use serde::{Deserialize, Serialize};
+
+/// SQL expression tree
+///
+/// Build expressions with [`col`], [`val`], and the function helpers rather
+/// than constructing variants by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Expr {
+    /// Column reference, emitted verbatim (e.g. `"users.email"`)
+    Column(String),
+    /// Literal value, bound as a parameter
+    Value(Value),
+    /// Function call such as `lower(email)`
+    Function(String, Vec<Expr>),
+    /// Binary operation
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    /// Logical negation
+    Not(Box<Expr>),
+    /// `IS NULL` check
+    IsNull(Box<Expr>),
+    /// `IS NOT NULL` check
+    IsNotNull(Box<Expr>),
+    /// `IN (...)` over a list of expressions
+    InList(Box<Expr>, Vec<Expr>),
+    /// `CASE WHEN ... THEN ... [ELSE ...] END`; build with [`case_when`]
+    Case(Vec<(Expr, Expr)>, Option<Box<Expr>>),
+}
+
+/// Builder for searched `CASE` expressions, started with [`case_when`]
+#[derive(Debug, Clone)]
+pub struct Case {
+    branches: Vec<(Expr, Expr)>,
+}
+
+/// Start a `CASE WHEN condition THEN result ...` expression
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{case_when, col, func};
+///
+/// // sum(CASE WHEN is_active = ? THEN ? ELSE ? END)
+/// let active = func(
+///     "sum",
+///     [case_when(col("is_active").eq(true), 1i64).otherwise(0i64)],
+/// );
+/// let (sql, params) = active.to_sql();
+/// assert_eq!(sql, r#"sum(CASE WHEN "is_active" = ? THEN ? ELSE ? END)"#);
+/// assert_eq!(params.len(), 3);
+/// ```
+pub fn case_when(condition: impl Into<Expr>, result: impl Into<Expr>) -> Case {
+    Case {
+        branches: vec![(condition.into(), result.into())],
+    }
+}
+
+impl Case {
+    /// Add another `WHEN condition THEN result` branch
+    pub fn when(mut self, condition: impl Into<Expr>, result: impl Into<Expr>) -> Self {
+        self.branches.push((condition.into(), result.into()));
+        self
+    }
+
+    /// Finish with an `ELSE` result
+    pub fn otherwise(self, result: impl Into<Expr>) -> Expr {
+        Expr::Case(self.branches, Some(Box::new(result.into())))
+    }
+
+    /// Finish without an `ELSE`, yielding `NULL` when no branch matches
+    pub fn end(self) -> Expr {
+        Expr::Case(self.branches, None)
+    }
+}
+
+impl From<Case> for Expr {
+    fn from(case: Case) -> Self {
+        case.end()
+    }
+}
+
+/// Binary operators usable in [`Expr::Binary`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Concat,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Like,
+    And,
+    Or,
+}
+
+impl std::fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Concat => "||",
+            BinaryOp::Eq => "=",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Like => "LIKE",
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+        };
+        f.write_str(op)
+    }
+}
+
+/// Reference a column by name
+pub fn col(name: impl Into<String>) -> Expr {
+    Expr::Column(name.into())
+}
+
+/// A literal value, bound as a parameter
+pub fn val(value: impl Into<Value>) -> Expr {
+    Expr::Value(value.into())
+}
+
+/// Call an SQL function by name; the name is emitted verbatim
+pub fn func<I, E>(name: impl Into<String>, args: I) -> Expr
+where
+    I: IntoIterator<Item = E>,
+    E: Into<Expr>,
+{
+    Expr::Function(name.into(), args.into_iter().map(Into::into).collect())
+}
+
+/// `lower(expr)`
+pub fn lower(expr: impl Into<Expr>) -> Expr {
+    func("lower", [expr.into()])
+}
+
+/// `upper(expr)`
+pub fn upper(expr: impl Into<Expr>) -> Expr {
+    func("upper", [expr.into()])
+}
+
+/// `length(expr)`
+pub fn length(expr: impl Into<Expr>) -> Expr {
+    func("length", [expr.into()])
+}
+
+/// `abs(expr)`
+pub fn abs(expr: impl Into<Expr>) -> Expr {
+    func("abs", [expr.into()])
+}
+
+/// `coalesce(a, b, ...)`
+pub fn coalesce<I, E>(exprs: I) -> Expr
+where
+    I: IntoIterator<Item = E>,
+    E: Into<Expr>,
+{
+    func("coalesce", exprs)
+}
+
+/// `json_extract(json, path)`, with the path bound as a parameter
+///
+/// ```rust
+/// use libsql_orm::{col, json_extract};
+///
+/// let (sql, params) = json_extract(col("prefs"), "$.theme").eq("dark").to_sql();
+/// assert_eq!(sql, r#"json_extract("prefs", ?) = ?"#);
+/// assert_eq!(params.len(), 2);
+/// ```
+pub fn json_extract(json: Expr, path: impl Into<String>) -> Expr {
+    func("json_extract", [json, val(path.into())])
+}
+
+/// `json_array_length(json)`
+pub fn json_array_length(json: Expr) -> Expr {
+    func("json_array_length", [json])
+}
+
+/// `group_concat(value, separator)`, with the separator bound as a parameter
+///
+/// Aggregates a group's non-NULL values into one delimited string, or NULL
+/// for a group without any; read it back with
+/// [`de::Delimited`](crate::de::Delimited).
+///
+/// ```rust
+/// use libsql_orm::{col, group_concat};
+///
+/// let (sql, params) = group_concat(col("tags.name"), ", ").to_sql();
+/// assert_eq!(sql, r#"group_concat("tags"."name", ?)"#);
+/// assert_eq!(params.len(), 1);
+/// ```
+pub fn group_concat(value: Expr, separator: impl Into<String>) -> Expr {
+    func("group_concat", [value, val(separator.into())])
+}
+
+/// `json_group_array(value)`, a group's values as a JSON array
+pub fn json_group_array(value: Expr) -> Expr {
+    func("json_group_array", [value])
+}
+
+/// `json_group_object(key, value)`, a group's pairs as a JSON object
+pub fn json_group_object(key: Expr, value: Expr) -> Expr {
+    func("json_group_object", [key, value])
+}
+
+impl Expr {
+    fn binary(self, op: BinaryOp, rhs: impl Into<Expr>) -> Expr {
+        Expr::Binary(Box::new(self), op, Box::new(rhs.into()))
+    }
+
+    /// `self = rhs`
+    pub fn eq(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Eq, rhs)
+    }
+
+    /// `self != rhs`
+    pub fn ne(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Ne, rhs)
+    }
+
+    /// `self < rhs`
+    pub fn lt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Lt, rhs)
+    }
+
+    /// `self <= rhs`
+    pub fn le(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Le, rhs)
+    }
+
+    /// `self > rhs`
+    pub fn gt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Gt, rhs)
+    }
+
+    /// `self >= rhs`
+    pub fn ge(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Ge, rhs)
+    }
+
+    /// `self LIKE pattern`
+    pub fn like(self, pattern: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Like, pattern)
+    }
+
+    /// `self || rhs` (string concatenation)
+    pub fn concat(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Concat, rhs)
+    }
+
+    /// `self AND rhs`
+    pub fn and(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::And, rhs)
+    }
+
+    /// `self OR rhs`
+    pub fn or(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(BinaryOp::Or, rhs)
+    }
+
+    /// `self IS NULL`
+    pub fn is_null(self) -> Expr {
+        Expr::IsNull(Box::new(self))
+    }
+
+    /// `self IS NOT NULL`
+    pub fn is_not_null(self) -> Expr {
+        Expr::IsNotNull(Box::new(self))
+    }
+
+    /// `self IN (a, b, ...)`
+    pub fn in_list<I, E>(self, values: I) -> Expr
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Expr>,
+    {
+        Expr::InList(Box::new(self), values.into_iter().map(Into::into).collect())
+    }
+
+    /// Render the expression and its parameters without executing it
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let (sql, params) = self.build();
+        (sql, params.into_iter().map(Value::from).collect())
+    }
+
+    /// Render the expression with parameters ready for binding
+    pub(crate) fn build(&self) -> (String, Vec<crate::compat::LibsqlValue>) {
+        let mut sql = String::new();
+        let mut params = Vec::new();
+        self.write_sql(&mut sql, &mut params);
+        (sql, params)
+    }
+
+    fn write_sql(&self, sql: &mut String, params: &mut Vec<crate::compat::LibsqlValue>) {
+        match self {
+            Expr::Column(name) => sql.push_str(&crate::query::quote_identifier(name)),
+            Expr::Value(value) => {
+                sql.push('?');
+                params.push(crate::query::value_to_libsql_value(value));
+            }
+            Expr::Function(name, args) => {
+                sql.push_str(name);
+                sql.push('(');
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        sql.push_str(", ");
+                    }
+                    arg.write_sql(sql, params);
+                }
+                sql.push(')');
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                lhs.write_operand(sql, params);
+                sql.push_str(&format!(" {op} "));
+                rhs.write_operand(sql, params);
+            }
+            Expr::Not(inner) => {
+                sql.push_str("NOT ");
+                inner.write_operand(sql, params);
+            }
+            Expr::IsNull(inner) => {
+                inner.write_operand(sql, params);
+                sql.push_str(" IS NULL");
+            }
+            Expr::IsNotNull(inner) => {
+                inner.write_operand(sql, params);
+                sql.push_str(" IS NOT NULL");
+            }
+            Expr::InList(inner, values) => {
+                inner.write_operand(sql, params);
+                sql.push_str(" IN (");
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        sql.push_str(", ");
+                    }
+                    value.write_sql(sql, params);
+                }
+                sql.push(')');
+            }
+            Expr::Case(branches, otherwise) => {
+                sql.push_str("CASE");
+                for (condition, result) in branches {
+                    sql.push_str(" WHEN ");
+                    condition.write_sql(sql, params);
+                    sql.push_str(" THEN ");
+                    result.write_sql(sql, params);
+                }
+                if let Some(otherwise) = otherwise {
+                    sql.push_str(" ELSE ");
+                    otherwise.write_sql(sql, params);
+                }
+                sql.push_str(" END");
+            }
+        }
+    }
+
+    /// Write a nested expression, parenthesizing compound ones so the tree's
+    /// grouping survives regardless of SQL operator precedence
+    fn write_operand(&self, sql: &mut String, params: &mut Vec<crate::compat::LibsqlValue>) {
+        match self {
+            Expr::Column(_) | Expr::Value(_) | Expr::Function(..) | Expr::Case(..) => {
+                self.write_sql(sql, params)
+            }
+            _ => {
+                sql.push('(');
+                self.write_sql(sql, params);
+                sql.push(')');
+            }
+        }
+    }
+}
+
+impl<T: Into<Value>> From<T> for Expr {
+    fn from(value: T) -> Self {
+        Expr::Value(value.into())
+    }
+}
+
+impl From<Expr> for FilterOperator {
+    fn from(expr: Expr) -> Self {
+        FilterOperator::Expr(expr)
+    }
+}
+
+impl std::ops::Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Self::Output {
+        Expr::Not(Box::new(self))
+    }
+}
+
+macro_rules! arithmetic_op {
+    ($trait:ident, $method:ident, $op:expr) => {
+        impl<R: Into<Expr>> std::ops::$trait<R> for Expr {
+            type Output = Expr;
+
+            fn $method(self, rhs: R) -> Self::Output {
+                self.binary($op, rhs)
+            }
+        }
+    };
+}
+
+arithmetic_op!(Add, add, BinaryOp::Add);
+arithmetic_op!(Sub, sub, BinaryOp::Sub);
+arithmetic_op!(Mul, mul, BinaryOp::Mul);
+arithmetic_op!(Div, div, BinaryOp::Div);
+arithmetic_op!(Rem, rem, BinaryOp::Rem);
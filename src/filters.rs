@@ -69,7 +69,7 @@
 //! ];
 //! ```
 
-use crate::{Operator, Value};
+use crate::{Expr, Operator, QueryBuilder, Value};
 use serde::{Deserialize, Serialize};
 
 /// Filter operator for building complex queries
@@ -110,8 +110,16 @@ pub enum FilterOperator {
     Or(Vec<FilterOperator>),
     /// NOT filter
     Not(Box<FilterOperator>),
-    /// Custom SQL condition
+    /// Custom SQL condition, emitted verbatim; never build it from user
+    /// input, use [`Filter::raw`] to bind values instead
     Custom(String),
+    /// SQL fragment with `?` placeholders and the values bound to them;
+    /// see [`Filter::raw`]
+    Raw(String, Vec<Value>),
+    /// `EXISTS (subquery)`; wrap in [`FilterOperator::Not`] for `NOT EXISTS`
+    Exists(Box<QueryBuilder>),
+    /// Typed expression built with [`col`](crate::col) and friends
+    Expr(Expr),
 }
 
 /// Individual filter condition
@@ -142,6 +150,34 @@ pub struct Filter {
     pub operator: Operator,
     /// Value(s) to compare against
     pub value: FilterValue,
+    /// Compare ignoring case: `COLLATE NOCASE`, or `lower()` on both sides for patterns
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive: bool,
+    /// The LIKE pattern escapes wildcards with `\`; see [`escape_like`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub escaped: bool,
+}
+
+/// Escape LIKE wildcards so `text` matches literally
+///
+/// Prefixes `%`, `_`, and `\` with `\`. Use the result in a pattern
+/// rendered with `ESCAPE '\'`, as [`Filter::contains`] and
+/// [`Filter::escaped`] do.
+///
+/// ```rust
+/// use libsql_orm::escape_like;
+///
+/// assert_eq!(escape_like("50%_off"), r"50\%\_off");
+/// ```
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 /// Filter value that can be a single value or multiple values
@@ -150,6 +186,8 @@ pub struct Filter {
 /// - Single values for basic comparisons (=, >, <, etc.)
 /// - Multiple values for IN/NOT IN operations  
 /// - Range values for BETWEEN/NOT BETWEEN operations
+/// - Subqueries for `IN (SELECT ...)` and scalar comparisons
+/// - JSON paths, comparing `json_extract(column, path)` instead of the column
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FilterValue {
     /// Single value
@@ -158,6 +196,10 @@ pub enum FilterValue {
     Multiple(Vec<Value>),
     /// Range values (for BETWEEN, NOT BETWEEN operators)
     Range(Value, Value),
+    /// Subquery whose result is compared against (for IN, NOT IN, or scalar comparisons)
+    Subquery(Box<QueryBuilder>),
+    /// Value compared against the JSON path in the column; both are bound
+    JsonPath(String, Value),
 }
 
 impl Filter {
@@ -167,6 +209,8 @@ impl Filter {
             column: column.into(),
             operator,
             value,
+            case_insensitive: false,
+            escaped: false,
         }
     }
 
@@ -180,6 +224,8 @@ impl Filter {
             column: column.into(),
             operator,
             value: FilterValue::Single(value.into()),
+            case_insensitive: false,
+            escaped: false,
         }
     }
 
@@ -214,6 +260,10 @@ impl Filter {
     }
 
     /// Create a LIKE filter
+    ///
+    /// The pattern is bound as a parameter, but `%` and `_` in it are still
+    /// wildcards; match user-supplied text with [`contains`](Self::contains)
+    /// or [`starts_with`](Self::starts_with), which match it literally.
     pub fn like(column: impl Into<String>, pattern: impl Into<String>) -> Self {
         Self::new(
             column,
@@ -222,6 +272,93 @@ impl Filter {
         )
     }
 
+    /// Create a case-insensitive LIKE filter
+    ///
+    /// Renders as `lower(column) LIKE lower(?)`, so it does not depend on the
+    /// `case_sensitive_like` pragma. See [`nocase`](Self::nocase) for what
+    /// is and is not folded.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, FilterOperator, QueryBuilder};
+    ///
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .r#where(Filter::ilike("name", "%alice%"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE lower("name") LIKE lower(?)"#);
+    /// ```
+    pub fn ilike(column: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::like(column, pattern).nocase()
+    }
+
+    /// Create a filter matching columns that contain `text`
+    ///
+    /// `text` is matched literally: its `%`, `_`, and `\` are escaped (see
+    /// [`escape_like`]) and the pattern is bound as a parameter. Chain
+    /// [`nocase`](Self::nocase) to ignore case.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder, Value};
+    ///
+    /// let (sql, params) = QueryBuilder::new("products")
+    ///     .r#where(Filter::contains("name", "100%"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "products" WHERE "name" LIKE ? ESCAPE '\'"#);
+    /// assert_eq!(params, vec![Value::Text(r"%100\%%".to_string())]);
+    /// ```
+    pub fn contains(column: impl Into<String>, text: &str) -> Self {
+        Self::like(column, format!("%{}%", escape_like(text))).escaped()
+    }
+
+    /// Create a filter matching columns that start with `text`, matched
+    /// literally like [`contains`](Self::contains)
+    pub fn starts_with(column: impl Into<String>, text: &str) -> Self {
+        Self::like(column, format!("{}%", escape_like(text))).escaped()
+    }
+
+    /// Create a filter matching columns that end with `text`, matched
+    /// literally like [`contains`](Self::contains)
+    pub fn ends_with(column: impl Into<String>, text: &str) -> Self {
+        Self::like(column, format!("%{}", escape_like(text))).escaped()
+    }
+
+    /// Mark a LIKE pattern as escaped with `\`, rendering `ESCAPE '\'`
+    pub fn escaped(mut self) -> Self {
+        self.escaped = true;
+        self
+    }
+
+    /// Create a case-insensitive equality filter using `COLLATE NOCASE`
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .r#where(Filter::eq_nocase("email", "Alice@Example.com"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE "email" COLLATE NOCASE = ?"#);
+    /// ```
+    pub fn eq_nocase(column: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::eq(column, value).nocase()
+    }
+
+    /// Make any filter compare case-insensitively
+    ///
+    /// LIKE and GLOB lower both sides, REGEXP patterns get a leading `(?i)`,
+    /// and every other operator compares with `COLLATE NOCASE`.
+    ///
+    /// Only ASCII letters are folded, following SQLite's built-in rules:
+    /// `É` does not match `é`, and accents are never ignored, so `cafe` does
+    /// not match `café`. Accent-insensitive matching is out of scope for the
+    /// query builder; store a normalized copy of the column and filter on it
+    /// instead.
+    pub fn nocase(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
     /// Create a NOT LIKE filter
     pub fn not_like(column: impl Into<String>, pattern: impl Into<String>) -> Self {
         Self::new(
@@ -231,6 +368,57 @@ impl Filter {
         )
     }
 
+    /// Create a GLOB filter
+    ///
+    /// GLOB uses Unix shell wildcards (`*`, `?`, `[...]`) and, unlike LIKE,
+    /// is case-sensitive.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, _) = QueryBuilder::new("files")
+    ///     .r#where(Filter::glob("path", "src/*.rs"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "files" WHERE "path" GLOB ?"#);
+    /// ```
+    pub fn glob(column: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::new(
+            column,
+            Operator::Glob,
+            FilterValue::Single(Value::Text(pattern.into())),
+        )
+    }
+
+    /// Create a NOT GLOB filter
+    pub fn not_glob(column: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::new(
+            column,
+            Operator::NotGlob,
+            FilterValue::Single(Value::Text(pattern.into())),
+        )
+    }
+
+    /// Create a REGEXP filter
+    ///
+    /// SQLite only parses `column REGEXP ?`; the `regexp()` function itself
+    /// must be provided by the database, e.g. an SQLite build with the
+    /// regexp extension loaded. The bundled turso engine rejects REGEXP
+    /// while parsing and has no hook for registering user functions, so on
+    /// [`Database`](crate::Database) these queries fail; the filter is for
+    /// SQL rendered with [`QueryBuilder::to_sql`] and run elsewhere.
+    ///
+    /// With [`nocase`](Self::nocase) the pattern gets a leading `(?i)` flag
+    /// instead of being lowered.
+    #[cfg(feature = "regexp")]
+    pub fn regexp(column: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::new(
+            column,
+            Operator::Regexp,
+            FilterValue::Single(Value::Text(pattern.into())),
+        )
+    }
+
     /// Create an IN filter
     pub fn in_values(column: impl Into<String>, values: Vec<impl Into<Value>>) -> Self {
         let values = values.into_iter().map(|v| v.into()).collect();
@@ -243,6 +431,95 @@ impl Filter {
         Self::new(column, Operator::NotIn, FilterValue::Multiple(values))
     }
 
+    /// Create an IN filter from any list of values
+    ///
+    /// Each value is bound as its own parameter. Lists longer than
+    /// [`MAX_INLINE_LIST_PARAMS`](crate::query::MAX_INLINE_LIST_PARAMS) are sent as a single
+    /// JSON array and expanded with `json_each`, as are all lists of a
+    /// statement that would otherwise bind more than
+    /// [`MAX_PARAMETERS`](crate::chunking::MAX_PARAMETERS) values, so they
+    /// never hit SQLite's bound-parameter limit. Lists of blobs go as hex
+    /// strings decoded with `unhex`. An empty list matches no rows.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, params) = QueryBuilder::new("orders")
+    ///     .r#where(Filter::in_list("status", ["paid", "shipped"]))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "orders" WHERE "status" IN (?, ?)"#);
+    /// assert_eq!(params.len(), 2);
+    ///
+    /// let ids: Vec<i64> = (1..=5000).collect();
+    /// let (sql, params) = QueryBuilder::new("orders")
+    ///     .r#where(Filter::in_list("id", ids))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "orders" WHERE "id" IN (SELECT value FROM json_each(?))"#);
+    /// assert_eq!(params.len(), 1);
+    /// ```
+    pub fn in_list<I>(column: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Value>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        Self::new(column, Operator::In, FilterValue::Multiple(values))
+    }
+
+    /// Create a NOT IN filter from any list of values
+    ///
+    /// Expanded like [`in_list`](Self::in_list); an empty list matches every row.
+    pub fn not_in<I>(column: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Value>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        Self::new(column, Operator::NotIn, FilterValue::Multiple(values))
+    }
+
+    /// Create an `IN (SELECT ...)` filter
+    ///
+    /// The subquery's parameters are bound in place, after any parameters
+    /// that precede this filter in the outer query.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, FilterOperator, QueryBuilder};
+    ///
+    /// let banned = QueryBuilder::new("banned_users")
+    ///     .select(["user_id"])
+    ///     .r#where(FilterOperator::Single(Filter::eq("reason", "spam")));
+    /// let (sql, params) = QueryBuilder::new("posts")
+    ///     .r#where(FilterOperator::Single(Filter::in_subquery("user_id", banned)))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT * FROM "posts" WHERE "user_id" IN (SELECT "user_id" FROM "banned_users" WHERE "reason" = ?)"#
+    /// );
+    /// assert_eq!(params.len(), 1);
+    /// ```
+    pub fn in_subquery(column: impl Into<String>, subquery: QueryBuilder) -> Self {
+        Self::new(
+            column,
+            Operator::In,
+            FilterValue::Subquery(Box::new(subquery)),
+        )
+    }
+
+    /// Create a `NOT IN (SELECT ...)` filter
+    pub fn not_in_subquery(column: impl Into<String>, subquery: QueryBuilder) -> Self {
+        Self::new(
+            column,
+            Operator::NotIn,
+            FilterValue::Subquery(Box::new(subquery)),
+        )
+    }
+
     /// Create an IS NULL filter
     pub fn is_null(column: impl Into<String>) -> Self {
         Self::new(column, Operator::IsNull, FilterValue::Single(Value::Null))
@@ -257,7 +534,113 @@ impl Filter {
         )
     }
 
+    /// Escape hatch for conditions the filter DSL does not cover
+    ///
+    /// The fragment is wrapped in parentheses so it composes safely inside
+    /// And/Or trees, and its values are bound in place. The number of `?`
+    /// placeholders (outside quoted literals) must match the number of
+    /// values, or building the query fails.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, FilterOperator, QueryBuilder, Value};
+    ///
+    /// let (sql, params) = QueryBuilder::new("users")
+    ///     .r#where(FilterOperator::Or(vec![
+    ///         Filter::eq("role", "admin").into(),
+    ///         Filter::raw("length(name) > ? AND name != '?'", [3i64]),
+    ///     ]))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT * FROM "users" WHERE ("role" = ? OR (length(name) > ? AND name != '?'))"#
+    /// );
+    /// assert_eq!(params, vec![Value::Text("admin".into()), Value::Integer(3)]);
+    ///
+    /// assert!(QueryBuilder::new("users")
+    ///     .r#where(Filter::raw("a = ? AND b = ?", [1i64]))
+    ///     .to_sql()
+    ///     .is_err());
+    /// ```
+    pub fn raw<I>(sql: impl Into<String>, params: I) -> FilterOperator
+    where
+        I: IntoIterator,
+        I::Item: Into<Value>,
+    {
+        FilterOperator::Raw(sql.into(), params.into_iter().map(Into::into).collect())
+    }
+
+    /// Compare a value inside a JSON column: `json_extract(column, path) = ?`
+    ///
+    /// Both the path and the value are bound as parameters. JSON booleans
+    /// extract as 1/0, which matches how `bool` values are bound.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, params) = QueryBuilder::new("users")
+    ///     .r#where(Filter::json_eq("prefs", "$.theme", "dark"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE json_extract("prefs", ?) = ?"#);
+    /// assert_eq!(params.len(), 2);
+    /// ```
+    ///
+    /// Other comparisons take a [`FilterValue::JsonPath`] with any operator.
+    pub fn json_eq(
+        column: impl Into<String>,
+        path: impl Into<String>,
+        value: impl Into<Value>,
+    ) -> Self {
+        Self::new(
+            column,
+            Operator::Eq,
+            FilterValue::JsonPath(path.into(), value.into()),
+        )
+    }
+
+    /// Match rows whose JSON array column contains `value`
+    ///
+    /// Renders as a correlated `EXISTS` over `json_each(column)`.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, _) = QueryBuilder::new("posts")
+    ///     .r#where(Filter::json_contains("posts.tags", "rust"))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT * FROM "posts" WHERE EXISTS (SELECT 1 FROM json_each("posts"."tags") WHERE "value" = ?)"#
+    /// );
+    /// ```
+    pub fn json_contains(column: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self::new(
+            column,
+            Operator::JsonContains,
+            FilterValue::Single(value.into()),
+        )
+    }
+
     /// Create a BETWEEN filter
+    ///
+    /// Both endpoints are inclusive and bound as parameters. Timestamps
+    /// convert to the same RFC 3339 text the models store, so date ranges
+    /// work directly:
+    ///
+    /// ```rust
+    /// use chrono::{Duration, Utc};
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let end = Utc::now();
+    /// let (sql, params) = QueryBuilder::new("orders")
+    ///     .r#where(Filter::between("created_at", end - Duration::days(7), end))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "orders" WHERE "created_at" BETWEEN ? AND ?"#);
+    /// assert_eq!(params.len(), 2);
+    /// ```
     pub fn between(
         column: impl Into<String>,
         min: impl Into<Value>,
@@ -282,6 +665,92 @@ impl Filter {
             FilterValue::Range(min.into(), max.into()),
         )
     }
+
+    /// Row-value comparison `(c1, c2, ...) > (v1, v2, ...)`
+    ///
+    /// Compares lexicographically, as needed for keyset pagination over a
+    /// sort key with a tie-breaker. The comparison is expanded into
+    /// `c1 > v1 OR (c1 = v1 AND c2 > v2) OR ...`, which every SQLite
+    /// flavour accepts and which can still use a composite index.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, QueryBuilder};
+    ///
+    /// let (sql, params) = QueryBuilder::new("posts")
+    ///     .r#where(Filter::tuple_gt(
+    ///         ("created_at", "id"),
+    ///         ("2024-05-01T12:00:00Z", 42i64),
+    ///     ))
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT * FROM "posts" WHERE ("created_at" > ? OR ("created_at" = ? AND "id" > ?))"#
+    /// );
+    /// assert_eq!(params.len(), 3);
+    /// ```
+    pub fn tuple_gt<C: RowValue<V>, V>(columns: C, values: V) -> FilterOperator {
+        row_comparison(columns.pair(values), Operator::Gt, Operator::Gt)
+    }
+
+    /// Row-value comparison `(c1, c2, ...) >= (v1, v2, ...)`
+    pub fn tuple_ge<C: RowValue<V>, V>(columns: C, values: V) -> FilterOperator {
+        row_comparison(columns.pair(values), Operator::Gt, Operator::Ge)
+    }
+
+    /// Row-value comparison `(c1, c2, ...) < (v1, v2, ...)`
+    pub fn tuple_lt<C: RowValue<V>, V>(columns: C, values: V) -> FilterOperator {
+        row_comparison(columns.pair(values), Operator::Lt, Operator::Lt)
+    }
+
+    /// Row-value comparison `(c1, c2, ...) <= (v1, v2, ...)`
+    pub fn tuple_le<C: RowValue<V>, V>(columns: C, values: V) -> FilterOperator {
+        row_comparison(columns.pair(values), Operator::Lt, Operator::Le)
+    }
+}
+
+/// A tuple of column names that can be compared against a tuple of values
+/// of the same arity; see [`Filter::tuple_gt`]
+pub trait RowValue<V> {
+    /// Pair every column with its value
+    fn pair(self, values: V) -> Vec<(String, Value)>;
+}
+
+macro_rules! impl_row_value {
+    ($(($c:ident, $v:ident, $idx:tt)),+) => {
+        impl<$($c: Into<String>,)+ $($v: Into<Value>,)+> RowValue<($($v,)+)> for ($($c,)+) {
+            fn pair(self, values: ($($v,)+)) -> Vec<(String, Value)> {
+                vec![$((self.$idx.into(), values.$idx.into()),)+]
+            }
+        }
+    };
+}
+
+impl_row_value!((C1, V1, 0), (C2, V2, 1));
+impl_row_value!((C1, V1, 0), (C2, V2, 1), (C3, V3, 2));
+impl_row_value!((C1, V1, 0), (C2, V2, 1), (C3, V3, 2), (C4, V4, 3));
+
+/// Expand a row-value comparison: every prefix of equal columns followed by
+/// a strict comparison, with `last` used for the final column
+fn row_comparison(pairs: Vec<(String, Value)>, strict: Operator, last: Operator) -> FilterOperator {
+    let n = pairs.len();
+    let branches = (0..n)
+        .map(|i| {
+            let mut terms: Vec<FilterOperator> = pairs[..i]
+                .iter()
+                .map(|(column, value)| Filter::eq(column.clone(), value.clone()).into())
+                .collect();
+            let operator = if i + 1 == n { last } else { strict };
+            let (column, value) = &pairs[i];
+            terms.push(Filter::new_simple(column.clone(), operator, value.clone()).into());
+            if terms.len() == 1 {
+                terms.pop().unwrap()
+            } else {
+                FilterOperator::And(terms)
+            }
+        })
+        .collect();
+    FilterOperator::Or(branches)
 }
 
 impl FilterOperator {
@@ -295,9 +764,45 @@ impl FilterOperator {
         FilterOperator::Or(filters)
     }
 
-    /// Create a NOT filter
-    pub fn negate(filter: FilterOperator) -> Self {
-        FilterOperator::Not(Box::new(filter))
+    /// Invert this filter, including any And/Or subtree beneath it
+    ///
+    /// Negating an already negated filter unwraps it instead of stacking
+    /// another `NOT`. The `!` operator does the same.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Filter, FilterOperator, QueryBuilder};
+    ///
+    /// let banned_or_dormant = FilterOperator::Or(vec![
+    ///     Filter::eq("status", "banned").into(),
+    ///     Filter::lt("last_login", "2024-01-01").into(),
+    /// ]);
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .r#where(banned_or_dormant.clone().negate())
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE NOT (("status" = ? OR "last_login" < ?))"#);
+    ///
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .r#where(banned_or_dormant.negate().negate())
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE ("status" = ? OR "last_login" < ?)"#);
+    /// ```
+    pub fn negate(self) -> Self {
+        match self {
+            FilterOperator::Not(inner) => *inner,
+            other => FilterOperator::Not(Box::new(other)),
+        }
+    }
+
+    /// Create an EXISTS filter
+    pub fn exists(subquery: QueryBuilder) -> Self {
+        FilterOperator::Exists(Box::new(subquery))
+    }
+
+    /// Create a NOT EXISTS filter
+    pub fn not_exists(subquery: QueryBuilder) -> Self {
+        FilterOperator::Not(Box::new(FilterOperator::Exists(Box::new(subquery))))
     }
 
     /// Add a filter to an AND group
@@ -323,11 +828,17 @@ impl FilterOperator {
     }
 }
 
+impl From<Filter> for FilterOperator {
+    fn from(filter: Filter) -> Self {
+        FilterOperator::Single(filter)
+    }
+}
+
 impl std::ops::Not for FilterOperator {
     type Output = Self;
 
     fn not(self) -> Self::Output {
-        FilterOperator::Not(Box::new(self))
+        self.negate()
     }
 }
 
@@ -351,6 +862,9 @@ impl std::ops::Not for FilterOperator {
 ///
 /// // Convert to filter for use in queries
 /// let filter_op = search.to_filter_operator();
+///
+/// // Ranked search through a companion FTS5 table
+/// let ranked = SearchFilter::new("rust orm", vec!["title", "body"]).fts("posts_fts");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchFilter {
@@ -362,6 +876,28 @@ pub struct SearchFilter {
     pub case_sensitive: bool,
     /// Whether to use exact match
     pub exact_match: bool,
+    /// Companion FTS5 table used by [`Model::search_ranked`](crate::Model::search_ranked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fts_table: Option<String>,
+    /// Pass `query` to FTS5 `MATCH` unchanged, operators and all
+    #[serde(default)]
+    pub raw_query: bool,
+}
+
+/// Column alias carrying the relevance score in ranked searches
+pub(crate) const SEARCH_SCORE_COLUMN: &str = "__search_score";
+
+/// A model returned by a ranked search, with its relevance score
+///
+/// Higher scores are more relevant. With an FTS5 index the score is the
+/// negated `bm25()` rank; with the LIKE fallback it is the number of
+/// searched columns that match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit<T> {
+    /// The matching record
+    pub model: T,
+    /// Relevance score, higher is better
+    pub score: f64,
 }
 
 impl SearchFilter {
@@ -372,6 +908,8 @@ impl SearchFilter {
             columns: columns.into_iter().map(|c| c.into()).collect(),
             case_sensitive: false,
             exact_match: false,
+            fts_table: None,
+            raw_query: false,
         }
     }
 
@@ -387,17 +925,93 @@ impl SearchFilter {
         self
     }
 
+    /// Use FTS5 query syntax (`AND`, `NEAR`, `prefix*`, column filters)
+    ///
+    /// By default every whitespace-separated term is quoted, so user input is
+    /// matched as plain words and cannot produce an FTS5 syntax error. Only
+    /// enable this for queries your application builds itself.
+    pub fn raw_query(mut self, raw_query: bool) -> Self {
+        self.raw_query = raw_query;
+        self
+    }
+
+    /// Rank results through an FTS5 table
+    ///
+    /// The table is expected to be an FTS5 index whose `rowid` matches the
+    /// model's primary key, e.g.
+    /// `CREATE VIRTUAL TABLE posts_fts USING fts5(title, body, content='posts', content_rowid='id')`.
+    /// When it does not exist, ranked searches fall back to LIKE.
+    pub fn fts(mut self, table: impl Into<String>) -> Self {
+        self.fts_table = Some(table.into());
+        self
+    }
+
+    /// Full-text query against `fts_table`, joined back to `table` and
+    /// ordered by relevance
+    pub(crate) fn fts_query(
+        &self,
+        fts_table: &str,
+        table: &str,
+        primary_key: &str,
+    ) -> (String, Vec<Value>) {
+        let quote = |term: &str| format!("\"{}\"", term.replace('"', "\"\""));
+        let query = if self.exact_match {
+            quote(&self.query)
+        } else if self.raw_query {
+            self.query.clone()
+        } else {
+            self.query
+                .split_whitespace()
+                .map(quote)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let fts_table = crate::query::quote_identifier(fts_table);
+        let table = crate::query::quote_name(table);
+        let primary_key = crate::query::quote_name(primary_key);
+        let score = crate::query::quote_name(SEARCH_SCORE_COLUMN);
+        let sql = format!(
+            "SELECT {table}.*, -bm25({fts_table}) AS {score} FROM {table} \
+             JOIN {fts_table} ON {fts_table}.rowid = {table}.{primary_key} \
+             WHERE {fts_table} MATCH ? ORDER BY {score} DESC"
+        );
+        (sql, vec![Value::Text(query)])
+    }
+
+    /// LIKE search that scores each row by the number of matching columns
+    pub(crate) fn like_ranked_query(&self, table: &str) -> crate::QueryBuilder {
+        let score = self
+            .columns
+            .iter()
+            .map(|column| crate::case_when(self.column_match(column), 1i64).otherwise(0i64))
+            .reduce(|a, b| a + b)
+            .unwrap_or_else(|| crate::val(0i64));
+
+        crate::QueryBuilder::new(table)
+            .select(["*"])
+            .select_expr(score, SEARCH_SCORE_COLUMN)
+            .r#where(self.to_filter_operator())
+            .order_by_expr(crate::col(SEARCH_SCORE_COLUMN), crate::SortOrder::Desc)
+    }
+
     /// Convert to FilterOperator
+    ///
+    /// Matching ignores case unless [`case_sensitive`](Self::case_sensitive)
+    /// is set, in which case substring matches use `instr()`. The query is
+    /// always bound and matched literally: `%` and `_` are not wildcards.
     pub fn to_filter_operator(&self) -> FilterOperator {
         let mut filters = Vec::new();
 
         for column in &self.columns {
-            let filter = if self.exact_match {
-                Filter::eq(column, &*self.query)
-            } else {
-                Filter::like(column, format!("%{}%", self.query))
+            let filter = match (self.exact_match, self.case_sensitive) {
+                (true, true) => FilterOperator::Single(Filter::eq(column, &*self.query)),
+                (true, false) => FilterOperator::Single(Filter::eq_nocase(column, &*self.query)),
+                (false, true) => self.column_match(column).into(),
+                (false, false) => {
+                    FilterOperator::Single(Filter::contains(column, &self.query).nocase())
+                }
             };
-            filters.push(FilterOperator::Single(filter));
+            filters.push(filter);
         }
 
         if filters.len() == 1 {
@@ -407,6 +1021,25 @@ impl SearchFilter {
         }
     }
 
+    /// Whether `column` matches the query, as an expression for scoring
+    fn column_match(&self, column: &str) -> crate::Expr {
+        let (column, query) = (crate::col(column), crate::val(self.query.as_str()));
+        match (self.exact_match, self.case_sensitive) {
+            (true, true) => column.eq(query),
+            (true, false) => crate::lower(column).eq(crate::lower(query)),
+            (false, true) => crate::func("instr", [column, query]).gt(0i64),
+            // like(pattern, value, escape) is `value LIKE pattern ESCAPE escape`
+            (false, false) => crate::func(
+                "like",
+                [
+                    crate::lower(crate::val(format!("%{}%", escape_like(&self.query)))),
+                    crate::lower(column),
+                    crate::val("\\"),
+                ],
+            ),
+        }
+    }
+
     /// Create a new search filter for a single field
     pub fn new_single_field(field: impl Into<String>, query: impl Into<String>) -> Self {
         Self {
@@ -414,6 +1047,8 @@ impl SearchFilter {
             columns: vec![field.into()],
             case_sensitive: false,
             exact_match: false,
+            fts_table: None,
+            raw_query: false,
         }
     }
 
@@ -424,36 +1059,17 @@ impl SearchFilter {
             columns: fields.into_iter().map(|f| f.into()).collect(),
             case_sensitive: false,
             exact_match: false,
+            fts_table: None,
+            raw_query: false,
         }
     }
 
     /// Convert to FilterOperator with improved search logic
+    ///
+    /// Same as [`to_filter_operator`](Self::to_filter_operator); kept for
+    /// compatibility.
     pub fn to_filter_operator_improved(&self) -> FilterOperator {
-        let mut filters = Vec::new();
-
-        for column in &self.columns {
-            let pattern = if self.exact_match {
-                self.query.clone()
-            } else {
-                format!("%{}%", self.query)
-            };
-
-            let filter = if self.case_sensitive {
-                Filter::like(column.clone(), pattern)
-            } else {
-                // For case-insensitive search, we'll use LOWER() function
-                // This will be handled in the query builder
-                Filter::like(column.clone(), pattern)
-            };
-
-            filters.push(FilterOperator::Single(filter));
-        }
-
-        if filters.len() == 1 {
-            filters.pop().unwrap()
-        } else {
-            FilterOperator::Or(filters)
-        }
+        self.to_filter_operator()
     }
 }
 
@@ -476,6 +1092,13 @@ impl SearchFilter {
 ///     Sort::new("priority", SortOrder::Desc),
 ///     Sort::new("name", SortOrder::Asc),
 /// ];
+///
+/// // Case-insensitive, with NULLs at the end
+/// let sort = Sort::asc("nickname").collate("NOCASE").nulls_last();
+/// assert_eq!(
+///     sort.to_sql(),
+///     r#"("nickname" IS NULL) ASC, "nickname" COLLATE NOCASE ASC"#
+/// );
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sort {
@@ -483,6 +1106,33 @@ pub struct Sort {
     pub column: String,
     /// Sort order
     pub order: crate::SortOrder,
+    /// Placement of NULLs; `None` keeps SQLite's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nulls: Option<crate::NullsOrder>,
+    /// Collating sequence for the column, such as `NOCASE`
+    ///
+    /// Deserializing rejects anything but a bare identifier, so a sort taken
+    /// from request parameters cannot smuggle SQL into the ORDER BY clause.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_collation"
+    )]
+    pub collation: Option<String>,
+}
+
+/// Accept only bare identifiers as collation names
+fn deserialize_collation<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let collation = Option::<String>::deserialize(deserializer)?;
+    match collation {
+        Some(name) if !crate::query::is_identifier(&name) => Err(serde::de::Error::custom(
+            format!("invalid collation name {name:?}"),
+        )),
+        collation => Ok(collation),
+    }
 }
 
 impl Sort {
@@ -491,19 +1141,21 @@ impl Sort {
         Self {
             column: column.into(),
             order,
+            nulls: None,
+            collation: None,
         }
     }
 
     /// Create a new sort with boolean flag for ascending
     pub fn new_bool(column: impl Into<String>, ascending: bool) -> Self {
-        Self {
-            column: column.into(),
-            order: if ascending {
+        Self::new(
+            column,
+            if ascending {
                 crate::SortOrder::Asc
             } else {
                 crate::SortOrder::Desc
             },
-        }
+        )
     }
 
     /// Create an ascending sort
@@ -515,4 +1167,53 @@ impl Sort {
     pub fn desc(column: impl Into<String>) -> Self {
         Self::new(column, crate::SortOrder::Desc)
     }
+
+    /// Set where NULLs are placed
+    pub fn nulls(mut self, nulls: crate::NullsOrder) -> Self {
+        self.nulls = Some(nulls);
+        self
+    }
+
+    /// Place NULLs before all other values
+    pub fn nulls_first(self) -> Self {
+        self.nulls(crate::NullsOrder::First)
+    }
+
+    /// Place NULLs after all other values
+    pub fn nulls_last(self) -> Self {
+        self.nulls(crate::NullsOrder::Last)
+    }
+
+    /// Compare values with the given collating sequence, e.g. `NOCASE`
+    ///
+    /// A name that is not a bare identifier is rendered quoted, so it can
+    /// only ever name a collation.
+    pub fn collate(mut self, collation: impl Into<String>) -> Self {
+        self.collation = Some(collation.into());
+        self
+    }
+
+    /// Render the ORDER BY term
+    ///
+    /// NULL placement is expressed as a leading `(column IS NULL)` term rather
+    /// than `NULLS FIRST`/`NULLS LAST`, which not every backend accepts.
+    pub fn to_sql(&self) -> String {
+        let mut sql = String::new();
+        let column = crate::query::quote_identifier(&self.column);
+        match self.nulls {
+            Some(crate::NullsOrder::First) => sql.push_str(&format!("({column} IS NULL) DESC, ")),
+            Some(crate::NullsOrder::Last) => sql.push_str(&format!("({column} IS NULL) ASC, ")),
+            None => {}
+        }
+        sql.push_str(&column);
+        if let Some(collation) = &self.collation {
+            if crate::query::is_identifier(collation) {
+                sql.push_str(&format!(" COLLATE {collation}"));
+            } else {
+                sql.push_str(&format!(" COLLATE {}", crate::query::quote_name(collation)));
+            }
+        }
+        sql.push_str(&format!(" {}", self.order));
+        sql
+    }
 }
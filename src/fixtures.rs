@@ -0,0 +1,362 @@
+//! Seed data loaded from JSON or YAML files
+//!
+//! A fixture file maps table names to labelled rows. A string value of the
+//! form `$table.label` is replaced by the primary key of that fixture once
+//! it has been inserted, so rows can refer to each other without hard-coded
+//! ids. Rows are inserted in file order, except that a row always follows
+//! the rows it refers to, which keeps foreign keys satisfied. Start a
+//! string with `$$` to store a literal leading `$`.
+//!
+//! ```yaml
+//! users:
+//!   alice:
+//!     name: Alice
+//!     email: alice@example.com
+//! posts:
+//!   welcome:
+//!     user_id: $users.alice
+//!     title: Welcome
+//! ```
+//!
+//! [`load`] inserts the columns as written. Tables registered on a
+//! [`Fixtures`] set are deserialized into their model first, which applies
+//! the model's boolean conversion and `#[orm_validate]` rules before any SQL
+//! runs.
+//!
+//! YAML files need the `yaml` feature. Rows are inserted one statement at
+//! a time; load inside a transaction to make the whole file atomic.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{fixtures, Database, Result};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct Post { id: Option<i64>, user_id: i64, title: String }
+//!
+//! # async fn example(db: &Database) -> Result<()> {
+//! let loaded = fixtures::Fixtures::new()
+//!     .register::<User>()
+//!     .register::<Post>()
+//!     .load(db, "fixtures/blog.json")
+//!     .await?;
+//! let alice_id = loaded.id("users", "alice").unwrap();
+//!
+//! // Or insert the columns as written
+//! fixtures::load(db, "fixtures/seed.json").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Error, Executor, InsertBuilder, Model, Result, Row, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+/// The syntax of a fixture file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureFormat {
+    Json,
+    /// Requires the `yaml` feature
+    Yaml,
+}
+
+impl FixtureFormat {
+    /// The format of a file with this extension: `json`, `yaml`, or `yml`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Insert every fixture in a file, with columns as written
+///
+/// Shorthand for [`Fixtures::new().load(db, path)`](Fixtures::load).
+pub async fn load(db: &impl Executor, path: impl AsRef<Path>) -> Result<LoadedFixtures> {
+    Fixtures::new().load(db, path).await
+}
+
+/// Primary keys of loaded fixtures, by table and label
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadedFixtures {
+    keys: HashMap<(String, String), Value>,
+}
+
+impl LoadedFixtures {
+    /// The integer primary key of a fixture
+    pub fn id(&self, table: &str, label: &str) -> Option<i64> {
+        match self.key(table, label)? {
+            Value::Integer(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// The primary key of a fixture, whatever its type
+    pub fn key(&self, table: &str, label: &str) -> Option<&Value> {
+        self.keys.get(&(table.to_string(), label.to_string()))
+    }
+
+    /// Number of loaded fixtures
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether nothing was loaded
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+struct RegisteredModel {
+    primary_key: &'static str,
+    normalize: fn(Row) -> Result<Row>,
+}
+
+/// Tables whose fixtures go through a model, and the loader that uses them
+#[derive(Default)]
+pub struct Fixtures {
+    models: HashMap<&'static str, RegisteredModel>,
+}
+
+impl Fixtures {
+    /// A loader with no registered models
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deserialize fixtures for `M::table_name()` into `M` before inserting
+    pub fn register<M: Model>(mut self) -> Self {
+        self.models.insert(
+            M::table_name(),
+            RegisteredModel {
+                primary_key: M::primary_key(),
+                normalize: |row| {
+                    let model = M::from_map(row)?;
+                    model.validate()?;
+                    model.to_map()
+                },
+            },
+        );
+        self
+    }
+
+    /// Insert every fixture in a file, choosing the format by extension
+    pub async fn load(&self, db: &impl Executor, path: impl AsRef<Path>) -> Result<LoadedFixtures> {
+        let path = path.as_ref();
+        let format = FixtureFormat::from_path(path).ok_or_else(|| {
+            Error::Validation(format!(
+                "Unknown fixture format for {}; expected .json, .yaml, or .yml",
+                path.display()
+            ))
+        })?;
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            Error::DatabaseError(format!("Failed to read fixtures {}: {e}", path.display()))
+        })?;
+        self.load_str(db, &contents, format).await
+    }
+
+    /// Insert every fixture in `contents`
+    pub async fn load_str(
+        &self,
+        db: &impl Executor,
+        contents: &str,
+        format: FixtureFormat,
+    ) -> Result<LoadedFixtures> {
+        let tables = parse(contents, format)?;
+        let rows: Vec<FixtureRow> = tables
+            .0
+            .into_iter()
+            .flat_map(|(table, rows)| {
+                rows.0.into_iter().map(move |(label, columns)| FixtureRow {
+                    table: table.clone(),
+                    label,
+                    columns,
+                })
+            })
+            .collect();
+
+        let mut loaded = LoadedFixtures::default();
+        for index in insertion_order(&rows)? {
+            let row = &rows[index];
+            let primary_key = self
+                .models
+                .get(row.table.as_str())
+                .map_or("id", |model| model.primary_key);
+
+            let mut values = Row::new();
+            for (column, value) in &row.columns {
+                let value = match reference(value) {
+                    Reference::Fixture(table, label) => {
+                        loaded.key(table, label).cloned().ok_or_else(|| {
+                            Error::Validation(format!(
+                                "Fixture {}.{} refers to {table}.{label}, which has no primary key",
+                                row.table, row.label
+                            ))
+                        })?
+                    }
+                    Reference::Escaped(text) => Value::Text(text.to_string()),
+                    Reference::None => Value::from(value.clone()),
+                };
+                values.insert(column.clone(), value);
+            }
+            if let Some(model) = self.models.get(row.table.as_str()) {
+                values = (model.normalize)(values).map_err(|e| row.error(e))?;
+            }
+
+            let mut values: Vec<(String, Value)> = values.into_iter().collect();
+            values.sort_by(|a, b| a.0.cmp(&b.0));
+            let (sql, params) = InsertBuilder::table(&row.table).values(values).to_sql()?;
+            let sql = format!(
+                "{sql} RETURNING {}",
+                crate::query::quote_identifier(primary_key)
+            );
+            let key = db
+                .query_statement(Some(&row.table), &sql, params)
+                .await
+                .map_err(|e| row.error(e))?
+                .into_iter()
+                .next()
+                .and_then(|mut returned| returned.remove(primary_key))
+                .unwrap_or(Value::Null);
+            loaded
+                .keys
+                .insert((row.table.clone(), row.label.clone()), key);
+        }
+        Ok(loaded)
+    }
+}
+
+struct FixtureRow {
+    table: String,
+    label: String,
+    columns: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FixtureRow {
+    fn error(&self, error: Error) -> Error {
+        Error::Validation(format!("Fixture {}.{}: {error}", self.table, self.label))
+    }
+}
+
+enum Reference<'a> {
+    Fixture(&'a str, &'a str),
+    Escaped(&'a str),
+    None,
+}
+
+fn reference(value: &serde_json::Value) -> Reference<'_> {
+    let Some(text) = value.as_str() else {
+        return Reference::None;
+    };
+    if text.starts_with("$$") {
+        return Reference::Escaped(&text[1..]);
+    }
+    match text.strip_prefix('$').and_then(|rest| rest.split_once('.')) {
+        Some((table, label)) if !table.is_empty() && !label.is_empty() => {
+            Reference::Fixture(table, label)
+        }
+        _ => Reference::None,
+    }
+}
+
+/// Row indices in file order, moving each row after the rows it refers to
+fn insertion_order(rows: &[FixtureRow]) -> Result<Vec<usize>> {
+    let index: HashMap<(&str, &str), usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| ((row.table.as_str(), row.label.as_str()), i))
+        .collect();
+
+    let mut pending = vec![0usize; rows.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+    for (i, row) in rows.iter().enumerate() {
+        for value in row.columns.values() {
+            if let Reference::Fixture(table, label) = reference(value) {
+                let target = *index.get(&(table, label)).ok_or_else(|| {
+                    Error::Validation(format!(
+                        "Fixture {}.{} refers to unknown fixture {table}.{label}",
+                        row.table, row.label
+                    ))
+                })?;
+                pending[i] += 1;
+                dependents[target].push(i);
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..rows.len()).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(rows.len());
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for &dependent in &dependents[i] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    if order.len() < rows.len() {
+        let cycle: Vec<String> = (0..rows.len())
+            .filter(|&i| pending[i] > 0)
+            .map(|i| format!("{}.{}", rows[i].table, rows[i].label))
+            .collect();
+        return Err(Error::Validation(format!(
+            "Fixtures refer to each other in a cycle: {}",
+            cycle.join(", ")
+        )));
+    }
+    Ok(order)
+}
+
+type FixtureTables = Ordered<Ordered<serde_json::Map<String, serde_json::Value>>>;
+
+fn parse(contents: &str, format: FixtureFormat) -> Result<FixtureTables> {
+    match format {
+        FixtureFormat::Json => serde_json::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid JSON fixtures: {e}"))),
+        #[cfg(feature = "yaml")]
+        FixtureFormat::Yaml => serde_yaml::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid YAML fixtures: {e}"))),
+        #[cfg(not(feature = "yaml"))]
+        FixtureFormat::Yaml => Err(Error::Validation(
+            "YAML fixtures require the `yaml` feature".to_string(),
+        )),
+    }
+}
+
+/// A map that keeps its entries in file order
+struct Ordered<V>(Vec<(String, V)>);
+
+impl<'de, V: serde::Deserialize<'de>> serde::Deserialize<'de> for Ordered<V> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        struct OrderedVisitor<V>(std::marker::PhantomData<V>);
+
+        impl<'de, V: serde::Deserialize<'de>> serde::de::Visitor<'de> for OrderedVisitor<V> {
+            type Value = Ordered<V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a map of labelled entries")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Ordered(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedVisitor(std::marker::PhantomData))
+    }
+}
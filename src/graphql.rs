@@ -0,0 +1,170 @@
+//! async-graphql integration, behind the `graphql` feature
+//!
+//! - Models are plain structs, so `#[derive(async_graphql::SimpleObject)]`
+//!   next to `#[derive(Model)]` makes a model a GraphQL output type
+//! - [`pagination`] turns Relay `after`/`first` arguments into a
+//!   [`Pagination`], and [`PaginatedResult::into_connection`] turns the page
+//!   back into a `Connection` with offset cursors and a `totalCount` field
+//! - [`ModelLoader`] is a `DataLoader` loader backed by
+//!   [`Model::find_by_ids`], so resolving a relation for every item in a
+//!   list runs one query instead of one per item
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use async_graphql::connection::Connection;
+//! use async_graphql::dataloader::DataLoader;
+//! use async_graphql::{Context, Object, SimpleObject};
+//! use libsql_orm::graphql::{pagination, ModelLoader, TotalCount};
+//! use libsql_orm::{Database, Model};
+//! use serde::{Deserialize, Serialize};
+//! use std::sync::Arc;
+//!
+//! #[derive(Model, SimpleObject, Clone, Serialize, Deserialize)]
+//! #[graphql(complex)]
+//! struct Post {
+//!     id: Option<i64>,
+//!     author_id: i64,
+//!     title: String,
+//! }
+//!
+//! #[derive(Model, SimpleObject, Clone, Serialize, Deserialize)]
+//! struct User {
+//!     id: Option<i64>,
+//!     name: String,
+//! }
+//!
+//! #[async_graphql::ComplexObject]
+//! impl Post {
+//!     async fn author(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<User>> {
+//!         let loader = ctx.data_unchecked::<DataLoader<ModelLoader<User>>>();
+//!         Ok(loader.load_one(self.author_id).await?)
+//!     }
+//! }
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn posts(
+//!         &self,
+//!         ctx: &Context<'_>,
+//!         after: Option<String>,
+//!         first: Option<i32>,
+//!     ) -> async_graphql::Result<Connection<usize, Post, TotalCount>> {
+//!         let db = ctx.data_unchecked::<Arc<Database>>();
+//!         let page = pagination(after.as_deref(), first, 20)?;
+//!         Ok(Post::find_paginated(&page, &**db).await?.into_connection())
+//!     }
+//! }
+//!
+//! # async fn example(db: Database) {
+//! let db = Arc::new(db);
+//! let schema = async_graphql::Schema::build(
+//!     Query,
+//!     async_graphql::EmptyMutation,
+//!     async_graphql::EmptySubscription,
+//! )
+//! .data(DataLoader::new(ModelLoader::<User>::new(db.clone()), tokio::spawn))
+//! .data(db)
+//! .finish();
+//! # }
+//! ```
+
+use crate::{Database, Error, Model, PaginatedResult, Pagination};
+use async_graphql::connection::{Connection, CursorType, Edge};
+use async_graphql::dataloader::Loader;
+use async_graphql::OutputType;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Extra `Connection` field holding the total number of items, when counted
+#[derive(Debug, Clone, Copy, async_graphql::SimpleObject)]
+pub struct TotalCount {
+    /// Items across all pages; `null` when the query skipped the count
+    pub total_count: Option<u64>,
+}
+
+/// The page a Relay `after` cursor and `first` count ask for
+///
+/// `after` is a cursor from [`PaginatedResult::into_connection`], and
+/// `first` defaults to `per_page`. Cursors point at page boundaries as long
+/// as the client keeps `first` the same from page to page.
+pub fn pagination(
+    after: Option<&str>,
+    first: Option<i32>,
+    per_page: u32,
+) -> async_graphql::Result<Pagination> {
+    let per_page = match first {
+        Some(first) if first <= 0 => {
+            return Err(async_graphql::Error::new("`first` must be positive"))
+        }
+        Some(first) => first as u32,
+        None => per_page.max(1),
+    };
+    let offset = match after {
+        Some(cursor) => {
+            usize::decode_cursor(cursor)
+                .map_err(|_| async_graphql::Error::new(format!("Invalid cursor {cursor:?}")))?
+                + 1
+        }
+        None => 0,
+    };
+    Ok(Pagination::new(offset as u32 / per_page + 1, per_page))
+}
+
+impl<T: OutputType> PaginatedResult<T> {
+    /// Convert into a Relay connection whose cursors are item offsets
+    pub fn into_connection(self) -> Connection<usize, T, TotalCount> {
+        let offset = self.pagination.offset() as usize;
+        let mut connection = Connection::with_additional_fields(
+            self.pagination.has_prev(),
+            self.pagination.has_next(),
+            TotalCount {
+                total_count: self.pagination.total,
+            },
+        );
+        connection.edges.extend(
+            self.data
+                .into_iter()
+                .enumerate()
+                .map(|(i, node)| Edge::new(offset + i, node)),
+        );
+        connection
+    }
+}
+
+/// `DataLoader` loader fetching models by primary key
+///
+/// Batched keys are loaded with one [`Model::find_by_ids`] query; keys
+/// without a record resolve to `None`.
+pub struct ModelLoader<M> {
+    db: Arc<Database>,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> ModelLoader<M> {
+    /// Load from the given database
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            model: PhantomData,
+        }
+    }
+}
+
+impl<M: Model + 'static> Loader<i64> for ModelLoader<M> {
+    type Value = M;
+    type Error = Arc<Error>;
+
+    async fn load(&self, keys: &[i64]) -> std::result::Result<HashMap<i64, M>, Self::Error> {
+        let models = crate::model::find_by_ids::<M>(keys, &*self.db)
+            .await
+            .map_err(Arc::new)?;
+        Ok(models
+            .into_iter()
+            .filter_map(|model| model.get_primary_key().map(|id| (id, model)))
+            .collect())
+    }
+}
@@ -0,0 +1,266 @@
+//! Bulk CSV import into models
+//!
+//! [`Model::import_csv`](crate::Model::import_csv) reads a CSV file whose
+//! header names model columns. Each field is parsed by the column's declared
+//! type, and each row is deserialized into the model and checked against its
+//! `#[orm_validate]` rules before it is inserted. Rows that fail are reported
+//! in the [`ImportReport`] with their line number instead of failing the
+//! whole file; the rest are inserted in transactions of
+//! [`ImportOptions::batch_size`] rows.
+//!
+//! Empty fields are `NULL`. `INTEGER` columns also accept `true`/`false`,
+//! and `BLOB` columns take hex, matching
+//! [`QueryBuilder::export_csv`](crate::QueryBuilder::export_csv).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::import::ImportOptions;
+//! use libsql_orm::{Database, Model, Result};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String, email: String }
+//!
+//! # async fn example(db: &Database) -> Result<()> {
+//! let file = std::fs::File::open("users.csv")?;
+//! let report = User::import_csv(
+//!     file,
+//!     db,
+//!     ImportOptions {
+//!         batch_size: 200,
+//!         upsert_on: Some("email"),
+//!     },
+//! )
+//! .await?;
+//! for failure in &report.errors {
+//!     eprintln!("line {}: {}", failure.line, failure.error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::executor::within_transaction;
+use crate::{Error, Executor, Model, Result, Row, Value};
+use std::collections::HashMap;
+use std::io::{BufReader, Read};
+
+/// How [`Model::import_csv`](crate::Model::import_csv) inserts rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportOptions<'a> {
+    /// Rows per transaction
+    pub batch_size: usize,
+    /// Update the existing row with the same value in this column instead of
+    /// inserting, via [`Model::upsert`](crate::Model::upsert)
+    pub upsert_on: Option<&'a str>,
+}
+
+impl Default for ImportOptions<'_> {
+    fn default() -> Self {
+        Self {
+            batch_size: 500,
+            upsert_on: None,
+        }
+    }
+}
+
+/// The outcome of an import
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Rows inserted or upserted
+    pub imported: u64,
+    /// Rows that were skipped, in file order
+    pub errors: Vec<ImportRowError>,
+}
+
+/// A row that could not be imported
+#[derive(Debug)]
+pub struct ImportRowError {
+    /// Line of the file the row starts on; the header is line 1
+    pub line: u64,
+    /// Why the row was skipped
+    pub error: Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Integer,
+    Real,
+    Boolean,
+    Blob,
+    Text,
+}
+
+/// Declared column types of a model, by SQLite affinity
+fn column_kinds<M: Model>() -> HashMap<String, ColumnKind> {
+    M::column_definitions()
+        .iter()
+        .filter_map(|definition| {
+            let mut words = definition.split_whitespace();
+            let name = words.next()?.trim_matches('"').to_string();
+            let declared = words.next().unwrap_or("").to_ascii_uppercase();
+            let kind = if declared.contains("BOOL") {
+                ColumnKind::Boolean
+            } else if declared.contains("INT") {
+                ColumnKind::Integer
+            } else if ["REAL", "FLOA", "DOUB"]
+                .iter()
+                .any(|t| declared.contains(t))
+            {
+                ColumnKind::Real
+            } else if declared.contains("BLOB") {
+                ColumnKind::Blob
+            } else {
+                ColumnKind::Text
+            };
+            Some((name, kind))
+        })
+        .collect()
+}
+
+fn parse_field(kind: ColumnKind, text: &str) -> std::result::Result<Value, String> {
+    if text.is_empty() {
+        return Ok(Value::Null);
+    }
+    let boolean = || match text.to_ascii_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    };
+    match kind {
+        ColumnKind::Integer => text
+            .trim()
+            .parse()
+            .map(Value::Integer)
+            .or_else(|_| boolean().map(|b| Value::Integer(b as i64)).ok_or(()))
+            .map_err(|_| format!("expected an integer, found {text:?}")),
+        ColumnKind::Real => text
+            .trim()
+            .parse()
+            .map(Value::Real)
+            .map_err(|_| format!("expected a number, found {text:?}")),
+        ColumnKind::Boolean => boolean()
+            .map(Value::Boolean)
+            .ok_or_else(|| format!("expected true or false, found {text:?}")),
+        ColumnKind::Blob => (0..text.len())
+            .step_by(2)
+            .map(|i| {
+                text.get(i..i + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map(Value::Blob)
+            .ok_or_else(|| format!("expected hex, found {text:?}")),
+        ColumnKind::Text => Ok(Value::Text(text.to_string())),
+    }
+}
+
+fn parse_row<M: Model>(
+    header: &[String],
+    fields: Vec<String>,
+    kinds: &HashMap<String, ColumnKind>,
+) -> Result<M> {
+    if fields.len() != header.len() {
+        return Err(Error::Validation(format!(
+            "expected {} fields, found {}",
+            header.len(),
+            fields.len()
+        )));
+    }
+    let mut row = Row::new();
+    for (column, text) in header.iter().zip(fields) {
+        let kind = kinds.get(column).copied().unwrap_or(ColumnKind::Text);
+        let value = parse_field(kind, &text)
+            .map_err(|message| Error::Validation(format!("{column}: {message}")))?;
+        row.insert(column.clone(), value);
+    }
+    // Columns without a declared type (such as `Option<i64>` fields) are
+    // read as text first; if the model rejects that, retry with numbers and
+    // booleans recognized
+    let model = match M::from_map(row.clone()) {
+        Ok(model) => model,
+        Err(error) => {
+            let inferred = row
+                .into_iter()
+                .map(|(column, value)| match value {
+                    Value::Text(text) => (column, infer(text)),
+                    value => (column, value),
+                })
+                .collect();
+            M::from_map(inferred).map_err(|_| error)?
+        }
+    };
+    model.validate()?;
+    Ok(model)
+}
+
+fn infer(text: String) -> Value {
+    if let Ok(i) = text.trim().parse() {
+        Value::Integer(i)
+    } else if let Ok(f) = text.trim().parse() {
+        Value::Real(f)
+    } else {
+        match text.as_str() {
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            _ => Value::Text(text),
+        }
+    }
+}
+
+/// Insert one batch, recording per-row failures
+async fn flush<M: Model>(
+    batch: &mut Vec<(u64, M)>,
+    db: &impl Executor,
+    options: &ImportOptions<'_>,
+    report: &mut ImportReport,
+) -> Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    // Rows only count as imported once their batch commits
+    let imported = within_transaction(db, !db.in_transaction(), async {
+        let mut imported = 0;
+        for (line, model) in batch.drain(..) {
+            let result = match options.upsert_on {
+                Some(column) => model.upsert(&[column], db).await,
+                None => model.create(db).await,
+            };
+            match result {
+                Ok(_) => imported += 1,
+                Err(error) => report.errors.push(ImportRowError { line, error }),
+            }
+        }
+        Ok(imported)
+    })
+    .await?;
+    report.imported += imported;
+    Ok(())
+}
+
+pub(crate) async fn import_csv<M: Model>(
+    reader: impl Read,
+    db: &impl Executor,
+    options: ImportOptions<'_>,
+) -> Result<ImportReport> {
+    let mut records = crate::csv::Records::new(BufReader::new(reader));
+    let mut report = ImportReport::default();
+    let Some((_, header)) = records.next_record()? else {
+        return Ok(report);
+    };
+    let header: Vec<String> = header.into_iter().map(|c| c.trim().to_string()).collect();
+    let kinds = column_kinds::<M>();
+
+    let mut batch = Vec::new();
+    while let Some((line, fields)) = records.next_record()? {
+        match parse_row::<M>(&header, fields, &kinds) {
+            Ok(model) => batch.push((line, model)),
+            Err(error) => report.errors.push(ImportRowError { line, error }),
+        }
+        if batch.len() >= options.batch_size.max(1) {
+            flush(&mut batch, db, &options, &mut report).await?;
+        }
+    }
+    flush(&mut batch, db, &options, &mut report).await?;
+
+    report.errors.sort_by_key(|failure| failure.line);
+    Ok(report)
+}
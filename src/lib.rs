@@ -219,36 +219,80 @@
 //!     Response::from_json(&users)
 //! }
 //! ```
+pub mod analyzer;
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod cache;
+pub mod chunking;
 pub mod compat;
+mod csv;
 pub mod database;
+pub mod de;
+pub mod driver;
 pub mod error;
+pub mod events;
+pub mod executor;
+pub mod expr;
 pub mod filters;
+pub mod fixtures;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod import;
 pub mod macros;
 pub mod migrations;
 pub mod model;
+pub mod mutation;
+pub mod options;
 pub mod pagination;
 pub mod query;
+#[cfg(feature = "sea-query")]
+pub mod sea_query;
+pub mod testing;
+pub mod transaction;
 pub mod types;
+pub mod validation;
 
 #[cfg(test)]
 mod tests;
 
+pub use analyzer::{QueryAnalyzer, RepeatedQuery};
 pub use database::Database;
-pub use error::{Error, Result};
-pub use filters::{Filter, FilterOperator, SearchFilter, Sort};
-pub use migrations::{templates, Migration, MigrationBuilder, MigrationManager};
-pub use model::Model;
-pub use pagination::{CursorPaginatedResult, CursorPagination, PaginatedResult, Pagination};
-pub use query::{QueryBuilder, QueryResult};
+pub use driver::{Driver, DriverExecutor};
+pub use error::{Error, Result, StatementContext};
+pub use events::{ChangeEvent, ChangeOperation, SubscriptionId};
+pub use executor::Executor;
+pub use expr::{
+    abs, case_when, coalesce, col, func, group_concat, json_array_length, json_extract,
+    json_group_array, json_group_object, length, lower, upper, val, BinaryOp, Case, Expr,
+};
+pub use filters::{escape_like, Filter, FilterOperator, RowValue, SearchFilter, SearchHit, Sort};
+pub use migrations::{
+    templates, Migration, MigrationBuilder, MigrationManager, MigrationStatus, ModelSchema,
+    PlannedMigration,
+};
+pub use model::{FromRow, Model};
+pub use mutation::{DeleteBuilder, InsertBuilder, UpdateBuilder};
+pub use options::{CancellationToken, QueryOptions, WithOptions};
+pub use pagination::{
+    CountCache, CountStrategy, CursorPaginatedResult, CursorPagination, PaginatedResult, Pagination,
+};
+pub use query::{quote_identifier, QueryBuilder, QueryResult, Window};
+pub use transaction::Transaction;
 pub use types::*;
+pub use validation::{FieldError, ValidationErrors};
 
 // Export the boolean deserializer
 pub use types::deserialize_bool;
 
 // Re-export commonly used types
 pub use chrono;
+#[cfg(feature = "schemars")]
+#[doc(hidden)]
+pub use schemars;
 pub use serde::{Deserialize, Serialize};
 pub use uuid::Uuid;
 
 /// Re-export the Model macro for convenience
-pub use libsql_orm_macros::{generate_migration, orm_column, Model};
+pub use libsql_orm_macros::{
+    embed_migrations, generate_migration, orm_column, Factory, FromRow, Model,
+};
@@ -92,10 +92,142 @@ macro_rules! filter_op {
     };
 
     (not, $filter:expr) => {
-        $crate::FilterOperator::not($filter)
+        $crate::FilterOperator::negate($filter)
     };
 
     ($filter:expr) => {
         $crate::FilterOperator::Single($filter)
     };
 }
+
+/// Helper macro for diffing models against the live schema
+///
+/// Expands to [`MigrationManager::diff_models`](crate::MigrationManager::diff_models)
+/// over the listed models; await the result.
+///
+/// ```no_run
+/// use libsql_orm::{generate_migration_diff, MigrationManager, Error};
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct User { id: Option<i64>, name: String }
+///
+/// # async fn example(manager: &MigrationManager) -> Result<(), Error> {
+/// let migrations = generate_migration_diff!(manager, User).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! generate_migration_diff {
+    ($manager:expr, $($model:ty),+ $(,)?) => {
+        $manager.diff_models(&[$($crate::ModelSchema::of::<$model>()),+])
+    };
+}
+
+/// Assert that two SQL statements are equal apart from formatting
+///
+/// Both sides are passed through
+/// [`testing::normalize_sql`](crate::testing::normalize_sql), so expected
+/// SQL can be written across several lines.
+///
+/// ```
+/// use libsql_orm::{assert_sql_eq, Filter, QueryBuilder};
+///
+/// let (sql, _) = QueryBuilder::new("users")
+///     .r#where(Filter::eq("email", "alice@example.com"))
+///     .to_sql()
+///     .unwrap();
+/// assert_sql_eq!(
+///     sql,
+///     r#"SELECT *
+///        FROM "users"
+///        WHERE "email" = ?"#
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_sql_eq {
+    ($actual:expr, $expected:expr $(,)?) => {
+        $crate::assert_sql_eq!($actual, $expected, "SQL differs after normalizing whitespace")
+    };
+    ($actual:expr, $expected:expr, $($message:tt)+) => {{
+        let actual = $crate::testing::normalize_sql(::std::convert::AsRef::<str>::as_ref(&$actual));
+        let expected =
+            $crate::testing::normalize_sql(::std::convert::AsRef::<str>::as_ref(&$expected));
+        assert_eq!(actual, expected, $($message)+);
+    }};
+}
+
+/// Assert that SQL matches `tests/snapshots/{name}.sql` in the calling crate
+///
+/// The snapshot is written on first run; set `LIBSQL_ORM_UPDATE_SNAPSHOTS=1`
+/// to rewrite snapshots after an intended change. See
+/// [`testing::check_sql_snapshot`](crate::testing::check_sql_snapshot).
+///
+/// ```no_run
+/// use libsql_orm::{assert_sql_snapshot, Filter, QueryBuilder};
+///
+/// let (sql, _) = QueryBuilder::new("users")
+///     .r#where(Filter::eq("email", "alice@example.com"))
+///     .to_sql()
+///     .unwrap();
+/// assert_sql_snapshot!("users_by_email", sql);
+/// ```
+#[macro_export]
+macro_rules! assert_sql_snapshot {
+    ($name:expr, $sql:expr $(,)?) => {{
+        let path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{}.sql", $name));
+        if let Err(message) =
+            $crate::testing::check_sql_snapshot(&path, ::std::convert::AsRef::<str>::as_ref(&$sql))
+        {
+            panic!("{}", message);
+        }
+    }};
+}
+
+/// Implement `schemars::JsonSchema` for models from [`Model::json_schema`](crate::Model::json_schema)
+///
+/// Requires the `schemars` feature. The schema is named after the type, so
+/// `schemars::schema_for!`, and OpenAPI generators built on schemars, pick up
+/// the same nullability, types, and validation limits as the table.
+///
+/// ```no_run
+/// use libsql_orm::{impl_json_schema, Model};
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct User { id: Option<i64>, name: String }
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct Post { id: Option<i64>, title: String }
+///
+/// impl_json_schema!(User, Post);
+///
+/// let schema = schemars::schema_for!(Vec<User>);
+/// ```
+#[cfg(feature = "schemars")]
+#[macro_export]
+macro_rules! impl_json_schema {
+    ($($model:ty),+ $(,)?) => {
+        $(
+            impl $crate::schemars::JsonSchema for $model {
+                fn schema_name() -> ::std::borrow::Cow<'static, str> {
+                    ::std::borrow::Cow::Borrowed(stringify!($model))
+                }
+
+                fn json_schema(_: &mut $crate::schemars::SchemaGenerator) -> $crate::schemars::Schema {
+                    $crate::macros::schemars_schema::<$model>()
+                }
+            }
+        )+
+    };
+}
+
+/// [`Model::json_schema`](crate::Model::json_schema) as a schemars subschema
+#[cfg(feature = "schemars")]
+#[doc(hidden)]
+pub fn schemars_schema<M: crate::Model>() -> schemars::Schema {
+    let mut schema = M::json_schema();
+    if let Some(object) = schema.as_object_mut() {
+        // Subschemas inherit the dialect of the document they end up in
+        object.remove("$schema");
+    }
+    schemars::Schema::try_from(schema).expect("model JSON Schema is an object")
+}
@@ -58,7 +58,14 @@
 //! let create_index = templates::create_index("idx_posts_title", "posts", &["title"]);
 //! ```
 
-use crate::{compat::text_value, database::Database, error::Error};
+use crate::query::{quote_identifier, quote_name};
+use crate::{
+    compat::{null_value, text_value},
+    database::Database,
+    error::Error,
+    model::Model,
+    types::Value,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -82,10 +89,421 @@ pub struct Migration {
     pub id: String,
     pub name: String,
     pub sql: String,
+    /// SQL that reverses `sql`, run by [`MigrationManager::rollback_migration`]
+    #[serde(default)]
+    pub down_sql: Option<String>,
+    /// Position in the migration history; see [`version_from_name`](Migration::version_from_name)
+    #[serde(default)]
+    pub version: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub executed_at: Option<DateTime<Utc>>,
 }
 
+impl Migration {
+    /// Create a migration from its SQL, as produced by `embed_migrations!`
+    ///
+    /// Like every migration, its id is its name, so the same migration
+    /// embedded in every build is recognized as already applied.
+    pub fn embedded(name: &str, up_sql: &str, down_sql: Option<&str>) -> Self {
+        Self {
+            id: name.to_string(),
+            name: name.to_string(),
+            sql: up_sql.to_string(),
+            down_sql: down_sql.map(str::to_string),
+            version: Self::version_from_name(name),
+            created_at: Utc::now(),
+            executed_at: None,
+        }
+    }
+
+    /// Version encoded in a migration name's leading digits
+    ///
+    /// A `YYYYMMDD_HHMMSS` prefix, as written by
+    /// [`generate_migration_name`](MigrationManager::generate_migration_name),
+    /// is read as `YYYYMMDDHHMMSS`, whatever follows it. Otherwise the
+    /// leading digits are the version, so `003_add_index` is version `3`.
+    /// Names without a numeric prefix have no version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::Migration;
+    ///
+    /// assert_eq!(Migration::version_from_name("20240101_120000_create_users"), Some(20240101120000));
+    /// assert_eq!(Migration::version_from_name("20240101_120000_2fa_codes"), Some(20240101120000));
+    /// assert_eq!(Migration::version_from_name("20240101120000_create_users"), Some(20240101120000));
+    /// assert_eq!(Migration::version_from_name("003_add_index"), Some(3));
+    /// assert_eq!(Migration::version_from_name("create_users"), None);
+    /// ```
+    pub fn version_from_name(name: &str) -> Option<i64> {
+        let bytes = name.as_bytes();
+        let timestamp = bytes.len() >= 15
+            && bytes[..8].iter().all(u8::is_ascii_digit)
+            && bytes[8] == b'_'
+            && bytes[9..15].iter().all(u8::is_ascii_digit)
+            && bytes.get(15).is_none_or(|b| !b.is_ascii_digit());
+        if timestamp {
+            return format!("{}{}", &name[..8], &name[9..15]).parse().ok();
+        }
+        let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    }
+
+    /// SHA-256 of the up SQL, hex encoded
+    ///
+    /// Recorded when the migration is executed and compared by
+    /// [`MigrationManager::verify`].
+    pub fn checksum(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(self.sql.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// Table definition a model expects, compared against the live schema by
+/// [`MigrationManager::diff_models`]
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{Model, ModelSchema};
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # #[table_name("users")]
+/// # struct User { id: Option<i64>, name: String }
+///
+/// let schema = ModelSchema::of::<User>();
+/// assert_eq!(schema.table, "users");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelSchema {
+    pub table: String,
+    /// Column and table constraint definitions, as in `CREATE TABLE`
+    pub definitions: Vec<String>,
+    /// Statement that creates the table from scratch
+    pub create_sql: String,
+}
+
+impl ModelSchema {
+    /// Schema of a model type
+    pub fn of<M: Model>() -> Self {
+        Self {
+            table: M::table_name().to_string(),
+            definitions: M::column_definitions(),
+            create_sql: M::migration_sql(),
+        }
+    }
+}
+
+/// Split the body of a `CREATE TABLE` statement into its definitions
+pub(crate) fn split_definitions(create_sql: &str) -> Vec<String> {
+    let (Some(start), Some(end)) = (create_sql.find('('), create_sql.rfind(')')) else {
+        return Vec::new();
+    };
+    if end <= start {
+        return Vec::new();
+    }
+
+    let mut definitions = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for c in create_sql[start + 1..end].chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    definitions.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        definitions.push(current.trim().to_string());
+    }
+    definitions
+}
+
+/// Column properties that decide whether a table must change
+#[derive(Debug, PartialEq)]
+struct ColumnShape {
+    data_type: String,
+    not_null: bool,
+    primary_key: bool,
+}
+
+/// Name and shape of a column definition, `None` for table constraints
+fn parse_definition(definition: &str) -> Option<(String, ColumnShape)> {
+    let mut tokens = definition.split_whitespace();
+    let name = tokens.next()?;
+    if matches!(
+        name.to_ascii_uppercase().as_str(),
+        "PRIMARY" | "UNIQUE" | "CHECK" | "FOREIGN" | "CONSTRAINT"
+    ) {
+        return None;
+    }
+
+    let rest: Vec<&str> = tokens.collect();
+    let data_type = rest
+        .iter()
+        .take_while(|token| {
+            !matches!(
+                token.to_ascii_uppercase().as_str(),
+                "PRIMARY"
+                    | "NOT"
+                    | "NULL"
+                    | "UNIQUE"
+                    | "DEFAULT"
+                    | "CHECK"
+                    | "REFERENCES"
+                    | "COLLATE"
+                    | "GENERATED"
+                    | "AS"
+                    | "CONSTRAINT"
+            )
+        })
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_uppercase();
+    let upper = definition.to_ascii_uppercase();
+    Some((
+        name.trim_matches(|c| c == '"' || c == '`').to_string(),
+        ColumnShape {
+            data_type,
+            not_null: upper.contains("NOT NULL"),
+            primary_key: upper.contains("PRIMARY KEY"),
+        },
+    ))
+}
+
+/// Whether `ALTER TABLE ... ADD COLUMN` accepts the definition
+fn can_add_column(definition: &str) -> bool {
+    let upper = definition.to_ascii_uppercase();
+    !upper.contains("PRIMARY KEY")
+        && !upper.contains("UNIQUE")
+        && !(upper.contains("NOT NULL") && !upper.contains("DEFAULT"))
+}
+
+/// First statement of every table rebuild
+///
+/// Dropping a table that other tables reference fails while foreign keys
+/// are enforced, so a migration containing this statement runs the way
+/// SQLite documents for rebuilds; see
+/// [`MigrationManager::execute_migration`].
+pub const FOREIGN_KEYS_OFF: &str = "PRAGMA foreign_keys = OFF";
+
+/// Whether a script contains [`FOREIGN_KEYS_OFF`], in any spelling
+fn disables_foreign_keys(sql: &str) -> bool {
+    split_statements(sql).iter().any(|statement| {
+        let compact: String = statement
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        matches!(
+            compact.as_str(),
+            "pragmaforeign_keys=off"
+                | "pragmaforeign_keys=0"
+                | "pragmaforeign_keys=false"
+                | "pragmaforeign_keys=no"
+        )
+    })
+}
+
+/// A foreign key declared in a `CREATE TABLE` statement
+#[derive(Debug, PartialEq)]
+pub(crate) struct ForeignKey {
+    pub(crate) columns: Vec<String>,
+    pub(crate) parent: String,
+    /// Empty when the key references the parent's primary key
+    pub(crate) parent_columns: Vec<String>,
+}
+
+/// Column- and table-level foreign keys of a `CREATE TABLE` statement
+pub(crate) fn foreign_keys(create_sql: &str) -> Vec<ForeignKey> {
+    fn unquote(name: &str) -> String {
+        name.trim()
+            .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+            .to_string()
+    }
+    fn names(list: &str) -> Vec<String> {
+        list.split(',')
+            .map(unquote)
+            .filter(|n| !n.is_empty())
+            .collect()
+    }
+    fn parenthesized(text: &str) -> Option<(&str, &str)> {
+        let rest = text.trim_start().strip_prefix('(')?;
+        let end = rest.find(')')?;
+        Some((&rest[..end], &rest[end + 1..]))
+    }
+    fn references(text: &str) -> Option<(String, Vec<String>)> {
+        let upper = text.to_ascii_uppercase();
+        let start = upper.find("REFERENCES")? + "REFERENCES".len();
+        let rest = text[start..].trim_start();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or(rest.len());
+        let parent_columns = parenthesized(&rest[end..])
+            .map(|(list, _)| names(list))
+            .unwrap_or_default();
+        Some((unquote(&rest[..end]), parent_columns))
+    }
+
+    split_definitions(create_sql)
+        .iter()
+        .filter_map(|definition| {
+            let mut text = definition.as_str();
+            if text.to_ascii_uppercase().starts_with("CONSTRAINT") {
+                // CONSTRAINT <name> <constraint>
+                let mut words = text.splitn(3, char::is_whitespace);
+                text = words.nth(2).unwrap_or_default().trim_start();
+            }
+            let upper = text.to_ascii_uppercase();
+            let (columns, rest) = match upper.strip_prefix("FOREIGN") {
+                Some(after) if after.trim_start().starts_with("KEY") => {
+                    let key = &text[upper.find("KEY")? + 3..];
+                    let (list, rest) = parenthesized(key)?;
+                    (names(list), rest)
+                }
+                _ if ["PRIMARY", "UNIQUE", "CHECK"]
+                    .iter()
+                    .any(|keyword| upper.starts_with(keyword)) =>
+                {
+                    return None
+                }
+                _ => (vec![unquote(text.split_whitespace().next()?)], text),
+            };
+            let (parent, parent_columns) = references(rest)?;
+            Some(ForeignKey {
+                columns,
+                parent,
+                parent_columns,
+            })
+        })
+        .collect()
+}
+
+/// Statements that recreate `table` with new definitions, keeping shared data
+///
+/// Starts with [`FOREIGN_KEYS_OFF`], so the manager runs it with foreign
+/// keys disabled and checks them before committing.
+fn rebuild_table_sql(
+    table: &str,
+    definitions: &[String],
+    copied_columns: &[String],
+    indexes: &[String],
+) -> String {
+    let staging = quote_identifier(&format!("{table}__rebuild"));
+    let table = quote_identifier(table);
+    let columns = copied_columns
+        .iter()
+        .map(|c| quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut statements = vec![
+        FOREIGN_KEYS_OFF.to_string(),
+        format!("CREATE TABLE {staging} ({})", definitions.join(", ")),
+        format!("INSERT INTO {staging} ({columns}) SELECT {columns} FROM {table}"),
+        format!("DROP TABLE {table}"),
+        format!("ALTER TABLE {staging} RENAME TO {table}"),
+    ];
+    statements.extend(indexes.iter().cloned());
+    statements.join(";\n")
+}
+
+/// Statement that records an executed migration in the tracking table
+const RECORD_MIGRATION_SQL: &str = "INSERT INTO migrations (id, name, sql, down_sql, checksum, version, created_at, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+
+/// Split a script into its `;`-separated statements
+///
+/// `;` inside quotes or inside a `CREATE TRIGGER ... BEGIN ... END` body does
+/// not end a statement.
+pub(crate) fn split_statements(sql: &str) -> Vec<String> {
+    let in_trigger_body = |statement: &str| {
+        let upper = statement.trim_start().to_ascii_uppercase();
+        let words: Vec<&str> = upper.split_whitespace().take(3).collect();
+        let is_trigger = words.get(1) == Some(&"TRIGGER")
+            || (words.get(2) == Some(&"TRIGGER")
+                && matches!(words.get(1), Some(&"TEMP") | Some(&"TEMPORARY")));
+        is_trigger && words.first() == Some(&"CREATE") && !upper.trim_end().ends_with("END")
+    };
+
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in sql.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if matches!(c, '\'' | '"' | '`') => quote = Some(c),
+            None if c == ';' && !in_trigger_body(&current) => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            None => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+/// Whether a migration is among the executed ones, by id or by name
+///
+/// Matching names as well keeps rows recorded with the random ids of
+/// earlier versions from being applied again.
+fn is_recorded(executed: &[Migration], migration: &Migration) -> bool {
+    executed
+        .iter()
+        .any(|e| e.id == migration.id || e.name == migration.name)
+}
+
+/// Registered migrations compared with the ones recorded in the database
+///
+/// Returned by [`MigrationManager::status`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    /// Registered migrations that have been applied, as recorded
+    pub applied: Vec<Migration>,
+    /// Registered migrations that have not been applied, in run order
+    pub pending: Vec<Migration>,
+    /// Recorded migrations that are not registered, e.g. from another branch
+    pub unknown: Vec<Migration>,
+}
+
+impl MigrationStatus {
+    /// Whether every registered migration has been applied
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// A pending migration and the statements running it would execute
+///
+/// Returned by [`MigrationManager::plan`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedMigration {
+    pub migration: Migration,
+    pub statements: Vec<String>,
+}
+
 /// Migration manager for handling database schema changes
 ///
 /// The central component for managing database migrations. Handles initialization,
@@ -112,12 +530,42 @@ pub struct Migration {
 /// ```
 pub struct MigrationManager {
     db: Database,
+    runner_id: String,
+    lock_timeout: chrono::Duration,
+    allow_out_of_order: bool,
 }
 
 impl MigrationManager {
     /// Create a new migration manager
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            runner_id: uuid::Uuid::new_v4().to_string(),
+            lock_timeout: chrono::Duration::minutes(10),
+            allow_out_of_order: false,
+        }
+    }
+
+    /// Allow pending migrations older than the newest applied one to run
+    ///
+    /// By default [`run_migrations`](Self::run_migrations) rejects them, since
+    /// a migration merged from an old branch may assume a schema that later
+    /// migrations already changed.
+    pub fn allow_out_of_order(mut self, allow: bool) -> Self {
+        self.allow_out_of_order = allow;
+        self
+    }
+
+    /// Set how long a migration lock is honored before it is considered stale
+    ///
+    /// A runner that crashes while holding the lock blocks others only until
+    /// the lock is this old. Runners refresh the lock before each migration
+    /// they apply or roll back, so a long run keeps it. Defaults to 10
+    /// minutes.
+    pub fn with_lock_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.lock_timeout =
+            chrono::Duration::from_std(timeout).unwrap_or(chrono::Duration::minutes(10));
+        self
     }
 
     /// Initialize the migration table
@@ -127,6 +575,9 @@ impl MigrationManager {
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
                 sql TEXT NOT NULL,
+                down_sql TEXT,
+                checksum TEXT,
+                version INTEGER,
                 created_at TEXT NOT NULL,
                 executed_at TEXT
             )
@@ -135,15 +586,50 @@ impl MigrationManager {
         let params = vec![];
 
         self.db.execute(sql, params).await?;
+        self.db
+            .execute(
+                "CREATE TABLE IF NOT EXISTS migrations_lock (id INTEGER PRIMARY KEY, holder TEXT NOT NULL, acquired_at TEXT NOT NULL)",
+                vec![],
+            )
+            .await?;
+
+        // Tables created by earlier versions lack the newer columns
+        let columns = self
+            .db
+            .query_rows("PRAGMA table_info(migrations)", vec![])
+            .await?;
+        if !columns.is_empty() {
+            for (added, column_type) in [
+                ("down_sql", "TEXT"),
+                ("checksum", "TEXT"),
+                ("version", "INTEGER"),
+            ] {
+                let exists = columns.iter().any(
+                    |column| matches!(column.get("name"), Some(Value::Text(name)) if name == added),
+                );
+                if !exists {
+                    self.db
+                        .execute(
+                            &format!("ALTER TABLE migrations ADD COLUMN {added} {column_type}"),
+                            vec![],
+                        )
+                        .await?;
+                }
+            }
+        }
         Ok(())
     }
 
     /// Create a new migration
+    ///
+    /// The name is the migration's id, so it must be unique.
     pub fn create_migration(name: &str, sql: &str) -> Migration {
         Migration {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: name.to_string(),
             name: name.to_string(),
             sql: sql.to_string(),
+            down_sql: None,
+            version: Migration::version_from_name(name),
             created_at: Utc::now(),
             executed_at: None,
         }
@@ -159,16 +645,21 @@ impl MigrationManager {
 
         #[cfg(feature = "turso")]
         {
-            let sql =
-                "SELECT id, name, sql, created_at, executed_at FROM migrations ORDER BY created_at";
+            let sql = "SELECT id, name, sql, created_at, executed_at, down_sql, version FROM migrations ORDER BY version IS NULL, version, name";
             let mut rows = self.db.query(sql, vec![]).await?;
 
             let mut migrations = Vec::new();
             while let Some(row) = rows.next().await? {
+                let name: String = row.get(1)?;
                 let migration = Migration {
                     id: row.get(0)?,
-                    name: row.get(1)?,
+                    version: row
+                        .get::<Option<i64>>(6)
+                        .unwrap_or(None)
+                        .or_else(|| Migration::version_from_name(&name)),
+                    name,
                     sql: row.get(2)?,
+                    down_sql: row.get::<Option<String>>(5).unwrap_or(None),
                     created_at: DateTime::parse_from_rfc3339(
                         &row.get::<String>(3).unwrap_or_default(),
                     )
@@ -194,45 +685,877 @@ impl MigrationManager {
     }
 
     /// Execute a migration
+    ///
+    /// The SQL and the tracking row are written in one transaction that is
+    /// rolled back if either fails.
+    ///
+    /// Migrations containing [`FOREIGN_KEYS_OFF`], such as the table
+    /// rebuilds from [`diff_models`](Self::diff_models) and
+    /// [`templates`](crate::templates), follow SQLite's procedure for
+    /// changing a table: when foreign keys are enforced, they are turned off
+    /// before the transaction, every foreign key is checked before COMMIT
+    /// (rolling back on a violation), and enforcement is turned back on
+    /// afterwards. The same applies to the batches of
+    /// [`run_migrations`](Self::run_migrations) and to rollbacks. SQLite
+    /// ignores the pragma inside a transaction, so these migrations cannot
+    /// run inside a transaction the caller opened.
+    ///
+    /// A migration whose id or name is already recorded is skipped, so
+    /// calling this on every startup is safe. The migration runs under the
+    /// migration lock, and [`Error::Validation`] is returned when another
+    /// runner holds it.
     pub async fn execute_migration(&self, migration: &Migration) -> Result<(), Error> {
+        if !self.try_lock().await? {
+            return Err(Error::Validation(format!(
+                "Cannot execute migration {}: another runner holds the migration lock",
+                migration.name
+            )));
+        }
+
+        let result = async {
+            let executed = self.get_executed_migrations().await?;
+            if is_recorded(&executed, migration) {
+                return Ok(());
+            }
+            self.apply_all(std::slice::from_ref(migration)).await
+        }
+        .await;
+
+        let unlocked = self.unlock().await;
+        result.and(unlocked)
+    }
+
+    /// Apply migrations in order inside a single transaction
+    async fn apply_all(&self, migrations: &[Migration]) -> Result<(), Error> {
+        let enforced = self
+            .suspend_foreign_keys(migrations.iter().map(|m| m.sql.as_str()))
+            .await?;
+        let result = self.apply_in_transaction(migrations, enforced).await;
+        self.resume_foreign_keys(enforced, result).await
+    }
+
+    async fn apply_in_transaction(
+        &self,
+        migrations: &[Migration],
+        check_foreign_keys: bool,
+    ) -> Result<(), Error> {
         // Begin transaction
         self.db.execute("BEGIN", vec![]).await?;
 
-        // Execute the migration SQL
-        self.db.execute(&migration.sql, vec![]).await?;
+        for migration in migrations {
+            let applied = async {
+                self.refresh_lock().await?;
 
-        // Record the migration
-        let sql = r#"
-            INSERT INTO migrations (id, name, sql, created_at, executed_at)
-            VALUES (?, ?, ?, ?, ?)
-        "#;
+                // Execute the migration SQL
+                self.db.execute_batch(&migration.sql).await?;
+
+                // Record the migration
+                self.record_migration(migration).await
+            }
+            .await;
 
+            if let Err(e) = applied {
+                let _ = self.db.execute("ROLLBACK", vec![]).await;
+                return Err(Error::DatabaseError(format!(
+                    "Migration {} failed, no migrations were applied: {e}",
+                    migration.name
+                )));
+            }
+        }
+
+        if check_foreign_keys {
+            if let Err(e) = self.check_foreign_keys().await {
+                let _ = self.db.execute("ROLLBACK", vec![]).await;
+                return Err(e);
+            }
+        }
+
+        // Commit transaction; a deferred foreign key violation fails here and
+        // leaves the transaction open
+        if let Err(e) = self.db.execute("COMMIT", vec![]).await {
+            let _ = self.db.execute("ROLLBACK", vec![]).await;
+            let names: Vec<&str> = migrations.iter().map(|m| m.name.as_str()).collect();
+            return Err(Error::DatabaseError(format!(
+                "Migration {} failed to commit, no migrations were applied: {e}",
+                names.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Turn foreign key enforcement off if any script rebuilds a table,
+    /// returning whether it was on; must run outside a transaction
+    async fn suspend_foreign_keys<'a>(
+        &self,
+        scripts: impl IntoIterator<Item = &'a str>,
+    ) -> Result<bool, Error> {
+        if !scripts.into_iter().any(disables_foreign_keys) {
+            return Ok(false);
+        }
+        if self.db.in_transaction() {
+            return Err(Error::Validation(
+                "Table rebuilds turn foreign keys off and cannot run inside an open transaction"
+                    .to_string(),
+            ));
+        }
+        let rows = self.db.query_rows("PRAGMA foreign_keys", vec![]).await?;
+        let enforced = rows
+            .first()
+            .and_then(|row| row.values().next())
+            .is_some_and(|value| matches!(value, Value::Integer(n) if *n != 0));
+        if enforced {
+            self.db.execute(FOREIGN_KEYS_OFF, vec![]).await?;
+        }
+        Ok(enforced)
+    }
+
+    /// Turn foreign key enforcement back on after
+    /// [`suspend_foreign_keys`](Self::suspend_foreign_keys), keeping the
+    /// first error
+    async fn resume_foreign_keys<T>(
+        &self,
+        enforced: bool,
+        result: Result<T, Error>,
+    ) -> Result<T, Error> {
+        if !enforced {
+            return result;
+        }
+        let resumed = self.db.execute("PRAGMA foreign_keys = ON", vec![]).await;
+        let value = result?;
+        resumed?;
+        Ok(value)
+    }
+
+    /// Fail with [`Error::Validation`] if any row breaks a foreign key
+    ///
+    /// Uses `PRAGMA foreign_key_check` where the engine has it, and
+    /// otherwise looks for orphaned rows of every declared foreign key.
+    async fn check_foreign_keys(&self) -> Result<(), Error> {
+        let text = |row: &crate::Row, column: &str| match row.get(column) {
+            Some(Value::Text(value)) => value.clone(),
+            _ => String::new(),
+        };
+
+        let mut violations: Vec<String> = Vec::new();
+        match self.db.query_rows("PRAGMA foreign_key_check", vec![]).await {
+            Ok(rows) => {
+                for row in &rows {
+                    violations.push(format!("{} -> {}", text(row, "table"), text(row, "parent")));
+                }
+            }
+            // turso has no foreign_key_check
+            Err(_) => {
+                let tables = self
+                    .db
+                    .query_rows(
+                        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND sql IS NOT NULL",
+                        vec![],
+                    )
+                    .await?;
+                for table in &tables {
+                    let child = text(table, "name");
+                    for key in foreign_keys(&text(table, "sql")) {
+                        if self.has_orphans(&child, &key).await? {
+                            violations.push(format!("{child} -> {}", key.parent));
+                        }
+                    }
+                }
+            }
+        }
+
+        violations.dedup();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(format!(
+                "Foreign key check failed: {}",
+                violations.join(", ")
+            )))
+        }
+    }
+
+    /// Whether rows of `child` reference missing rows through `key`
+    async fn has_orphans(&self, child: &str, key: &ForeignKey) -> Result<bool, Error> {
+        let mut parent_columns = key.parent_columns.clone();
+        if parent_columns.is_empty() {
+            let mut primary_key: Vec<(i64, String)> = self
+                .db
+                .query_rows(
+                    &format!("PRAGMA table_info({})", quote_name(&key.parent)),
+                    vec![],
+                )
+                .await?
+                .iter()
+                .filter_map(|row| match (row.get("pk"), row.get("name")) {
+                    (Some(Value::Integer(pk)), Some(Value::Text(name))) if *pk > 0 => {
+                        Some((*pk, name.clone()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            primary_key.sort();
+            parent_columns = primary_key.into_iter().map(|(_, name)| name).collect();
+            if parent_columns.is_empty() {
+                parent_columns.push("rowid".to_string());
+            }
+        }
+
+        let present = key
+            .columns
+            .iter()
+            .map(|column| format!("child.{} IS NOT NULL", quote_name(column)))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let matched = key
+            .columns
+            .iter()
+            .zip(&parent_columns)
+            .map(|(column, parent_column)| {
+                format!(
+                    "parent.{} = child.{}",
+                    quote_name(parent_column),
+                    quote_name(column)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let sql = format!(
+            "SELECT 1 FROM {} AS child WHERE {present} AND NOT EXISTS \
+             (SELECT 1 FROM {} AS parent WHERE {matched}) LIMIT 1",
+            quote_name(child),
+            quote_name(&key.parent)
+        );
+        Ok(!self.db.query_rows(&sql, vec![]).await?.is_empty())
+    }
+
+    /// Insert the tracking row for a migration
+    async fn record_migration(&self, migration: &Migration) -> Result<(), Error> {
         self.db
             .execute(
-                sql,
+                RECORD_MIGRATION_SQL,
                 vec![
                     text_value(migration.id.clone()),
                     text_value(migration.name.clone()),
                     text_value(migration.sql.clone()),
+                    migration
+                        .down_sql
+                        .clone()
+                        .map(text_value)
+                        .unwrap_or_else(null_value),
+                    text_value(migration.checksum()),
+                    migration
+                        .version
+                        .map(crate::compat::integer_value)
+                        .unwrap_or_else(null_value),
                     text_value(migration.created_at.to_rfc3339()),
                     text_value(Utc::now().to_rfc3339()),
                 ],
             )
             .await?;
+        Ok(())
+    }
 
-        // Commit transaction
-        self.db.execute("COMMIT", vec![]).await?;
+    /// Mark migrations up to `version` as applied without running them
+    ///
+    /// For adopting the migration system on a database whose schema already
+    /// exists: every pending migration in `migrations` with a version at or
+    /// below `version` is recorded as executed (with its checksum and down
+    /// SQL), so [`run_migrations`](Self::run_migrations) only applies the
+    /// newer ones. Migrations without a version are left pending. Returns the
+    /// migrations that were marked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Migration, MigrationManager, Error};
+    ///
+    /// # async fn example(manager: &MigrationManager, migrations: Vec<Migration>) -> Result<(), Error> {
+    /// // The production database already matches migration 20240301000000
+    /// manager.baseline(&migrations, 20240301000000).await?;
+    /// manager.run_migrations(migrations).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn baseline(
+        &self,
+        migrations: &[Migration],
+        version: i64,
+    ) -> Result<Vec<Migration>, Error> {
+        if !self.try_lock().await? {
+            return Err(Error::Validation(
+                "Cannot baseline: another runner holds the migration lock".to_string(),
+            ));
+        }
 
-        Ok(())
+        let result = async {
+            let executed = self.get_executed_migrations().await?;
+            let marked: Vec<Migration> = migrations
+                .iter()
+                .filter(|m| m.version.is_some_and(|v| v <= version))
+                .filter(|m| m.executed_at.is_none() && !is_recorded(&executed, m))
+                .cloned()
+                .collect();
+            for migration in &marked {
+                self.record_migration(migration).await?;
+            }
+            Ok::<Vec<Migration>, Error>(marked)
+        }
+        .await;
+
+        let unlocked = self.unlock().await;
+        let marked = result?;
+        unlocked?;
+        Ok(marked)
+    }
+
+    /// Statements [`execute_migration`](Self::execute_migration) would run, without running them
+    ///
+    /// Includes the surrounding transaction and the insert into the tracking
+    /// table, whose values are bound as `?` parameters when executed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{MigrationBuilder, MigrationManager};
+    ///
+    /// # fn example(manager: &MigrationManager) {
+    /// let migration = MigrationBuilder::new("add_index")
+    ///     .up("CREATE INDEX idx_users_email ON users(email)")
+    ///     .build();
+    /// for statement in manager.execute_migration_dry_run(&migration) {
+    ///     println!("{statement};");
+    /// }
+    /// # }
+    /// ```
+    pub fn execute_migration_dry_run(&self, migration: &Migration) -> Vec<String> {
+        let mut statements = vec!["BEGIN".to_string()];
+        statements.extend(split_statements(&migration.sql));
+        statements.push(RECORD_MIGRATION_SQL.to_string());
+        statements.push("COMMIT".to_string());
+        statements
+    }
+
+    /// Pending migrations and the SQL [`run_migrations`](Self::run_migrations) would execute
+    ///
+    /// Reads the tracking table to decide what is pending but changes nothing,
+    /// so CI can print the plan for review.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Migration, MigrationManager, Error};
+    ///
+    /// # async fn example(manager: &MigrationManager, migrations: Vec<Migration>) -> Result<(), Error> {
+    /// for planned in manager.plan(&migrations).await? {
+    ///     println!("-- {}", planned.migration.name);
+    ///     for statement in &planned.statements {
+    ///         println!("{statement};");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan(&self, migrations: &[Migration]) -> Result<Vec<PlannedMigration>, Error> {
+        let pending = self.pending_of(migrations.to_vec()).await?;
+        Ok(pending
+            .into_iter()
+            .map(|migration| PlannedMigration {
+                statements: self.execute_migration_dry_run(&migration),
+                migration,
+            })
+            .collect())
     }
 
     /// Rollback a migration
+    ///
+    /// Runs the migration's stored down SQL and removes its tracking row in a
+    /// single transaction, so a failing down script leaves both the schema and
+    /// the history untouched. Migrations recorded without down SQL cannot be
+    /// rolled back and return [`Error::Validation`].
     pub async fn rollback_migration(&self, migration_id: &str) -> Result<(), Error> {
-        let sql = "DELETE FROM migrations WHERE id = ?";
-        self.db
-            .execute(sql, vec![text_value(migration_id.to_string())])
+        let rows = self
+            .db
+            .query_rows(
+                "SELECT name, down_sql FROM migrations WHERE id = ?",
+                vec![text_value(migration_id.to_string())],
+            )
             .await?;
-        Ok(())
+        let row = rows
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NotFound(format!("Migration {migration_id} not found")))?;
+        let down_sql = match row.get("down_sql") {
+            Some(Value::Text(sql)) if !sql.trim().is_empty() => sql.clone(),
+            _ => {
+                let name = match row.get("name") {
+                    Some(Value::Text(name)) => name.as_str(),
+                    _ => migration_id,
+                };
+                return Err(Error::Validation(format!(
+                    "Migration {name} has no down SQL and cannot be rolled back"
+                )));
+            }
+        };
+
+        let enforced = self.suspend_foreign_keys([down_sql.as_str()]).await?;
+        let result = async {
+            self.db.execute("BEGIN", vec![]).await?;
+
+            let result = async {
+                self.db.execute_batch(&down_sql).await?;
+                self.db
+                    .execute(
+                        "DELETE FROM migrations WHERE id = ?",
+                        vec![text_value(migration_id.to_string())],
+                    )
+                    .await?;
+                if enforced {
+                    self.check_foreign_keys().await?;
+                }
+                self.db.execute("COMMIT", vec![]).await?;
+                Ok::<(), Error>(())
+            }
+            .await;
+
+            if result.is_err() {
+                let _ = self.db.execute("ROLLBACK", vec![]).await;
+            }
+            result
+        }
+        .await;
+        self.resume_foreign_keys(enforced, result).await
+    }
+
+    /// Roll back the last `steps` executed migrations, newest first
+    ///
+    /// Migrations are ordered by version, then by when they ran. Every
+    /// selected migration must have down SQL; this is checked before
+    /// anything runs. Each migration is reversed in its own transaction, so if
+    /// a down script fails the migrations already rolled back stay rolled back
+    /// and the error is returned. Returns the migrations that were reversed.
+    ///
+    /// The rollback holds the migration lock and returns
+    /// [`Error::Validation`] when another runner holds it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{MigrationManager, Error};
+    ///
+    /// # async fn example(manager: &MigrationManager) -> Result<(), Error> {
+    /// // Revert the last deploy's two migrations
+    /// let reverted = manager.rollback(2).await?;
+    /// for migration in reverted {
+    ///     println!("rolled back {}", migration.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rollback(&self, steps: usize) -> Result<Vec<Migration>, Error> {
+        self.rollback_locked(|executed| Ok(executed.into_iter().take(steps).collect()))
+            .await
+    }
+
+    /// Roll back every migration executed after `version_or_name`
+    ///
+    /// The target is matched against migration ids, names, and versions,
+    /// such as `20240101000000`, and stays applied. Returns [`Error::NotFound`] when no executed migration
+    /// matches. Rollback semantics are the same as [`rollback`](Self::rollback).
+    pub async fn rollback_to(&self, version_or_name: &str) -> Result<Vec<Migration>, Error> {
+        let version = version_or_name.parse::<i64>().ok();
+        self.rollback_locked(|executed| {
+            let position = executed
+                .iter()
+                .position(|m| {
+                    m.id == version_or_name
+                        || m.name == version_or_name
+                        || (version.is_some() && m.version == version)
+                })
+                .ok_or_else(|| {
+                    Error::NotFound(format!("Executed migration {version_or_name} not found"))
+                })?;
+            Ok(executed.into_iter().take(position).collect())
+        })
+        .await
+    }
+
+    /// Under the migration lock, roll back the migrations `select` picks from
+    /// the executed ones, newest first
+    async fn rollback_locked(
+        &self,
+        select: impl FnOnce(Vec<Migration>) -> Result<Vec<Migration>, Error>,
+    ) -> Result<Vec<Migration>, Error> {
+        if !self.try_lock().await? {
+            return Err(Error::Validation(
+                "Cannot roll back: another runner holds the migration lock".to_string(),
+            ));
+        }
+
+        let result = async {
+            let targets = select(self.executed_newest_first().await?)?;
+            self.rollback_all(targets).await
+        }
+        .await;
+
+        let unlocked = self.unlock().await;
+        let reverted = result?;
+        unlocked?;
+        Ok(reverted)
+    }
+
+    /// Executed migrations by descending version, then by when they ran;
+    /// `executed_at` has one-second resolution, so a batch shares it
+    async fn executed_newest_first(&self) -> Result<Vec<Migration>, Error> {
+        let mut executed = self.get_executed_migrations().await?;
+        executed.sort_by(|a, b| {
+            b.version
+                .cmp(&a.version)
+                .then_with(|| b.executed_at.cmp(&a.executed_at))
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
+        Ok(executed)
+    }
+
+    async fn rollback_all(&self, targets: Vec<Migration>) -> Result<Vec<Migration>, Error> {
+        if let Some(irreversible) = targets.iter().find(|m| {
+            m.down_sql
+                .as_deref()
+                .is_none_or(|sql| sql.trim().is_empty())
+        }) {
+            return Err(Error::Validation(format!(
+                "Migration {} has no down SQL and cannot be rolled back",
+                irreversible.name
+            )));
+        }
+
+        for migration in &targets {
+            self.refresh_lock().await?;
+            self.rollback_migration(&migration.id).await?;
+        }
+        Ok(targets)
+    }
+
+    /// Check applied migrations against their current definitions
+    ///
+    /// Each migration is matched to its tracking row by id, falling back to
+    /// name, and its [`checksum`](Migration::checksum) is compared with the one
+    /// recorded when it ran. Returns [`Error::Validation`] naming every
+    /// migration whose SQL changed after being applied. Migrations that have
+    /// not run yet, and rows recorded before checksums were stored, are
+    /// skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Migration, MigrationManager, Error};
+    ///
+    /// # async fn example(manager: &MigrationManager, migrations: Vec<Migration>) -> Result<(), Error> {
+    /// // e.g. `embed_migrations!("migrations/")`
+    /// manager.verify(&migrations).await?;
+    /// manager.run_migrations(migrations).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify(&self, migrations: &[Migration]) -> Result<(), Error> {
+        let rows = self
+            .db
+            .query_rows(
+                "SELECT id, name, checksum FROM migrations WHERE executed_at IS NOT NULL",
+                vec![],
+            )
+            .await?;
+        let text = |row: &crate::Row, column: &str| match row.get(column) {
+            Some(Value::Text(value)) => Some(value.clone()),
+            _ => None,
+        };
+
+        let mut drifted = Vec::new();
+        for migration in migrations {
+            let recorded = rows
+                .iter()
+                .find(|row| text(row, "id").as_deref() == Some(migration.id.as_str()))
+                .or_else(|| {
+                    rows.iter()
+                        .find(|row| text(row, "name").as_deref() == Some(migration.name.as_str()))
+                })
+                .and_then(|row| text(row, "checksum"));
+            if let Some(checksum) = recorded {
+                if checksum != migration.checksum() {
+                    drifted.push(migration.name.clone());
+                }
+            }
+        }
+
+        if drifted.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(format!(
+                "Applied migrations have changed since they ran: {}",
+                drifted.join(", ")
+            )))
+        }
+    }
+
+    /// Compare models with the live schema and build the migrations that reconcile them
+    ///
+    /// Returns one migration per table that differs, in the order given:
+    ///
+    /// - Missing tables get their `CREATE TABLE` statement.
+    /// - New columns that SQLite can add in place become
+    ///   `ALTER TABLE ... ADD COLUMN`.
+    /// - Anything `ALTER TABLE` cannot express — removed columns, changed
+    ///   types, `NOT NULL` or primary key changes, and new `UNIQUE`,
+    ///   `PRIMARY KEY`, or `NOT NULL` columns without a default — rebuilds
+    ///   the table: create a copy with the new definition, copy shared
+    ///   columns, drop the original, rename the copy, and recreate indexes.
+    ///
+    /// Migrations are named like `20240101_120000_create_table_users_01`,
+    /// with the timestamp advanced one second per migration so their
+    /// versions stay distinct and ordered.
+    ///
+    /// Every migration carries down SQL that restores the previous table
+    /// definition. Column defaults are not compared. The migrations are only
+    /// built, not run; review and execute them with
+    /// [`execute_migration`](Self::execute_migration).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{generate_migration_diff, MigrationManager, Error};
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct User { id: Option<i64>, name: String }
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct Post { id: Option<i64>, title: String }
+    ///
+    /// # async fn example(manager: &MigrationManager) -> Result<(), Error> {
+    /// let migrations = generate_migration_diff!(manager, User, Post).await?;
+    /// for migration in &migrations {
+    ///     println!("{}:\n{}", migration.name, migration.sql);
+    /// }
+    /// manager.run_migrations(migrations).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn diff_models(&self, models: &[ModelSchema]) -> Result<Vec<Migration>, Error> {
+        let started = Utc::now();
+        let mut migrations = Vec::new();
+        for model in models {
+            // One second per migration keeps their versions distinct
+            let sequence = migrations.len() + 1;
+            let at = started + chrono::Duration::seconds(sequence as i64 - 1);
+            let name = |description: &str| {
+                Self::migration_name_at(at, &format!("{description}_{sequence:02}"))
+            };
+            if let Some(migration) = self.diff_model(model, name).await? {
+                migrations.push(migration);
+            }
+        }
+        Ok(migrations)
+    }
+
+    async fn diff_model(
+        &self,
+        model: &ModelSchema,
+        name: impl Fn(&str) -> String,
+    ) -> Result<Option<Migration>, Error> {
+        let table = model.table.as_str();
+        let text = |row: &crate::Row, column: &str| match row.get(column) {
+            Some(Value::Text(value)) => value.clone(),
+            _ => String::new(),
+        };
+
+        let existing = self
+            .db
+            .query_rows(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+                vec![text_value(table.to_string())],
+            )
+            .await?;
+        let Some(existing) = existing.first() else {
+            let migration = MigrationBuilder::new(&name(&format!("create_table_{table}")))
+                .up(&model.create_sql)
+                .down(&format!("DROP TABLE {}", quote_identifier(table)))
+                .build();
+            return Ok(Some(migration));
+        };
+        let live_definitions = split_definitions(&text(existing, "sql"));
+
+        let live_columns: Vec<(String, ColumnShape)> = self
+            .db
+            .query_rows(
+                &format!("PRAGMA table_info({})", quote_identifier(table)),
+                vec![],
+            )
+            .await?
+            .iter()
+            .map(|row| {
+                let flag =
+                    |column: &str| matches!(row.get(column), Some(Value::Integer(n)) if *n != 0);
+                (
+                    text(row, "name"),
+                    ColumnShape {
+                        data_type: text(row, "type").to_ascii_uppercase(),
+                        not_null: flag("notnull"),
+                        primary_key: flag("pk"),
+                    },
+                )
+            })
+            .collect();
+        let expected: Vec<(String, ColumnShape, &String)> = model
+            .definitions
+            .iter()
+            .filter_map(|definition| {
+                parse_definition(definition).map(|(name, shape)| (name, shape, definition))
+            })
+            .collect();
+
+        let live_shape = |name: &str| {
+            live_columns
+                .iter()
+                .find(|(live, _)| live.eq_ignore_ascii_case(name))
+                .map(|(_, shape)| shape)
+        };
+        let added: Vec<&(String, ColumnShape, &String)> = expected
+            .iter()
+            .filter(|(name, _, _)| live_shape(name).is_none())
+            .collect();
+        let removed: Vec<&String> = live_columns
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| {
+                !expected
+                    .iter()
+                    .any(|(e, _, _)| e.eq_ignore_ascii_case(name))
+            })
+            .collect();
+        let changed = expected
+            .iter()
+            .any(|(name, shape, _)| live_shape(name).is_some_and(|live| live != shape));
+
+        if added.is_empty() && removed.is_empty() && !changed {
+            return Ok(None);
+        }
+
+        let name = name(&format!("alter_table_{table}"));
+        if removed.is_empty()
+            && !changed
+            && added
+                .iter()
+                .all(|(_, _, definition)| can_add_column(definition))
+        {
+            let up = added
+                .iter()
+                .map(|(_, _, definition)| {
+                    format!(
+                        "ALTER TABLE {} ADD COLUMN {definition}",
+                        quote_identifier(table)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(";\n");
+            let down = added
+                .iter()
+                .rev()
+                .map(|(column, _, _)| {
+                    format!(
+                        "ALTER TABLE {} DROP COLUMN {}",
+                        quote_identifier(table),
+                        quote_identifier(column)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(";\n");
+            return Ok(Some(
+                MigrationBuilder::new(&name).up(&up).down(&down).build(),
+            ));
+        }
+
+        let indexes: Vec<String> = self
+            .db
+            .query_rows(
+                "SELECT sql FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND sql IS NOT NULL",
+                vec![text_value(table.to_string())],
+            )
+            .await?
+            .iter()
+            .map(|row| text(row, "sql"))
+            .collect();
+        let kept_indexes: Vec<String> = indexes
+            .iter()
+            .filter(|sql| {
+                !removed.iter().any(|column| {
+                    sql.split(|c: char| !c.is_alphanumeric() && c != '_')
+                        .any(|token| token.eq_ignore_ascii_case(column))
+                })
+            })
+            .cloned()
+            .collect();
+        let shared: Vec<String> = expected
+            .iter()
+            .filter(|(name, _, _)| live_shape(name).is_some())
+            .map(|(name, _, _)| name.clone())
+            .collect();
+
+        let up = rebuild_table_sql(table, &model.definitions, &shared, &kept_indexes);
+        let down = rebuild_table_sql(table, &live_definitions, &shared, &indexes);
+        Ok(Some(
+            MigrationBuilder::new(&name).up(&up).down(&down).build(),
+        ))
+    }
+
+    /// Export the application schema as `CREATE` statements
+    ///
+    /// Same as [`Database::dump_schema`] but without the migration tracking
+    /// tables, which [`init`](Self::init) recreates.
+    pub async fn dump_schema(&self) -> Result<String, Error> {
+        self.db
+            .dump_schema_excluding(&["migrations", "migrations_lock"])
+            .await
+    }
+
+    /// Compare the application's migrations with the database
+    ///
+    /// Unlike [`get_pending_migrations`](Self::get_pending_migrations), which
+    /// can only see rows already in the tracking table, this reports
+    /// registered migrations that were never applied, plus recorded
+    /// migrations the application no longer knows about.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Migration, MigrationManager, Error};
+    ///
+    /// # async fn example(manager: &MigrationManager, migrations: Vec<Migration>) -> Result<(), Error> {
+    /// let status = manager.status(&migrations).await?;
+    /// if !status.is_up_to_date() {
+    ///     println!("{} pending migrations", status.pending.len());
+    /// }
+    /// for unknown in &status.unknown {
+    ///     println!("applied but not registered: {}", unknown.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&self, registered: &[Migration]) -> Result<MigrationStatus, Error> {
+        let executed = self.get_executed_migrations().await?;
+
+        let (applied, unknown): (Vec<Migration>, Vec<Migration>) = executed
+            .iter()
+            .cloned()
+            .partition(|recorded| is_recorded(registered, recorded));
+        let mut pending: Vec<Migration> = registered
+            .iter()
+            .filter(|m| !is_recorded(&executed, m))
+            .cloned()
+            .collect();
+        pending.sort_by_key(|m| m.version.unwrap_or(i64::MAX));
+
+        Ok(MigrationStatus {
+            applied,
+            pending,
+            unknown,
+        })
     }
 
     /// Get pending migrations (not yet executed)
@@ -254,18 +1577,142 @@ impl MigrationManager {
     }
 
     /// Run all pending migrations
+    ///
+    /// Migrations marked as executed, or whose id or name is already recorded
+    /// in the migrations table, are skipped, so this can run on every
+    /// application startup.
+    ///
+    /// The batch is all-or-nothing: pending migrations run in one
+    /// transaction, and if any fails the whole batch is rolled back and the
+    /// error names the failing migration.
+    ///
+    /// The run holds the migration lock, so when several instances start at
+    /// once only one applies migrations. The others log a warning and return
+    /// without running anything; use [`try_lock`](Self::try_lock) directly to
+    /// wait or fail instead.
     pub async fn run_migrations(&self, migrations: Vec<Migration>) -> Result<(), Error> {
-        for migration in migrations {
-            if let Some(_executed_at) = migration.executed_at {
-                continue;
-            }
+        if !self.try_lock().await? {
+            log::warn!("Skipping migrations: another runner holds the migration lock");
+            return Ok(());
+        }
 
-            self.execute_migration(&migration).await?;
+        let result = async {
+            let pending = self.pending_of(migrations).await?;
+            if pending.is_empty() {
+                return Ok(());
+            }
+            self.apply_all(&pending).await
         }
+        .await;
+
+        let unlocked = self.unlock().await;
+        result.and(unlocked)
+    }
 
+    /// Take the migration lock for this manager
+    ///
+    /// Returns `false` when another runner holds a lock younger than the
+    /// [lock timeout](Self::with_lock_timeout). Taking a lock this manager
+    /// already holds succeeds. Requires [`init`](Self::init).
+    pub async fn try_lock(&self) -> Result<bool, Error> {
+        let now = Utc::now();
+        let stale_before = now - self.lock_timeout;
+        self.db
+            .execute(
+                "DELETE FROM migrations_lock WHERE acquired_at < ?",
+                vec![text_value(stale_before.to_rfc3339())],
+            )
+            .await?;
+        self.db
+            .execute(
+                "INSERT OR IGNORE INTO migrations_lock (id, holder, acquired_at) VALUES (1, ?, ?)",
+                vec![
+                    text_value(self.runner_id.clone()),
+                    text_value(now.to_rfc3339()),
+                ],
+            )
+            .await?;
+
+        let rows = self
+            .db
+            .query_rows("SELECT holder FROM migrations_lock WHERE id = 1", vec![])
+            .await?;
+        Ok(rows.first().and_then(|row| row.get("holder"))
+            == Some(&Value::Text(self.runner_id.clone())))
+    }
+
+    /// Restart the lock timeout of a lock this manager holds, so a long run of
+    /// migrations keeps the lock
+    async fn refresh_lock(&self) -> Result<(), Error> {
+        self.db
+            .execute(
+                "UPDATE migrations_lock SET acquired_at = ? WHERE id = 1 AND holder = ?",
+                vec![
+                    text_value(Utc::now().to_rfc3339()),
+                    text_value(self.runner_id.clone()),
+                ],
+            )
+            .await?;
         Ok(())
     }
 
+    /// Release the migration lock if this manager holds it
+    pub async fn unlock(&self) -> Result<(), Error> {
+        self.db
+            .execute(
+                "DELETE FROM migrations_lock WHERE id = 1 AND holder = ?",
+                vec![text_value(self.runner_id.clone())],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// The given migrations that have not been executed yet, in version order
+    ///
+    /// Versioned migrations run in ascending version order; migrations
+    /// without a version keep their relative order and run last. Fails on
+    /// duplicate versions and, unless allowed, on pending migrations older
+    /// than the newest applied one.
+    async fn pending_of(&self, migrations: Vec<Migration>) -> Result<Vec<Migration>, Error> {
+        let executed = self.get_executed_migrations().await?;
+
+        let mut seen = std::collections::HashMap::new();
+        for migration in &migrations {
+            if let Some(version) = migration.version {
+                if let Some(other) = seen.insert(version, &migration.name) {
+                    if *other != migration.name {
+                        return Err(Error::Validation(format!(
+                            "Migrations {other} and {} share version {version}",
+                            migration.name
+                        )));
+                    }
+                }
+            }
+        }
+
+        let mut pending: Vec<Migration> = migrations
+            .into_iter()
+            .filter(|m| m.executed_at.is_none() && !is_recorded(&executed, m))
+            .collect();
+        pending.sort_by_key(|m| m.version.unwrap_or(i64::MAX));
+
+        let newest = executed.iter().filter_map(|m| m.version).max();
+        if let (Some(newest), false) = (newest, self.allow_out_of_order) {
+            let stale = pending
+                .iter()
+                .find(|m| m.version.is_some_and(|version| version < newest));
+            if let Some(stale) = stale {
+                return Err(Error::Validation(format!(
+                    "Migration {} (version {}) is older than the newest applied version {newest}",
+                    stale.name,
+                    stale.version.unwrap_or_default()
+                )));
+            }
+        }
+
+        Ok(pending)
+    }
+
     /// Create a migration from a file
     pub async fn create_migration_from_file(
         name: &str,
@@ -277,9 +1724,69 @@ impl MigrationManager {
         Ok(Self::create_migration(name, &sql))
     }
 
+    /// Load every migration in a directory at runtime
+    ///
+    /// Uses the same layouts as `embed_migrations!` (`NAME.sql`,
+    /// `NAME.up.sql` with `NAME.down.sql`, or `NAME/up.sql` with
+    /// `NAME/down.sql`) and the same name-as-id identity, sorted by name.
+    pub fn load_migrations_dir(dir: &str) -> Result<Vec<Migration>, Error> {
+        use std::collections::BTreeMap;
+        use std::path::PathBuf;
+
+        let read_error =
+            |e: std::io::Error| Error::DatabaseError(format!("Failed to read migrations: {e}"));
+        let mut ups: BTreeMap<String, PathBuf> = BTreeMap::new();
+        let mut downs: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for entry in std::fs::read_dir(dir).map_err(read_error)? {
+            let path = entry.map_err(read_error)?.path();
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+
+            if path.is_dir() {
+                if path.join("up.sql").is_file() {
+                    ups.insert(file_name.clone(), path.join("up.sql"));
+                }
+                if path.join("down.sql").is_file() {
+                    downs.insert(file_name, path.join("down.sql"));
+                }
+            } else if let Some(name) = file_name.strip_suffix(".down.sql") {
+                downs.insert(name.to_string(), path);
+            } else if let Some(name) = file_name.strip_suffix(".up.sql") {
+                ups.insert(name.to_string(), path);
+            } else if let Some(name) = file_name.strip_suffix(".sql") {
+                ups.insert(name.to_string(), path);
+            }
+        }
+
+        if let Some(orphan) = downs.keys().find(|name| !ups.contains_key(*name)) {
+            return Err(Error::Validation(format!(
+                "Migration {orphan} has down SQL but no up SQL"
+            )));
+        }
+
+        ups.iter()
+            .map(|(name, up)| {
+                let up_sql = std::fs::read_to_string(up).map_err(read_error)?;
+                let down_sql = downs
+                    .get(name)
+                    .map(std::fs::read_to_string)
+                    .transpose()
+                    .map_err(read_error)?;
+                Ok(Migration::embedded(name, &up_sql, down_sql.as_deref()))
+            })
+            .collect()
+    }
+
     /// Generate a migration name from a description
     pub fn generate_migration_name(description: &str) -> String {
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        Self::migration_name_at(Utc::now(), description)
+    }
+
+    fn migration_name_at(at: DateTime<Utc>, description: &str) -> String {
+        let timestamp = at.format("%Y%m%d_%H%M%S");
         let sanitized_description = description
             .to_lowercase()
             .replace(" ", "_")
@@ -314,6 +1821,7 @@ pub struct MigrationBuilder {
     name: String,
     up_sql: String,
     down_sql: Option<String>,
+    version: Option<i64>,
 }
 
 impl MigrationBuilder {
@@ -323,6 +1831,7 @@ impl MigrationBuilder {
             name: name.to_string(),
             up_sql: String::new(),
             down_sql: None,
+            version: None,
         }
     }
 
@@ -338,12 +1847,25 @@ impl MigrationBuilder {
         self
     }
 
+    /// Set an explicit version instead of reading it from the name
+    pub fn version(mut self, version: i64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
     /// Build the migration
+    ///
+    /// The name is the migration's id, so building the same migration on
+    /// every startup is recognized as already applied.
     pub fn build(self) -> Migration {
         Migration {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: self.name.clone(),
+            version: self
+                .version
+                .or_else(|| Migration::version_from_name(&self.name)),
             name: self.name,
             sql: self.up_sql,
+            down_sql: self.down_sql,
             created_at: Utc::now(),
             executed_at: None,
         }
@@ -379,31 +1901,39 @@ pub mod templates {
 
     /// Create a table migration
     pub fn create_table(table_name: &str, columns: &[(&str, &str)]) -> Migration {
+        let table = quote_identifier(table_name);
         let column_definitions = columns
             .iter()
-            .map(|(name, definition)| format!("{name} {definition}"))
+            .map(|(name, definition)| format!("{} {definition}", quote_identifier(name)))
             .collect::<Vec<_>>()
             .join(", ");
 
-        let sql = format!("CREATE TABLE {table_name} ({column_definitions})");
+        let sql = format!("CREATE TABLE {table} ({column_definitions})");
 
         MigrationBuilder::new(&format!("create_table_{table_name}"))
             .up(&sql)
+            .down(&format!("DROP TABLE {table}"))
             .build()
     }
 
     /// Add column migration
     pub fn add_column(table_name: &str, column_name: &str, definition: &str) -> Migration {
-        let sql = format!("ALTER TABLE {table_name} ADD COLUMN {column_name} {definition}");
+        let (table, column) = (quote_identifier(table_name), quote_identifier(column_name));
+        let sql = format!("ALTER TABLE {table} ADD COLUMN {column} {definition}");
 
         MigrationBuilder::new(&format!("add_column_{table_name}_{column_name}"))
             .up(&sql)
+            .down(&format!("ALTER TABLE {table} DROP COLUMN {column}"))
             .build()
     }
 
     /// Drop column migration
     pub fn drop_column(table_name: &str, column_name: &str) -> Migration {
-        let sql = format!("ALTER TABLE {table_name} DROP COLUMN {column_name}");
+        let sql = format!(
+            "ALTER TABLE {} DROP COLUMN {}",
+            quote_identifier(table_name),
+            quote_identifier(column_name)
+        );
 
         MigrationBuilder::new(&format!("drop_column_{table_name}_{column_name}"))
             .up(&sql)
@@ -412,20 +1942,240 @@ pub mod templates {
 
     /// Create index migration
     pub fn create_index(index_name: &str, table_name: &str, columns: &[&str]) -> Migration {
-        let column_list = columns.join(", ");
-        let sql = format!("CREATE INDEX {index_name} ON {table_name} ({column_list})");
+        let index = quote_identifier(index_name);
+        let column_list = columns
+            .iter()
+            .map(|c| quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "CREATE INDEX {index} ON {} ({column_list})",
+            quote_identifier(table_name)
+        );
 
         MigrationBuilder::new(&format!("create_index_{index_name}"))
             .up(&sql)
+            .down(&format!("DROP INDEX {index}"))
             .build()
     }
 
     /// Drop index migration
     pub fn drop_index(index_name: &str) -> Migration {
-        let sql = format!("DROP INDEX {index_name}");
+        let sql = format!("DROP INDEX {}", quote_identifier(index_name));
 
         MigrationBuilder::new(&format!("drop_index_{index_name}"))
             .up(&sql)
             .build()
     }
+
+    /// Rename column migration
+    pub fn rename_column(table_name: &str, old_name: &str, new_name: &str) -> Migration {
+        let table = quote_identifier(table_name);
+        let (old, new) = (quote_identifier(old_name), quote_identifier(new_name));
+        MigrationBuilder::new(&format!(
+            "rename_column_{table_name}_{old_name}_to_{new_name}"
+        ))
+        .up(&format!("ALTER TABLE {table} RENAME COLUMN {old} TO {new}"))
+        .down(&format!("ALTER TABLE {table} RENAME COLUMN {new} TO {old}"))
+        .build()
+    }
+
+    /// Rename table migration
+    pub fn rename_table(old_name: &str, new_name: &str) -> Migration {
+        let (old, new) = (quote_identifier(old_name), quote_identifier(new_name));
+        MigrationBuilder::new(&format!("rename_table_{old_name}_to_{new_name}"))
+            .up(&format!("ALTER TABLE {old} RENAME TO {new}"))
+            .down(&format!("ALTER TABLE {new} RENAME TO {old}"))
+            .build()
+    }
+
+    /// Add a foreign key to an existing column by rebuilding the table
+    ///
+    /// SQLite cannot add constraints with `ALTER TABLE`, so this uses
+    /// [`rebuild_table`] with the table's full current `columns`, copying every
+    /// column and restoring `indexes` afterwards. Existing rows must already
+    /// satisfy the constraint when foreign keys are enforced. The down SQL
+    /// rebuilds the table without the constraint.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::templates;
+    ///
+    /// let migration = templates::add_foreign_key(
+    ///     "posts",
+    ///     &[("id", "INTEGER PRIMARY KEY"), ("user_id", "INTEGER NOT NULL"), ("title", "TEXT")],
+    ///     "user_id",
+    ///     "users",
+    ///     "id",
+    ///     &["CREATE INDEX idx_posts_user_id ON posts (user_id)"],
+    /// );
+    /// assert!(migration.sql.contains(r#"FOREIGN KEY ("user_id") REFERENCES "users" ("id")"#));
+    /// ```
+    pub fn add_foreign_key(
+        table_name: &str,
+        columns: &[(&str, &str)],
+        column: &str,
+        references_table: &str,
+        references_column: &str,
+        indexes: &[&str],
+    ) -> Migration {
+        let constraint = format!(
+            "FOREIGN KEY ({}) REFERENCES {} ({})",
+            quote_identifier(column),
+            quote_identifier(references_table),
+            quote_identifier(references_column)
+        );
+        let names: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+        let with_key = rebuild_table(table_name, columns, &[&constraint], &names, indexes);
+        let without_key = rebuild_table(table_name, columns, &[], &names, indexes);
+
+        MigrationBuilder::new(&format!("add_foreign_key_{table_name}_{column}"))
+            .up(&with_key.sql)
+            .down(&without_key.sql)
+            .build()
+    }
+
+    /// Create trigger migration
+    ///
+    /// `timing` is the trigger time and event, such as `AFTER INSERT` or
+    /// `BEFORE UPDATE OF status`; `body` holds the statements run for each
+    /// row, which may refer to `NEW` and `OLD`. Triggers run on SQLite and
+    /// Turso Cloud; the local turso engine only enables them experimentally.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::templates;
+    ///
+    /// let migration = templates::create_trigger(
+    ///     "log_user_delete",
+    ///     "users",
+    ///     "AFTER DELETE",
+    ///     "INSERT INTO audit_log (user_id, action) VALUES (OLD.id, 'delete')",
+    /// );
+    /// assert_eq!(migration.down_sql.as_deref(), Some(r#"DROP TRIGGER IF EXISTS "log_user_delete""#));
+    /// ```
+    pub fn create_trigger(
+        trigger_name: &str,
+        table_name: &str,
+        timing: &str,
+        body: &str,
+    ) -> Migration {
+        let body = body.trim().trim_end_matches(';');
+        let trigger = quote_identifier(trigger_name);
+        let sql = format!(
+            "CREATE TRIGGER {trigger} {timing} ON {} FOR EACH ROW BEGIN {body}; END",
+            quote_identifier(table_name)
+        );
+
+        MigrationBuilder::new(&format!("create_trigger_{trigger_name}"))
+            .up(&sql)
+            .down(&format!("DROP TRIGGER IF EXISTS {trigger}"))
+            .build()
+    }
+
+    /// Drop trigger migration
+    pub fn drop_trigger(trigger_name: &str) -> Migration {
+        let sql = format!("DROP TRIGGER {}", quote_identifier(trigger_name));
+
+        MigrationBuilder::new(&format!("drop_trigger_{trigger_name}"))
+            .up(&sql)
+            .build()
+    }
+
+    /// Trigger that sets a timestamp column whenever a row is updated
+    ///
+    /// The column is set to the current UTC time as RFC 3339 text
+    /// (`2024-01-01T12:00:00.000Z`), matching how `DateTime<Utc>` values are
+    /// stored. Updates that set the column themselves are left alone, which
+    /// also keeps the trigger from re-firing on its own update.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::templates;
+    ///
+    /// let migration = templates::updated_at_trigger("posts", "updated_at");
+    /// assert_eq!(migration.name, "create_trigger_posts_touch_updated_at");
+    /// ```
+    pub fn updated_at_trigger(table_name: &str, column_name: &str) -> Migration {
+        let trigger_name = format!("{table_name}_touch_{column_name}");
+        let trigger = quote_identifier(&trigger_name);
+        let (table, column) = (quote_identifier(table_name), quote_identifier(column_name));
+        let sql = format!(
+            "CREATE TRIGGER {trigger} AFTER UPDATE ON {table} FOR EACH ROW \
+             WHEN NEW.{column} IS OLD.{column} \
+             BEGIN UPDATE {table} SET {column} = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') \
+             WHERE rowid = NEW.rowid; END"
+        );
+
+        MigrationBuilder::new(&format!("create_trigger_{trigger_name}"))
+            .up(&sql)
+            .down(&format!("DROP TRIGGER IF EXISTS {trigger}"))
+            .build()
+    }
+
+    /// Rebuild a table with a new definition, for changes `ALTER TABLE` cannot make
+    ///
+    /// Generates SQLite's documented procedure: create `{table}__rebuild` with
+    /// the new `columns` and table `constraints` (such as `FOREIGN KEY` or
+    /// composite `UNIQUE` clauses), copy `copy_columns` from the old table,
+    /// drop it, rename the new table into place, then run `indexes` — the
+    /// `CREATE INDEX` statements to restore, since dropping a table drops its
+    /// indexes. Foreign keys in other tables that reference the table by name
+    /// keep working once the rename completes. The script starts with
+    /// [`FOREIGN_KEYS_OFF`](crate::migrations::FOREIGN_KEYS_OFF), so the
+    /// manager suspends enforcement and checks every foreign key before
+    /// committing; run it through a [`MigrationManager`], not inside a
+    /// transaction of your own.
+    ///
+    /// The previous definition is not known here, so the migration has no
+    /// down SQL; add one with a second `rebuild_table` if it must be
+    /// reversible.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::templates;
+    ///
+    /// // Make `email` NOT NULL, which ALTER TABLE cannot do
+    /// let migration = templates::rebuild_table(
+    ///     "users",
+    ///     &[
+    ///         ("id", "INTEGER PRIMARY KEY AUTOINCREMENT"),
+    ///         ("email", "TEXT NOT NULL"),
+    ///         ("team_id", "INTEGER"),
+    ///     ],
+    ///     &["FOREIGN KEY (team_id) REFERENCES teams (id)"],
+    ///     &["id", "email", "team_id"],
+    ///     &["CREATE UNIQUE INDEX idx_users_email ON users (email)"],
+    /// );
+    /// assert!(migration.sql.starts_with("PRAGMA foreign_keys = OFF"));
+    /// assert!(migration.sql.contains(r#"CREATE TABLE "users__rebuild""#));
+    /// ```
+    pub fn rebuild_table(
+        table_name: &str,
+        columns: &[(&str, &str)],
+        constraints: &[&str],
+        copy_columns: &[&str],
+        indexes: &[&str],
+    ) -> Migration {
+        let definitions: Vec<String> = columns
+            .iter()
+            .map(|(name, definition)| format!("{} {definition}", quote_identifier(name)))
+            .chain(constraints.iter().map(|c| c.to_string()))
+            .collect();
+        let copy_columns: Vec<String> = copy_columns.iter().map(|c| c.to_string()).collect();
+        let indexes: Vec<String> = indexes.iter().map(|i| i.to_string()).collect();
+
+        MigrationBuilder::new(&format!("rebuild_table_{table_name}"))
+            .up(&rebuild_table_sql(
+                table_name,
+                &definitions,
+                &copy_columns,
+                &indexes,
+            ))
+            .build()
+    }
 }
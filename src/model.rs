@@ -24,14 +24,22 @@
 //! }
 //! ```
 
+use crate::chunking::ChunkLimits;
+use crate::executor::within_transaction;
+use crate::filters::SEARCH_SCORE_COLUMN;
+use crate::query::quote_name;
 use crate::{
-    Aggregate, Database, Error, FilterOperator, PaginatedResult, Pagination, QueryBuilder, Result,
-    SearchFilter, Sort,
+    Aggregate, ChangeEvent, ChangeOperation, DeleteBuilder, Error, Executor, FilterOperator,
+    InsertBuilder, PaginatedResult, Pagination, QueryBuilder, Result, SearchFilter, SearchHit,
+    Sort, UpdateBuilder,
 };
 use std::collections::HashMap;
 
 use serde::{de::DeserializeOwned, Serialize};
 
+/// Records [`Model::export_jsonl`] reads per query
+pub const EXPORT_BATCH_SIZE: u32 = 500;
+
 /// Mask numeric IDs for logging
 fn mask_id(id: i64) -> String {
     if id < 100 {
@@ -43,6 +51,137 @@ fn mask_id(id: i64) -> String {
     format!("{}{}", &id_str[..visible_digits], "*".repeat(masked_digits))
 }
 
+/// Order a model's values by its declared columns, then any extra keys by name
+fn ordered_values<M: Model>(mut map: HashMap<String, crate::Value>) -> Vec<(String, crate::Value)> {
+    let mut values: Vec<(String, crate::Value)> = M::columns()
+        .into_iter()
+        .filter_map(|column| map.remove(column).map(|v| (column.to_string(), v)))
+        .collect();
+    let mut rest: Vec<(String, crate::Value)> = map.into_iter().collect();
+    rest.sort_by(|a, b| a.0.cmp(&b.0));
+    values.extend(rest);
+    values
+}
+
+/// The single integer of a `COUNT(*)` result
+pub(crate) fn single_count(rows: Vec<crate::Row>) -> Result<u64> {
+    let row = rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Query("No count result".to_string()))?;
+    match row.into_values().next() {
+        Some(crate::Value::Integer(i)) => Ok(i as u64),
+        _ => Err(Error::Query("Failed to get count".to_string())),
+    }
+}
+
+/// Reject filter and sort columns that aren't among the model's columns
+///
+/// Filters and sorts deserialize from requests, and a name that isn't a plain
+/// identifier reaches the SQL as written, so finders check them first.
+/// Custom, raw, and expression filters are built in code and aren't checked.
+fn check_columns<M: Model>(filter: Option<&FilterOperator>, sorts: &[Sort]) -> Result<()> {
+    fn filter_columns<'a>(filter: &'a FilterOperator, columns: &mut Vec<&'a str>) {
+        match filter {
+            FilterOperator::Single(filter) => columns.push(&filter.column),
+            FilterOperator::And(filters) | FilterOperator::Or(filters) => {
+                for filter in filters {
+                    filter_columns(filter, columns);
+                }
+            }
+            FilterOperator::Not(filter) => filter_columns(filter, columns),
+            FilterOperator::Custom(_)
+            | FilterOperator::Raw(..)
+            | FilterOperator::Exists(_)
+            | FilterOperator::Expr(_) => {}
+        }
+    }
+
+    let mut columns: Vec<&str> = sorts.iter().map(|sort| sort.column.as_str()).collect();
+    if let Some(filter) = filter {
+        filter_columns(filter, &mut columns);
+    }
+    let known = M::columns();
+    match columns.into_iter().find(|column| !known.contains(column)) {
+        Some(column) => Err(Error::Validation(format!(
+            "Unknown column {column:?} for table {}",
+            M::table_name()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Load the records with the given primary keys; see [`Model::find_by_ids`]
+///
+/// A free function so callers that need a `Send` future for a generic model,
+/// such as GraphQL data loaders, can run it on a concrete executor.
+pub(crate) async fn find_by_ids<M: Model>(ids: &[i64], db: &impl Executor) -> Result<Vec<M>> {
+    let mut models = Vec::with_capacity(ids.len());
+    for chunk in ChunkLimits::default().split(ids, |_| (1, 8)) {
+        let placeholders: Vec<&str> = chunk.iter().map(|_| "?").collect();
+        let sql = format!(
+            "SELECT * FROM {} WHERE {} IN ({})",
+            quote_name(M::table_name()),
+            quote_name(M::primary_key()),
+            placeholders.join(", ")
+        );
+        let params: Vec<crate::Value> = chunk.iter().map(|&id| crate::Value::Integer(id)).collect();
+        for row in db
+            .query_statement(Some(M::table_name()), &sql, params)
+            .await?
+        {
+            models.push(M::from_map(row)?);
+        }
+    }
+    Ok(models)
+}
+
+/// Snapshot a model for a [`ChangeEvent`]; deletes report it as the old row
+fn change_event<M: Model>(model: &M, operation: ChangeOperation) -> ChangeEvent {
+    let row = model.to_map().ok();
+    let (old, new) = match operation {
+        ChangeOperation::Delete => (row, None),
+        ChangeOperation::Insert | ChangeOperation::Update => (None, row),
+    };
+    ChangeEvent {
+        table: M::table_name().to_string(),
+        operation,
+        primary_key: model.get_primary_key(),
+        old,
+        new,
+    }
+}
+
+/// Conversion from a result row into a Rust type
+///
+/// Rows are keyed by column name, so any projection works as long as its
+/// column names (or aliases) match the target's fields. Use
+/// `#[derive(FromRow)]` for custom result structs; models deriving `Model`
+/// implement it automatically.
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{FromRow, Row, Result, Value};
+///
+/// struct EmailOnly {
+///     email: String,
+/// }
+///
+/// impl FromRow for EmailOnly {
+///     fn from_row(mut row: Row) -> Result<Self> {
+///         match row.remove("email") {
+///             Some(Value::Text(email)) => Ok(Self { email }),
+///             _ => Err(libsql_orm::Error::Serialization("missing email".to_string())),
+///         }
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Build a value from a row of column values
+    fn from_row(row: crate::Row) -> Result<Self>;
+}
+
 /// Core trait for all database models
 #[allow(async_fn_in_trait)]
 pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
@@ -63,42 +202,162 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     /// Get all column names for this model
     fn columns() -> Vec<&'static str>;
 
+    /// Look up one of the model's columns by name
+    ///
+    /// Builders emit names that aren't plain identifiers verbatim, so pass
+    /// column names that come from user input, such as a `sort` query
+    /// parameter, through this first. The finders, such as
+    /// [`list`](Self::list) and [`find_where`](Self::find_where), check their
+    /// filter and sort columns themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{Model, Sort};
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct User { id: Option<i64>, name: String }
+    ///
+    /// let sort = Sort::asc(User::column("name").unwrap());
+    /// assert!(User::column("name; DROP TABLE user").is_err());
+    /// ```
+    fn column(name: &str) -> Result<&'static str> {
+        Self::columns()
+            .into_iter()
+            .find(|column| *column == name)
+            .ok_or_else(|| {
+                Error::Query(format!(
+                    "Unknown column {name:?} for table {}",
+                    Self::table_name()
+                ))
+            })
+    }
+
     /// Generate SQL for creating the table
     fn migration_sql() -> String;
 
+    /// `CREATE INDEX` statements to run after [`migration_sql`](Self::migration_sql)
+    ///
+    /// Empty by default; override to have
+    /// [`TestDatabase`](crate::testing::TestDatabase) create the model's indexes.
+    fn index_sql() -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Column (and table constraint) definitions inside [`migration_sql`](Self::migration_sql)
+    ///
+    /// Used by [`MigrationManager::diff_models`](crate::MigrationManager::diff_models)
+    /// to compare the model with the live schema.
+    fn column_definitions() -> Vec<String> {
+        crate::migrations::split_definitions(&Self::migration_sql())
+    }
+
+    /// JSON Schema of the model's serialized form
+    ///
+    /// `#[derive(Model)]` builds it from the field types: `Option` fields are
+    /// nullable and not required, chrono and uuid fields get a `format`, and
+    /// `#[orm_validate]` rules become `format: "email"`, length limits, and
+    /// `minimum`/`maximum`. Doc comments become descriptions. The
+    /// default lists the columns without constraints.
+    ///
+    /// When the model also implements `schemars::JsonSchema` (see
+    /// [`impl_json_schema!`](crate::impl_json_schema)), call this as
+    /// `<User as Model>::json_schema()`.
+    fn json_schema() -> serde_json::Value {
+        let properties: serde_json::Map<String, serde_json::Value> = Self::columns()
+            .into_iter()
+            .map(|column| (column.to_string(), serde_json::json!({})))
+            .collect();
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": Self::table_name(),
+            "type": "object",
+            "properties": properties,
+        })
+    }
+
+    /// Check the model's `#[orm_validate]` rules
+    ///
+    /// Called by [`create`](Self::create), [`update`](Self::update),
+    /// [`upsert`](Self::upsert), and the bulk variants before they run any
+    /// SQL. See [`validation`](crate::validation) for the available rules.
+    fn validate(&self) -> std::result::Result<(), crate::ValidationErrors> {
+        Ok(())
+    }
+
     /// Convert the model to a HashMap for database operations
     fn to_map(&self) -> Result<HashMap<String, crate::Value>>;
 
     /// Create a model from a HashMap
     fn from_map(map: HashMap<String, crate::Value>) -> Result<Self>;
 
-    /// Create a new record in the database
-    async fn create(&self, db: &Database) -> Result<Self> {
-        let map = self.to_map()?;
-        let columns: Vec<String> = map.keys().cloned().collect();
-        let values: Vec<String> = map.keys().map(|_| "?".to_string()).collect();
+    /// SQL and parameters that [`create`](Self::create) executes, which adds
+    /// a `RETURNING` clause for the new primary key
+    ///
+    /// Columns follow [`columns`](Self::columns) order, so the output is
+    /// stable enough for logging, snapshot tests, or `EXPLAIN`.
+    fn insert_statement(&self) -> Result<(String, Vec<crate::Value>)> {
+        InsertBuilder::table(Self::table_name())
+            .values(ordered_values::<Self>(self.to_map()?))
+            .to_sql()
+    }
 
-        let sql = format!(
-            "INSERT INTO {} ({}) VALUES ({})",
-            Self::table_name(),
-            columns.join(", "),
-            values.join(", ")
-        );
+    /// SQL and parameters that [`update`](Self::update) executes
+    fn update_statement(&self) -> Result<(String, Vec<crate::Value>)> {
+        let id = self.get_primary_key().ok_or_else(|| {
+            Error::Validation("Cannot update record without primary key".to_string())
+        })?;
+
+        let mut builder = UpdateBuilder::table(Self::table_name());
+        for (column, value) in ordered_values::<Self>(self.to_map()?) {
+            if column != Self::primary_key() {
+                builder = builder.set(column, value);
+            }
+        }
+        builder
+            .r#where(FilterOperator::Single(crate::Filter::eq(
+                Self::primary_key(),
+                id,
+            )))
+            .to_sql()
+    }
+
+    /// SQL and parameters that [`delete`](Self::delete) executes
+    fn delete_statement(&self) -> Result<(String, Vec<crate::Value>)> {
+        let id = self.get_primary_key().ok_or_else(|| {
+            Error::Validation("Cannot delete record without primary key".to_string())
+        })?;
+
+        DeleteBuilder::table(Self::table_name())
+            .r#where(FilterOperator::Single(crate::Filter::eq(
+                Self::primary_key(),
+                id,
+            )))
+            .to_sql()
+    }
+
+    /// Create a new record in the database
+    async fn create(&self, db: &impl Executor) -> Result<Self> {
+        self.validate()?;
+        let (sql, params) = self.insert_statement()?;
 
         Self::log_info(&format!("Creating record in table: {}", Self::table_name()));
         Self::log_debug(&format!("SQL: {sql}"));
 
-        let params: Vec<crate::compat::LibsqlValue> = map
-            .values()
-            .map(|v| Self::value_to_libsql_value(v))
-            .collect();
-
-        db.execute(&sql, params).await?;
-        let id = 1i64; // Placeholder - libsql WASM doesn't support last_insert_rowid
+        let sql = format!("{sql} RETURNING {}", quote_name(Self::primary_key()));
+        let returned = db
+            .query_statement(Some(Self::table_name()), &sql, params)
+            .await?
+            .into_iter()
+            .next()
+            .and_then(|mut row| row.remove(Self::primary_key()));
 
         let mut result = self.clone();
-        result.set_primary_key(id);
+        if let Some(crate::Value::Integer(id)) = returned {
+            result.set_primary_key(id);
+        }
+        db.emit_changes(|| vec![change_event(&result, ChangeOperation::Insert)]);
 
+        let id = result.get_primary_key().unwrap_or_default();
         Self::log_info(&format!(
             "Successfully created record with ID: {}",
             mask_id(id)
@@ -107,7 +366,7 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     }
 
     /// Create or update a record based on whether it has a primary key
-    async fn create_or_update(&self, db: &Database) -> Result<Self> {
+    async fn create_or_update(&self, db: &impl Executor) -> Result<Self> {
         if let Some(id) = self.get_primary_key() {
             Self::log_info(&format!(
                 "Updating existing record with ID: {}",
@@ -136,7 +395,8 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     }
 
     /// Create or update a record based on unique constraints
-    async fn upsert(&self, unique_columns: &[&str], db: &Database) -> Result<Self> {
+    async fn upsert(&self, unique_columns: &[&str], db: &impl Executor) -> Result<Self> {
+        self.validate()?;
         let map = self.to_map()?;
 
         // Build WHERE clause for unique columns
@@ -145,8 +405,8 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
 
         for &column in unique_columns {
             if let Some(value) = map.get(column) {
-                where_conditions.push(format!("{column} = ?"));
-                where_params.push(Self::value_to_libsql_value(value));
+                where_conditions.push(format!("{} = ?", quote_name(column)));
+                where_params.push(value.clone());
             }
         }
 
@@ -159,8 +419,8 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         let where_clause = where_conditions.join(" AND ");
         let sql = format!(
             "SELECT {} FROM {} WHERE {}",
-            Self::primary_key(),
-            Self::table_name(),
+            quote_name(Self::primary_key()),
+            quote_name(Self::table_name()),
             where_clause
         );
 
@@ -170,12 +430,14 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         ));
         Self::log_debug(&format!("SQL: {sql}"));
 
-        let mut rows = db.query(&sql, where_params).await?;
+        let rows = db
+            .query_statement(Some(Self::table_name()), &sql, where_params)
+            .await?;
 
-        if let Some(row) = rows.next().await? {
+        if let Some(row) = rows.into_iter().next() {
             // Record exists, update it
-            if let Some(existing_id) = row.get_value(0).ok().and_then(|v| match v {
-                crate::compat::LibsqlValue::Integer(i) => Some(i),
+            if let Some(existing_id) = row.get(Self::primary_key()).and_then(|v| match v {
+                crate::Value::Integer(i) => Some(*i),
                 _ => None,
             }) {
                 Self::log_info(&format!(
@@ -198,59 +460,71 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     }
 
     /// Create multiple records in the database
-    async fn bulk_create(models: &[Self], db: &Database) -> Result<Vec<Self>> {
+    ///
+    /// Records are inserted with multi-row `INSERT`s, split to stay within
+    /// the [`chunking`](crate::chunking) limits, in one transaction.
+    /// Overrides of [`insert_statement`](Self::insert_statement) are not
+    /// used.
+    async fn bulk_create(models: &[Self], db: &impl Executor) -> Result<Vec<Self>> {
         if models.is_empty() {
             return Ok(Vec::new());
         }
-
-        let mut results = Vec::new();
-        // Note: Manual transaction handling for WASM
-        db.execute("BEGIN", vec![]).await?;
-
+        let mut rows = Vec::with_capacity(models.len());
         for model in models {
-            let map = model.to_map()?;
-            let columns: Vec<String> = map.keys().cloned().collect();
-            let values: Vec<String> = map.keys().map(|_| "?".to_string()).collect();
-
-            let sql = format!(
-                "INSERT INTO {} ({}) VALUES ({})",
-                Self::table_name(),
-                columns.join(", "),
-                values.join(", ")
-            );
-
-            let params: Vec<crate::compat::LibsqlValue> = map
-                .values()
-                .map(|v| Self::value_to_libsql_value(v))
-                .collect();
-
-            db.execute(&sql, params).await?;
-            let id = 1i64; // Placeholder - libsql WASM doesn't support last_insert_rowid
-
-            let mut result = model.clone();
-            result.set_primary_key(id);
-            results.push(result);
+            model.validate()?;
+            rows.push(ordered_values::<Self>(model.to_map()?));
         }
 
-        db.execute("COMMIT", vec![]).await?;
+        // Note: Manual transaction handling for WASM
+        let statements =
+            crate::chunking::insert_statements(Self::table_name(), &rows, ChunkLimits::default());
+        within_transaction(db, !db.in_transaction(), async {
+            for (sql, params) in statements {
+                db.execute_statement(Some(Self::table_name()), &sql, params)
+                    .await?;
+            }
+            Ok(())
+        })
+        .await?;
+        let id = 1i64; // Placeholder - libsql WASM doesn't support last_insert_rowid
+        let results = models
+            .iter()
+            .map(|model| {
+                let mut result = model.clone();
+                result.set_primary_key(id);
+                result
+            })
+            .collect();
+
+        db.emit_changes(|| {
+            models
+                .iter()
+                .map(|model| change_event(model, ChangeOperation::Insert))
+                .collect()
+        });
         Ok(results)
     }
 
     /// Find a record by its primary key
-    async fn find_by_id(id: i64, db: &Database) -> Result<Option<Self>> {
+    async fn find_by_id(id: i64, db: &impl Executor) -> Result<Option<Self>> {
         let sql = format!(
             "SELECT * FROM {} WHERE {} = ?",
-            Self::table_name(),
-            Self::primary_key()
+            quote_name(Self::table_name()),
+            quote_name(Self::primary_key())
         );
 
         Self::log_debug(&format!("Finding record by ID: {}", mask_id(id)));
         Self::log_debug(&format!("SQL: {sql}"));
 
-        let mut rows = db.query(&sql, vec![crate::compat::integer_value(id)]).await?;
+        let rows = db
+            .query_statement(
+                Some(Self::table_name()),
+                &sql,
+                vec![crate::Value::Integer(id)],
+            )
+            .await?;
 
-        if let Some(row) = rows.next().await? {
-            let map = Self::row_to_map(&row)?;
+        if let Some(map) = rows.into_iter().next() {
             Self::log_debug(&format!("Found record with ID: {}", mask_id(id)));
             Ok(Some(Self::from_map(map)?))
         } else {
@@ -259,8 +533,17 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         }
     }
 
+    /// Find the records with the given primary keys
+    ///
+    /// Runs a single `IN` query, or one per chunk of keys beyond the
+    /// [`chunking`](crate::chunking) limits. Keys without a record are
+    /// skipped, and the records come back in no particular order.
+    async fn find_by_ids(ids: &[i64], db: &impl Executor) -> Result<Vec<Self>> {
+        find_by_ids(ids, db).await
+    }
+
     /// Find a single record by a specific condition
-    async fn find_one(filter: FilterOperator, db: &Database) -> Result<Option<Self>> {
+    async fn find_one(filter: FilterOperator, db: &impl Executor) -> Result<Option<Self>> {
         let builder = QueryBuilder::new(Self::table_name())
             .r#where(filter)
             .limit(1);
@@ -269,14 +552,50 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         Ok(results.into_iter().next())
     }
 
+    /// Find records with hand-written SQL
+    ///
+    /// The query should select the model's columns (e.g. `SELECT * FROM ...`);
+    /// parameters are bound positionally to `?` placeholders.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Model, Database, Result};
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct User { id: Option<i64>, name: String, email: String }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let users = User::find_by_sql(
+    ///     "SELECT * FROM users WHERE email LIKE ? ORDER BY id",
+    ///     vec!["%@example.com".into()],
+    ///     db,
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn find_by_sql(
+        sql: &str,
+        params: Vec<crate::Value>,
+        db: &impl Executor,
+    ) -> Result<Vec<Self>> {
+        Self::log_debug(&format!("SQL: {sql}"));
+        db.query_statement(Some(Self::table_name()), sql, params)
+            .await?
+            .into_iter()
+            .map(Self::from_map)
+            .collect()
+    }
+
     /// Find all records
-    async fn find_all(db: &Database) -> Result<Vec<Self>> {
+    async fn find_all(db: &impl Executor) -> Result<Vec<Self>> {
         let builder = QueryBuilder::new(Self::table_name());
         builder.execute_model::<Self>(db).await
     }
 
     /// Find records with a filter
-    async fn find_where(filter: FilterOperator, db: &Database) -> Result<Vec<Self>> {
+    async fn find_where(filter: FilterOperator, db: &impl Executor) -> Result<Vec<Self>> {
+        check_columns::<Self>(Some(&filter), &[])?;
         let builder = QueryBuilder::new(Self::table_name()).r#where(filter);
         builder.execute_model::<Self>(db).await
     }
@@ -284,8 +603,9 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     /// Find records with pagination
     async fn find_paginated(
         pagination: &Pagination,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
+        check_columns::<Self>(None, &pagination.sort)?;
         let builder = QueryBuilder::new(Self::table_name());
         builder.execute_model_paginated::<Self>(db, pagination).await
     }
@@ -294,8 +614,9 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     async fn find_where_paginated(
         filter: FilterOperator,
         pagination: &Pagination,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
+        check_columns::<Self>(Some(&filter), &pagination.sort)?;
         let builder = QueryBuilder::new(Self::table_name()).r#where(filter);
         builder.execute_model_paginated::<Self>(db, pagination).await
     }
@@ -304,7 +625,7 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     async fn search(
         search_filter: &SearchFilter,
         pagination: Option<&Pagination>,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
         let filter = search_filter.to_filter_operator();
         let pagination = pagination.unwrap_or(&Pagination::default()).clone();
@@ -312,75 +633,118 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         Self::find_where_paginated(filter, &pagination, db).await
     }
 
-    /// Count all records
-    async fn count(db: &Database) -> Result<u64> {
-        let sql = format!("SELECT COUNT(*) FROM {}", Self::table_name());
-        let mut rows = db.query(&sql, vec![]).await?;
+    /// Search records and rank them by relevance
+    ///
+    /// When the filter names an FTS5 table (see [`SearchFilter::fts`]) that
+    /// exists, the query runs against it and ranks by `bm25()`. Otherwise it
+    /// falls back to LIKE over the filter's columns, ranking rows by how many
+    /// columns match. Only `page` and `per_page` of the pagination are used.
+    ///
+    /// FTS5 query terms are matched as plain words unless
+    /// [`SearchFilter::raw_query`] is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Model, Pagination, Result, SearchFilter};
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct Post { id: Option<i64>, title: String, body: String }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let search = SearchFilter::new("async runtime", vec!["title", "body"]).fts("posts_fts");
+    /// for hit in Post::search_ranked(&search, Some(&Pagination::new(1, 10)), db).await? {
+    ///     println!("{:.2} {}", hit.score, hit.model.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn search_ranked(
+        search_filter: &SearchFilter,
+        pagination: Option<&Pagination>,
+        db: &impl Executor,
+    ) -> Result<Vec<SearchHit<Self>>> {
+        let pagination = pagination.cloned().unwrap_or_default();
+
+        let mut fts_table = None;
+        if let Some(table) = &search_filter.fts_table {
+            let exists = db
+                .query_statement(
+                    None,
+                    "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?",
+                    vec![crate::Value::Text(table.clone())],
+                )
+                .await?;
+            if exists.is_empty() {
+                Self::log_warn(&format!(
+                    "FTS table {table} not found, falling back to LIKE search"
+                ));
+            } else {
+                fts_table = Some(table.as_str());
+            }
+        }
 
-        if let Some(row) = rows.next().await? {
-            row.get_value(0)
-                .ok()
-                .and_then(|v| match v {
-                    crate::compat::LibsqlValue::Integer(i) => Some(i as u64),
-                    _ => None,
+        let (sql, params) = match fts_table {
+            Some(fts_table) => {
+                let (sql, mut params) =
+                    search_filter.fts_query(fts_table, Self::table_name(), Self::primary_key());
+                params.push(crate::Value::Integer(pagination.limit().into()));
+                params.push(crate::Value::Integer(pagination.offset().into()));
+                (format!("{sql} LIMIT ? OFFSET ?"), params)
+            }
+            None => search_filter
+                .like_ranked_query(Self::table_name())
+                .limit(pagination.limit())
+                .offset(pagination.offset())
+                .to_sql()?,
+        };
+        Self::log_debug(&format!("SQL: {sql}"));
+
+        db.query_statement(Some(Self::table_name()), &sql, params)
+            .await?
+            .into_iter()
+            .map(|mut row| {
+                let score = match row.remove(SEARCH_SCORE_COLUMN) {
+                    Some(crate::Value::Real(f)) => f,
+                    Some(crate::Value::Integer(i)) => i as f64,
+                    _ => 0.0,
+                };
+                Ok(SearchHit {
+                    model: Self::from_map(row)?,
+                    score,
                 })
-                .ok_or_else(|| Error::Query("Failed to get count".to_string()))
-        } else {
-            Err(Error::Query("No count result".to_string()))
-        }
+            })
+            .collect()
+    }
+
+    /// Count all records
+    async fn count(db: &impl Executor) -> Result<u64> {
+        let sql = format!("SELECT COUNT(*) FROM {}", quote_name(Self::table_name()));
+        let rows = db
+            .query_statement(Some(Self::table_name()), &sql, vec![])
+            .await?;
+        single_count(rows)
     }
 
     /// Count records with a filter
-    async fn count_where(filter: FilterOperator, db: &Database) -> Result<u64> {
+    async fn count_where(filter: FilterOperator, db: &impl Executor) -> Result<u64> {
+        check_columns::<Self>(Some(&filter), &[])?;
         let builder = QueryBuilder::new(Self::table_name()).r#where(filter);
 
-        let (sql, params) = builder.build_count()?;
-        let mut rows = db.query(&sql, params).await?;
-
-        if let Some(row) = rows.next().await? {
-            row.get_value(0)
-                .ok()
-                .and_then(|v| match v {
-                    crate::compat::LibsqlValue::Integer(i) => Some(i as u64),
-                    _ => None,
-                })
-                .ok_or_else(|| Error::Query("Failed to get count".to_string()))
-        } else {
-            Err(Error::Query("No count result".to_string()))
-        }
+        builder.execute_count(db).await
     }
 
     /// Update a record
-    async fn update(&self, db: &Database) -> Result<Self> {
-        let id = self.get_primary_key().ok_or_else(|| {
-            Error::Validation("Cannot update record without primary key".to_string())
-        })?;
-
-        let map = self.to_map()?;
-        let set_clauses: Vec<String> = map
-            .keys()
-            .filter(|&k| k != Self::primary_key())
-            .map(|k| format!("{k} = ?"))
-            .collect();
-
-        let sql = format!(
-            "UPDATE {} SET {} WHERE {} = ?",
-            Self::table_name(),
-            set_clauses.join(", "),
-            Self::primary_key()
-        );
+    async fn update(&self, db: &impl Executor) -> Result<Self> {
+        self.validate()?;
+        let (sql, params) = self.update_statement()?;
+        let id = self.get_primary_key().unwrap_or_default();
 
         Self::log_info(&format!("Updating record with ID: {}", mask_id(id)));
         Self::log_debug(&format!("SQL: {sql}"));
 
-        let mut params: Vec<crate::compat::LibsqlValue> = map
-            .iter()
-            .filter(|(k, _)| k != &Self::primary_key())
-            .map(|(_, v)| Self::value_to_libsql_value(v))
-            .collect();
-        params.push(crate::compat::integer_value(id));
-
-        db.execute(&sql, params).await?;
+        db.execute_statement(Some(Self::table_name()), &sql, params)
+            .await?;
+        db.emit_changes(|| vec![change_event(self, ChangeOperation::Update)]);
         Self::log_info(&format!(
             "Successfully updated record with ID: {}",
             mask_id(id)
@@ -389,41 +753,45 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     }
 
     /// Update multiple records
-    async fn bulk_update(models: &[Self], db: &Database) -> Result<Vec<Self>> {
+    async fn bulk_update(models: &[Self], db: &impl Executor) -> Result<Vec<Self>> {
         if models.is_empty() {
             return Ok(Vec::new());
         }
-
-        let mut results = Vec::new();
-        // Note: Manual transaction handling for WASM
-        db.execute("BEGIN", vec![]).await?;
-
         for model in models {
-            let result = model.update(db).await?;
-            results.push(result);
+            model.validate()?;
         }
 
-        db.execute("COMMIT", vec![]).await?;
-        Ok(results)
+        // Note: Manual transaction handling for WASM
+        within_transaction(db, !db.in_transaction(), async {
+            for model in models {
+                let (sql, params) = model.update_statement()?;
+
+                db.execute_statement(Some(Self::table_name()), &sql, params)
+                    .await?;
+            }
+            Ok(())
+        })
+        .await?;
+        db.emit_changes(|| {
+            models
+                .iter()
+                .map(|model| change_event(model, ChangeOperation::Update))
+                .collect()
+        });
+        Ok(models.to_vec())
     }
 
     /// Delete a record
-    async fn delete(&self, db: &Database) -> Result<bool> {
-        let id = self.get_primary_key().ok_or_else(|| {
-            Error::Validation("Cannot delete record without primary key".to_string())
-        })?;
-
-        let sql = format!(
-            "DELETE FROM {} WHERE {} = ?",
-            Self::table_name(),
-            Self::primary_key()
-        );
+    async fn delete(&self, db: &impl Executor) -> Result<bool> {
+        let (sql, params) = self.delete_statement()?;
+        let id = self.get_primary_key().unwrap_or_default();
 
         Self::log_info(&format!("Deleting record with ID: {}", mask_id(id)));
         Self::log_debug(&format!("SQL: {sql}"));
 
-        db.execute(&sql, vec![crate::compat::integer_value(id)])
+        db.execute_statement(Some(Self::table_name()), &sql, params)
             .await?;
+        db.emit_changes(|| vec![change_event(self, ChangeOperation::Delete)]);
         Self::log_info(&format!(
             "Successfully deleted record with ID: {}",
             mask_id(id)
@@ -432,53 +800,95 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     }
 
     /// Delete multiple records
-    async fn bulk_delete(ids: &[i64], db: &Database) -> Result<u64> {
+    ///
+    /// Keys beyond the [`chunking`](crate::chunking) limits are deleted with
+    /// one statement per chunk, in one transaction. Keys without a record
+    /// are skipped: the count and the change events cover only the rows
+    /// that were deleted.
+    async fn bulk_delete(ids: &[i64], db: &impl Executor) -> Result<u64> {
         if ids.is_empty() {
             return Ok(0);
         }
 
-        let placeholders: Vec<String> = ids.iter().map(|_| "?".to_string()).collect();
-        let sql = format!(
-            "DELETE FROM {} WHERE {} IN ({})",
-            Self::table_name(),
-            Self::primary_key(),
-            placeholders.join(", ")
-        );
-
-        let params: Vec<crate::compat::LibsqlValue> = ids
-            .iter()
-            .map(|&id| crate::compat::integer_value(id))
-            .collect();
-        db.execute(&sql, params).await?;
-        Ok(ids.len() as u64)
+        let chunks = ChunkLimits::default().split(ids, |_| (1, 8));
+        let own_transaction = chunks.len() > 1 && !db.in_transaction();
+        let mut deleted = Vec::with_capacity(ids.len());
+        within_transaction(db, own_transaction, async {
+            for chunk in chunks {
+                let placeholders: Vec<String> = chunk.iter().map(|_| "?".to_string()).collect();
+                let sql = format!(
+                    "DELETE FROM {table} WHERE {pk} IN ({}) RETURNING {pk}",
+                    placeholders.join(", "),
+                    table = quote_name(Self::table_name()),
+                    pk = quote_name(Self::primary_key()),
+                );
+
+                let params: Vec<crate::Value> =
+                    chunk.iter().map(|&id| crate::Value::Integer(id)).collect();
+                let rows = db
+                    .query_statement(Some(Self::table_name()), &sql, params)
+                    .await?;
+                deleted.extend(
+                    rows.iter()
+                        .filter_map(|row| match row.get(Self::primary_key()) {
+                            Some(crate::Value::Integer(id)) => Some(*id),
+                            _ => None,
+                        }),
+                );
+            }
+            Ok(())
+        })
+        .await?;
+        db.emit_changes(|| {
+            deleted
+                .iter()
+                .map(|&id| ChangeEvent {
+                    table: Self::table_name().to_string(),
+                    operation: ChangeOperation::Delete,
+                    primary_key: Some(id),
+                    old: None,
+                    new: None,
+                })
+                .collect()
+        });
+        Ok(deleted.len() as u64)
     }
 
     /// Delete records with a filter
-    async fn delete_where(filter: FilterOperator, db: &Database) -> Result<u64> {
-        let builder = QueryBuilder::new(Self::table_name()).r#where(filter);
-
-        let (sql, params) = builder.build()?;
-        let delete_sql = sql.replace("SELECT *", "DELETE");
-        db.execute(&delete_sql, params).await?;
-
-        // Note: SQLite doesn't return the number of affected rows directly
-        // This is a simplified implementation
-        Ok(1)
+    ///
+    /// Subscribers receive a single [`ChangeEvent`] without a primary key.
+    async fn delete_where(filter: FilterOperator, db: &impl Executor) -> Result<u64> {
+        let deleted = DeleteBuilder::table(Self::table_name())
+            .r#where(filter)
+            .execute(db)
+            .await?;
+        if deleted == 0 {
+            return Ok(0);
+        }
+        db.emit_changes(|| {
+            vec![ChangeEvent {
+                table: Self::table_name().to_string(),
+                operation: ChangeOperation::Delete,
+                primary_key: None,
+                old: None,
+                new: None,
+            }]
+        });
+        Ok(deleted)
     }
 
     /// List records with optional sorting and pagination
     async fn list(
         sort: Option<Vec<Sort>>,
         pagination: Option<&Pagination>,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
-        let mut builder = QueryBuilder::new(Self::table_name());
-
-        if let Some(sorts) = sort {
-            builder = builder.order_by_multiple(sorts);
-        }
-
         let pagination = pagination.unwrap_or(&Pagination::default()).clone();
+        let sort = sort.unwrap_or_default();
+        check_columns::<Self>(None, &sort)?;
+        check_columns::<Self>(None, &pagination.sort)?;
+        let builder = QueryBuilder::new(Self::table_name()).order_by_multiple(sort);
+
         builder.execute_model_paginated::<Self>(db, &pagination).await
     }
 
@@ -487,20 +897,94 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         filter: FilterOperator,
         sort: Option<Vec<Sort>>,
         pagination: Option<&Pagination>,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
-        let mut builder = QueryBuilder::new(Self::table_name()).r#where(filter);
+        let pagination = pagination.unwrap_or(&Pagination::default()).clone();
+        let sort = sort.unwrap_or_default();
+        check_columns::<Self>(Some(&filter), &sort)?;
+        check_columns::<Self>(None, &pagination.sort)?;
+        let builder = QueryBuilder::new(Self::table_name())
+            .r#where(filter)
+            .order_by_multiple(sort);
 
-        if let Some(sorts) = sort {
-            builder = builder.order_by_multiple(sorts);
+        builder.execute_model_paginated::<Self>(db, &pagination).await
+    }
+
+    /// Stream matching records as JSON Lines, returning the number written
+    ///
+    /// Records are read in primary key order, [`EXPORT_BATCH_SIZE`] at a
+    /// time, and each is written through the model's `Serialize` impl as one
+    /// line, so the output holds booleans and other field types rather than
+    /// raw column values.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Filter, FilterOperator, Model, Result};
+    /// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+    /// # struct User { id: Option<i64>, name: String, is_active: bool }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let mut out = Vec::new();
+    /// User::export_jsonl(
+    ///     Some(FilterOperator::Single(Filter::eq("is_active", true))),
+    ///     &mut out,
+    ///     db,
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn export_jsonl<W: std::io::Write>(
+        filter: Option<FilterOperator>,
+        mut writer: W,
+        db: &impl Executor,
+    ) -> Result<u64> {
+        let mut written = 0;
+        let mut after: Option<i64> = None;
+        loop {
+            let mut conditions: Vec<FilterOperator> = filter.iter().cloned().collect();
+            if let Some(last) = after {
+                conditions.push(FilterOperator::Single(crate::Filter::gt(
+                    Self::primary_key(),
+                    last,
+                )));
+            }
+            let mut builder = QueryBuilder::new(Self::table_name())
+                .order_by(Sort::asc(Self::primary_key()))
+                .limit(EXPORT_BATCH_SIZE);
+            if !conditions.is_empty() {
+                builder = builder.r#where(FilterOperator::And(conditions));
+            }
+
+            let batch = builder.execute_model::<Self>(db).await?;
+            for model in &batch {
+                serde_json::to_writer(&mut writer, model)?;
+                writer.write_all(b"\n")?;
+            }
+            written += batch.len() as u64;
+            after = batch.last().and_then(Model::get_primary_key);
+            if batch.len() < EXPORT_BATCH_SIZE as usize || after.is_none() {
+                break;
+            }
         }
+        writer.flush()?;
+        Ok(written)
+    }
 
-        let pagination = pagination.unwrap_or(&Pagination::default()).clone();
-        builder.execute_model_paginated::<Self>(db, &pagination).await
+    /// Import records from CSV, reporting rows that fail instead of stopping
+    ///
+    /// See [`import`](crate::import) for how fields are parsed.
+    async fn import_csv(
+        reader: impl std::io::Read,
+        db: &impl Executor,
+        options: crate::import::ImportOptions<'_>,
+    ) -> Result<crate::import::ImportReport> {
+        crate::import::import_csv::<Self>(reader, db, options).await
     }
 
     /// Execute a custom query
-    async fn query(builder: QueryBuilder, db: &Database) -> Result<Vec<Self>> {
+    async fn query(builder: QueryBuilder, db: &impl Executor) -> Result<Vec<Self>> {
         builder.execute_model::<Self>(db).await
     }
 
@@ -508,7 +992,7 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
     async fn query_paginated(
         builder: QueryBuilder,
         pagination: &Pagination,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<PaginatedResult<Self>> {
         builder.execute_model_paginated::<Self>(db, pagination).await
     }
@@ -518,7 +1002,7 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
         function: Aggregate,
         column: &str,
         filter: Option<FilterOperator>,
-        db: &Database,
+        db: &impl Executor,
     ) -> Result<Option<f64>> {
         let mut builder =
             QueryBuilder::new(Self::table_name()).aggregate(function, column, None::<String>);
@@ -527,16 +1011,18 @@ pub trait Model: Serialize + DeserializeOwned + Send + Sync + Clone {
             builder = builder.r#where(filter);
         }
 
-        let (sql, params) = builder.build()?;
-        let mut rows = db.query(&sql, params).await?;
+        let (sql, params) = builder.to_sql()?;
+        let rows = db
+            .query_statement(Some(Self::table_name()), &sql, params)
+            .await?;
 
-        if let Some(row) = rows.next().await? {
+        if let Some(row) = rows.into_iter().next() {
             let value = row
-                .get_value(0)
-                .ok()
+                .into_values()
+                .next()
                 .and_then(|v| match v {
-                    crate::compat::LibsqlValue::Integer(i) => Some(i as f64),
-                    crate::compat::LibsqlValue::Real(f) => Some(f),
+                    crate::Value::Integer(i) => Some(i as f64),
+                    crate::Value::Real(f) => Some(f),
                     _ => None,
                 })
                 .ok_or_else(|| Error::Query("Failed to get aggregate value".to_string()))?;
@@ -0,0 +1,311 @@
+//! INSERT, UPDATE and DELETE builders for libsql-orm
+//!
+//! These builders complement the SELECT-only [`QueryBuilder`](crate::QueryBuilder)
+//! for dynamic mutations. Values are always bound as parameters, and WHERE
+//! clauses reuse the same [`FilterOperator`] machinery as queries.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use libsql_orm::{InsertBuilder, UpdateBuilder, DeleteBuilder, FilterOperator, Filter, Result};
+//!
+//! # fn example() -> Result<()> {
+//! let (sql, params) = InsertBuilder::table("users")
+//!     .value("name", "Alice")
+//!     .value("is_active", true)
+//!     .build()?;
+//! assert_eq!(sql, "INSERT INTO users (name, is_active) VALUES (?, ?)");
+//!
+//! let (sql, params) = UpdateBuilder::table("users")
+//!     .set("name", "Bob")
+//!     .r#where(FilterOperator::Single(Filter::eq("id", 1i64)))
+//!     .build()?;
+//! assert_eq!(sql, "UPDATE users SET name = ? WHERE id = ?");
+//!
+//! let (sql, params) = DeleteBuilder::table("users")
+//!     .r#where(FilterOperator::Single(Filter::eq("is_active", false)))
+//!     .build()?;
+//! assert_eq!(sql, "DELETE FROM users WHERE is_active = ?");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::query::{
+    build_where_clause, quote_identifier, value_to_libsql_value, within_parameter_budget,
+    ListBinding,
+};
+use crate::{Error, Executor, FilterOperator, Result, Value};
+
+/// INSERT statement builder
+///
+/// Columns are emitted in the order they were added.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::{InsertBuilder, Database, Result};
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// let inserted = InsertBuilder::table("audit_log")
+///     .value("action", "login")
+///     .value("user_id", 42i64)
+///     .execute(db)
+///     .await?;
+/// assert_eq!(inserted, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InsertBuilder {
+    table: String,
+    values: Vec<(String, Value)>,
+}
+
+impl InsertBuilder {
+    /// Start an INSERT into `table`
+    pub fn table(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Set a column value
+    pub fn value(mut self, column: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.values.push((column.into(), value.into()));
+        self
+    }
+
+    /// Set several column values at once
+    pub fn values<I, C, V>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = (C, V)>,
+        C: Into<String>,
+        V: Into<Value>,
+    {
+        self.values
+            .extend(values.into_iter().map(|(c, v)| (c.into(), v.into())));
+        self
+    }
+
+    /// Build the SQL statement and its parameters
+    ///
+    /// With no values this emits `INSERT INTO table DEFAULT VALUES`.
+    pub fn build(&self) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        if self.values.is_empty() {
+            return Ok((
+                format!(
+                    "INSERT INTO {} DEFAULT VALUES",
+                    quote_identifier(&self.table)
+                ),
+                vec![],
+            ));
+        }
+
+        let columns: Vec<String> = self
+            .values
+            .iter()
+            .map(|(c, _)| quote_identifier(c))
+            .collect();
+        let placeholders: Vec<&str> = self.values.iter().map(|_| "?").collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_identifier(&self.table),
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        let params = self
+            .values
+            .iter()
+            .map(|(_, v)| value_to_libsql_value(v))
+            .collect();
+
+        Ok((sql, params))
+    }
+
+    /// Render the statement without executing it, with parameters as [`Value`]s
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>)> {
+        let (sql, params) = self.build()?;
+        Ok((sql, params.into_iter().map(Value::from).collect()))
+    }
+
+    /// Execute the statement, returning the number of inserted rows
+    pub async fn execute(&self, db: &impl Executor) -> Result<u64> {
+        let (sql, params) = self.to_sql()?;
+        db.execute_statement(Some(&self.table), &sql, params).await
+    }
+}
+
+/// UPDATE statement builder
+///
+/// Without a WHERE clause the update applies to every row, as in SQL.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::{UpdateBuilder, FilterOperator, Filter, Database, Result};
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// let updated = UpdateBuilder::table("users")
+///     .set("is_active", false)
+///     .r#where(FilterOperator::Single(Filter::lt("last_login", "2024-01-01")))
+///     .execute(db)
+///     .await?;
+/// println!("Deactivated {updated} users");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UpdateBuilder {
+    table: String,
+    assignments: Vec<(String, Value)>,
+    where_clauses: Vec<FilterOperator>,
+}
+
+impl UpdateBuilder {
+    /// Start an UPDATE of `table`
+    pub fn table(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            assignments: Vec::new(),
+            where_clauses: Vec::new(),
+        }
+    }
+
+    /// Assign a new value to a column
+    pub fn set(mut self, column: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.assignments.push((column.into(), value.into()));
+        self
+    }
+
+    /// Add a where clause; multiple clauses are combined with AND
+    pub fn r#where(mut self, filter: impl Into<FilterOperator>) -> Self {
+        self.where_clauses.push(filter.into());
+        self
+    }
+
+    /// Build the SQL statement and its parameters
+    pub fn build(&self) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        within_parameter_budget(|lists| self.build_statement(lists))
+    }
+
+    fn build_statement(
+        &self,
+        lists: ListBinding,
+    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        if self.assignments.is_empty() {
+            return Err(Error::Query(format!(
+                "UPDATE {} requires at least one column to set",
+                self.table
+            )));
+        }
+
+        let set_clauses: Vec<String> = self
+            .assignments
+            .iter()
+            .map(|(c, _)| format!("{} = ?", quote_identifier(c)))
+            .collect();
+        let mut sql = format!(
+            "UPDATE {} SET {}",
+            quote_identifier(&self.table),
+            set_clauses.join(", ")
+        );
+        let mut params: Vec<crate::compat::LibsqlValue> = self
+            .assignments
+            .iter()
+            .map(|(_, v)| value_to_libsql_value(v))
+            .collect();
+
+        if !self.where_clauses.is_empty() {
+            let (where_sql, where_params) = build_where_clause(&self.where_clauses, lists)?;
+            sql.push_str(&format!(" WHERE {where_sql}"));
+            params.extend(where_params);
+        }
+
+        Ok((sql, params))
+    }
+
+    /// Render the statement without executing it, with parameters as [`Value`]s
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>)> {
+        let (sql, params) = self.build()?;
+        Ok((sql, params.into_iter().map(Value::from).collect()))
+    }
+
+    /// Execute the statement, returning the number of updated rows
+    pub async fn execute(&self, db: &impl Executor) -> Result<u64> {
+        let (sql, params) = self.to_sql()?;
+        db.execute_statement(Some(&self.table), &sql, params).await
+    }
+}
+
+/// DELETE statement builder
+///
+/// Without a WHERE clause every row is deleted, as in SQL.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::{DeleteBuilder, FilterOperator, Filter, Database, Result};
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// let deleted = DeleteBuilder::table("sessions")
+///     .r#where(FilterOperator::Single(Filter::lt("expires_at", "2024-01-01")))
+///     .execute(db)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeleteBuilder {
+    table: String,
+    where_clauses: Vec<FilterOperator>,
+}
+
+impl DeleteBuilder {
+    /// Start a DELETE from `table`
+    pub fn table(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            where_clauses: Vec::new(),
+        }
+    }
+
+    /// Add a where clause; multiple clauses are combined with AND
+    pub fn r#where(mut self, filter: impl Into<FilterOperator>) -> Self {
+        self.where_clauses.push(filter.into());
+        self
+    }
+
+    /// Build the SQL statement and its parameters
+    pub fn build(&self) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        within_parameter_budget(|lists| self.build_statement(lists))
+    }
+
+    fn build_statement(
+        &self,
+        lists: ListBinding,
+    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        let mut sql = format!("DELETE FROM {}", quote_identifier(&self.table));
+        let mut params = Vec::new();
+
+        if !self.where_clauses.is_empty() {
+            let (where_sql, where_params) = build_where_clause(&self.where_clauses, lists)?;
+            sql.push_str(&format!(" WHERE {where_sql}"));
+            params.extend(where_params);
+        }
+
+        Ok((sql, params))
+    }
+
+    /// Render the statement without executing it, with parameters as [`Value`]s
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>)> {
+        let (sql, params) = self.build()?;
+        Ok((sql, params.into_iter().map(Value::from).collect()))
+    }
+
+    /// Execute the statement, returning the number of deleted rows
+    pub async fn execute(&self, db: &impl Executor) -> Result<u64> {
+        let (sql, params) = self.to_sql()?;
+        db.execute_statement(Some(&self.table), &sql, params).await
+    }
+}
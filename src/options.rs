@@ -0,0 +1,353 @@
+//! Per-query timeouts and cancellation
+//!
+//! [`QueryOptions`] bound how long a statement may run and let another task
+//! stop it through a [`CancellationToken`]. Wrap any [`Executor`] with
+//! [`Executor::with_options`] and pass the wrapper to model and builder
+//! methods; every statement they run gets the options. For hand-written
+//! SQL, use [`Database::query_with_options`](crate::Database::query_with_options)
+//! and [`Database::execute_with_options`](crate::Database::execute_with_options).
+//!
+//! The timeout applies to each statement on its own, counted from when it
+//! starts. A statement that runs out of time fails with
+//! [`Error::Timeout`], and one whose token is cancelled fails with
+//! [`Error::Cancelled`]. The statement is abandoned at its next wait for
+//! I/O or, for queries on a [`Database`](crate::Database), its next row;
+//! dropping it resets it, so the connection can run the next statement. A
+//! single step that neither waits nor returns rows, such as an aggregate
+//! over an in-memory table, runs to completion first.
+//!
+//! Transaction control is exempt: the `BEGIN`, `COMMIT`, and `ROLLBACK` of
+//! bulk operations run on the wrapped executor without the options, so a
+//! cancelled or timed-out batch is still rolled back.
+//!
+//! On `wasm32` there is no timer thread, so timeouts are only noticed when
+//! the statement next makes progress; cancel the token from a platform
+//! timer to stop a stalled request.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{CancellationToken, Database, Executor, Model, QueryOptions, Result};
+//! use std::time::Duration;
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//!
+//! # async fn example(db: &Database) -> Result<()> {
+//! let token = CancellationToken::new();
+//! let options = QueryOptions::new()
+//!     .timeout(Duration::from_millis(500))
+//!     .cancellation_token(token.clone());
+//!
+//! // Cancel from elsewhere, e.g. when the client disconnects
+//! // token.cancel();
+//! let users = User::find_all(&db.with_options(options)).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ChangeEvent, Error, Executor, Result, Row, Value};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+/// Limits for the statements of one operation
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Longest a single statement may run
+    pub timeout: Option<Duration>,
+    /// Token that stops statements when cancelled
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl QueryOptions {
+    /// Options without a timeout or token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail statements running longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stop statements once `token` is cancelled
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Fail if the token has been cancelled
+    pub(crate) fn check_cancelled(&self) -> Result<()> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => {
+                Err(Error::Cancelled("statement was cancelled".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Run `statement` under these options
+    pub(crate) async fn run<T>(&self, statement: impl Future<Output = Result<T>>) -> Result<T> {
+        let deadline = self
+            .timeout
+            .map(|timeout| (timeout, deadline_after(timeout)));
+        let mut statement = std::pin::pin!(statement);
+        let mut timer_armed = false;
+        // Unregistered when the statement finishes or is dropped
+        let slot = self
+            .cancellation_token
+            .as_ref()
+            .map(CancellationToken::slot);
+        std::future::poll_fn(|cx| {
+            if let Err(err) = self.check_cancelled() {
+                return Poll::Ready(Err(err));
+            }
+            if let Some(slot) = &slot {
+                slot.register(cx.waker());
+            }
+            if let Some((timeout, at)) = deadline {
+                if chrono::Utc::now() >= at {
+                    return Poll::Ready(Err(timed_out(timeout)));
+                }
+                if !timer_armed {
+                    wake_at(at, cx.waker().clone());
+                    timer_armed = true;
+                }
+            }
+            statement.as_mut().poll(cx)
+        })
+        .await
+    }
+
+    /// Per-row check for statements that return many rows
+    pub(crate) fn row_guard(&self) -> RowGuard<'_> {
+        RowGuard {
+            options: self,
+            deadline: self
+                .timeout
+                .map(|timeout| (timeout, deadline_after(timeout))),
+        }
+    }
+}
+
+/// Checks the options between the rows of one statement
+pub(crate) struct RowGuard<'a> {
+    options: &'a QueryOptions,
+    deadline: Option<(Duration, Deadline)>,
+}
+
+impl RowGuard<'_> {
+    pub(crate) fn check(&self) -> Result<()> {
+        self.options.check_cancelled()?;
+        match self.deadline {
+            Some((timeout, at)) if chrono::Utc::now() >= at => Err(timed_out(timeout)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// When a statement runs out of time; wall-clock, as `Instant` is
+/// unavailable on `wasm32`
+type Deadline = chrono::DateTime<chrono::Utc>;
+
+fn deadline_after(timeout: Duration) -> Deadline {
+    chrono::Duration::from_std(timeout)
+        .ok()
+        .and_then(|timeout| chrono::Utc::now().checked_add_signed(timeout))
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+}
+
+fn timed_out(timeout: Duration) -> Error {
+    Error::Timeout(format!("statement ran longer than {timeout:?}"))
+}
+
+/// Wake `waker` at `at` from a shared timer thread
+#[cfg(not(target_arch = "wasm32"))]
+fn wake_at(at: Deadline, waker: Waker) {
+    use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+    use std::sync::OnceLock;
+
+    static TIMER: OnceLock<Mutex<Sender<(Deadline, Waker)>>> = OnceLock::new();
+    let timer = TIMER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<(Deadline, Waker)>();
+        std::thread::Builder::new()
+            .name("libsql-orm-timer".to_string())
+            .spawn(move || {
+                let mut pending: Vec<(Deadline, Waker)> = Vec::new();
+                loop {
+                    let now = chrono::Utc::now();
+                    pending.retain(|(at, waker)| {
+                        let due = *at <= now;
+                        if due {
+                            waker.wake_by_ref();
+                        }
+                        !due
+                    });
+                    let received = match pending.iter().map(|(at, _)| *at).min() {
+                        Some(next) => {
+                            receiver.recv_timeout((next - now).to_std().unwrap_or_default())
+                        }
+                        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    };
+                    match received {
+                        Ok(entry) => pending.push(entry),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            })
+            .expect("failed to spawn the query timer thread");
+        Mutex::new(sender)
+    });
+    let _ = timer
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .send((at, waker));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn wake_at(_at: Deadline, _waker: Waker) {}
+
+/// Signal for stopping statements from another task
+///
+/// Clones share the same state, so keep one and hand clones to
+/// [`QueryOptions::cancellation_token`]. Cancelling is permanent.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Wakers>,
+}
+
+/// The wakers of running statements, one slot per [`QueryOptions::run`]
+#[derive(Debug, Default)]
+struct Wakers {
+    next_slot: u64,
+    slots: Vec<(u64, Waker)>,
+}
+
+impl CancellationToken {
+    /// A token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop every statement using this token, now and later
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        for (_, waker) in self.wakers().slots.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// A slot for the waker of one running statement
+    fn slot(&self) -> WakerSlot<'_> {
+        let mut wakers = self.wakers();
+        let id = wakers.next_slot;
+        wakers.next_slot += 1;
+        WakerSlot { token: self, id }
+    }
+
+    /// Wakers of statements still running, for tests
+    #[cfg(test)]
+    pub(crate) fn registered_wakers(&self) -> usize {
+        self.wakers().slots.len()
+    }
+
+    fn wakers(&self) -> std::sync::MutexGuard<'_, Wakers> {
+        self.state
+            .wakers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Keeps the latest waker of one statement until dropped
+struct WakerSlot<'a> {
+    token: &'a CancellationToken,
+    id: u64,
+}
+
+impl WakerSlot<'_> {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.token.wakers();
+        match wakers.slots.iter_mut().find(|(id, _)| *id == self.id) {
+            Some((_, registered)) if registered.will_wake(waker) => {}
+            Some((_, registered)) => *registered = waker.clone(),
+            None => wakers.slots.push((self.id, waker.clone())),
+        }
+    }
+}
+
+impl Drop for WakerSlot<'_> {
+    fn drop(&mut self) {
+        self.token.wakers().slots.retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// An [`Executor`] running every statement under [`QueryOptions`]
+///
+/// Created by [`Executor::with_options`].
+pub struct WithOptions<'a, E> {
+    inner: &'a E,
+    options: QueryOptions,
+}
+
+impl<'a, E: Executor> WithOptions<'a, E> {
+    pub(crate) fn new(inner: &'a E, options: QueryOptions) -> Self {
+        Self { inner, options }
+    }
+
+    /// The options applied to each statement
+    pub fn options(&self) -> &QueryOptions {
+        &self.options
+    }
+}
+
+impl<E: Executor> Executor for WithOptions<'_, E> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        self.inner
+            .execute_statement_with_options(table, sql, params, &self.options)
+            .await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        self.inner
+            .query_statement_with_options(table, sql, params, &self.options)
+            .await
+    }
+
+    async fn execute_transaction_control(&self, sql: &str) -> Result<()> {
+        self.inner.execute_transaction_control(sql).await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        self.inner.emit_changes(events)
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.inner.in_transaction()
+    }
+}
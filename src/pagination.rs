@@ -46,6 +46,117 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How paginated queries find the total number of rows
+///
+/// Counting every matching row on each page load is the slowest part of
+/// listing endpoints over large tables. Pick a cheaper strategy with
+/// [`Pagination::with_count_strategy`]:
+///
+/// - [`Exact`](Self::Exact) runs `COUNT(*)` for every page (the default)
+/// - [`Cached`](Self::Cached) reuses a count for the same query for a while,
+///   so totals can lag behind writes by up to the TTL
+/// - [`Estimated`](Self::Estimated) reads the row count `ANALYZE` stored in
+///   `sqlite_stat1`, falling back to `max(rowid)`; only queries over a whole
+///   table can be estimated, others are counted exactly. The result sets
+///   [`Pagination::total_estimated`]
+///
+/// The strategy is chosen by the server, so it is never read from or
+/// written to serialized pagination parameters.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::{CountStrategy, Database, Model, Pagination, Result};
+/// use std::time::Duration;
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct User { id: Option<i64>, name: String }
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// // Keep one cached strategy around, e.g. in application state; clones
+/// // share their counts
+/// let counts = CountStrategy::cached(Duration::from_secs(30));
+///
+/// let page = Pagination::new(3, 50).with_count_strategy(counts.clone());
+/// let users = User::find_paginated(&page, db).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub enum CountStrategy {
+    /// Count matching rows for every page
+    #[default]
+    Exact,
+    /// Reuse counts from a shared [`CountCache`] until they expire
+    Cached(CountCache),
+    /// Estimate the size of the whole table from its statistics
+    Estimated,
+}
+
+impl CountStrategy {
+    /// Cache counts for `ttl` in a new [`CountCache`]
+    pub fn cached(ttl: Duration) -> Self {
+        Self::Cached(CountCache::new(ttl))
+    }
+}
+
+/// Counts remembered by [`CountStrategy::Cached`]
+///
+/// Entries are keyed by the count query and its parameters. They are only
+/// dropped when they expire or on [`clear`](Self::clear); writes don't
+/// invalidate them. Clones share the same entries.
+#[derive(Debug, Clone)]
+pub struct CountCache {
+    ttl: Duration,
+    entries: Arc<Mutex<CountEntries>>,
+}
+
+/// Cached totals and when they expire, by cache key
+type CountEntries = HashMap<String, (u64, chrono::DateTime<chrono::Utc>)>;
+
+impl CountCache {
+    /// Create an empty cache whose counts live for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Drop every cached count
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<u64> {
+        let mut entries = self.entries();
+        let (total, expires_at) = *entries.get(key)?;
+        if expires_at <= chrono::Utc::now() {
+            entries.remove(key);
+            return None;
+        }
+        Some(total)
+    }
+
+    pub(crate) fn put(&self, key: String, total: u64) {
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or(chrono::Duration::MAX);
+        let expires_at = chrono::Utc::now()
+            .checked_add_signed(ttl)
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        let mut entries = self.entries();
+        entries.retain(|_, (_, expires)| *expires > chrono::Utc::now());
+        entries.insert(key, (total, expires_at));
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, CountEntries> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
 
 /// Pagination parameters for queries
 ///
@@ -76,6 +187,25 @@ pub struct Pagination {
     pub total: Option<u64>,
     /// Total number of pages (calculated)
     pub total_pages: Option<u32>,
+    /// Whether queries run a COUNT to fill `total` and `total_pages`
+    #[serde(default = "default_count_total")]
+    pub count_total: bool,
+    /// Whether another page follows, set when the total is skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+    /// Ordering applied by paginated queries, most significant first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort: Vec<crate::Sort>,
+    /// How `total` is found when `count_total` is set
+    #[serde(skip)]
+    pub count_strategy: CountStrategy,
+    /// Whether `total` is an estimate rather than an exact count
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub total_estimated: bool,
+}
+
+fn default_count_total() -> bool {
+    true
 }
 
 impl Pagination {
@@ -86,9 +216,61 @@ impl Pagination {
             per_page,
             total: None,
             total_pages: None,
+            count_total: true,
+            has_more: None,
+            sort: Vec::new(),
+            count_strategy: CountStrategy::Exact,
+            total_estimated: false,
         }
     }
 
+    /// Order paginated results by the given columns, most significant first
+    ///
+    /// The sorts are applied after any ordering already on the query, so
+    /// they also act as tie-breakers for builder-level ORDER BY clauses.
+    ///
+    /// ```rust
+    /// use libsql_orm::{Pagination, Sort};
+    ///
+    /// let pagination = Pagination::new(1, 25).with_sort(vec![
+    ///     Sort::desc("last_login").nulls_last(),
+    ///     Sort::asc("name").collate("NOCASE"),
+    /// ]);
+    /// assert_eq!(pagination.sort.len(), 2);
+    /// ```
+    pub fn with_sort(mut self, sort: Vec<crate::Sort>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Skip the COUNT query and detect the next page by fetching one extra row
+    ///
+    /// `total` and `total_pages` stay `None`; [`has_next`](Self::has_next)
+    /// reports whether the extra row was found. This halves the roundtrips
+    /// for listings that only need "next"/"previous" links.
+    ///
+    /// ```rust
+    /// use libsql_orm::Pagination;
+    ///
+    /// let mut pagination = Pagination::new(1, 10).without_total();
+    /// pagination.set_has_next(true);
+    ///
+    /// assert_eq!(pagination.total, None);
+    /// assert!(pagination.has_next());
+    /// assert_eq!(pagination.next_page(), Some(2));
+    /// ```
+    pub fn without_total(mut self) -> Self {
+        self.count_total = false;
+        self
+    }
+
+    /// Find the total with the given [`CountStrategy`] instead of an exact
+    /// `COUNT(*)` on every page
+    pub fn with_count_strategy(mut self, strategy: CountStrategy) -> Self {
+        self.count_strategy = strategy;
+        self
+    }
+
     /// Get the offset for SQL LIMIT/OFFSET
     pub fn offset(&self) -> u32 {
         (self.page - 1) * self.per_page
@@ -105,12 +287,17 @@ impl Pagination {
         self.total_pages = Some(((total as f64) / (self.per_page as f64)).ceil() as u32);
     }
 
+    /// Record whether another page follows without knowing the total
+    pub fn set_has_next(&mut self, has_next: bool) {
+        self.has_more = Some(has_next);
+    }
+
     /// Check if there's a next page
     pub fn has_next(&self) -> bool {
         if let (Some(total_pages), Some(current_page)) = (self.total_pages, Some(self.page)) {
             current_page < total_pages
         } else {
-            false
+            self.has_more.unwrap_or(false)
         }
     }
 
@@ -191,6 +378,12 @@ impl<T> PaginatedResult<T> {
         Self { data, pagination }
     }
 
+    /// Create a paginated result that only knows whether a next page exists
+    pub fn with_has_next(data: Vec<T>, mut pagination: Pagination, has_next: bool) -> Self {
+        pagination.set_has_next(has_next);
+        Self { data, pagination }
+    }
+
     /// Get the data items
     pub fn data(&self) -> &[T] {
         &self.data
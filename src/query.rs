@@ -45,9 +45,10 @@
 
 use crate::filters::FilterValue;
 use crate::{
-    Aggregate, Database, FilterOperator, Operator, PaginatedResult, Pagination, Result, Sort, Value,
+    Aggregate, CountStrategy, Executor, Expr, FilterOperator, FromRow, Operator, PaginatedResult,
+    Pagination, Result, Sort, Value,
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
 /// Query result wrapper
 ///
@@ -82,7 +83,8 @@ impl<T> QueryResult<T> {
 /// SQL query builder for complex queries
 ///
 /// Provides a fluent interface for building SQL queries with support for:
-/// - Column selection and table joins
+/// - Common table expressions (`WITH` / `WITH RECURSIVE`)
+/// - Column selection (including raw projections and window functions) and table joins
 /// - WHERE clauses with complex filtering
 /// - GROUP BY and HAVING clauses
 /// - ORDER BY with multiple sort criteria
@@ -116,21 +118,147 @@ impl<T> QueryResult<T> {
 ///     .having(FilterOperator::Single(Filter::gt("total_amount", 1000.0)));
 /// # }
 /// ```
+#[derive(Debug, Serialize, Deserialize)]
 pub struct QueryBuilder {
+    ctes: Vec<CommonTableExpression>,
     table: String,
-    select_columns: Vec<String>,
+    select_columns: Vec<SelectItem>,
     joins: Vec<JoinClause>,
     where_clauses: Vec<FilterOperator>,
     group_by: Vec<String>,
     having: Vec<FilterOperator>,
-    order_by: Vec<Sort>,
+    order_by: Vec<OrderItem>,
     limit: Option<u32>,
     offset: Option<u32>,
     distinct: bool,
     aggregate: Option<AggregateClause>,
 }
 
+/// Window function expression for [`QueryBuilder::select_window`]
+///
+/// Renders as `function OVER (PARTITION BY ... ORDER BY ...) AS alias`.
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{Sort, Window};
+///
+/// let rank = Window::new("rank()")
+///     .partition_by(["team_id"])
+///     .order_by(Sort::desc("score"))
+///     .alias("team_rank");
+/// assert_eq!(
+///     rank.to_string(),
+///     r#"rank() OVER (PARTITION BY "team_id" ORDER BY "score" DESC) AS "team_rank""#
+/// );
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Window {
+    function: String,
+    partition_by: Vec<String>,
+    order_by: Vec<Sort>,
+    alias: Option<String>,
+}
+
+impl Window {
+    /// Create a window over the given function call, e.g. `"row_number()"`
+    /// or `"sum(amount)"`
+    pub fn new(function: impl Into<String>) -> Self {
+        Self {
+            function: function.into(),
+            partition_by: Vec::new(),
+            order_by: Vec::new(),
+            alias: None,
+        }
+    }
+
+    /// Set the `PARTITION BY` columns
+    pub fn partition_by<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.partition_by = columns
+            .into_iter()
+            .map(|c| c.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Add an `ORDER BY` term inside the window
+    pub fn order_by(mut self, sort: Sort) -> Self {
+        self.order_by.push(sort);
+        self
+    }
+
+    /// Set the result column name
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+}
+
+impl std::fmt::Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} OVER (", self.function)?;
+        if !self.partition_by.is_empty() {
+            let columns: Vec<String> = self
+                .partition_by
+                .iter()
+                .map(|c| quote_identifier(c))
+                .collect();
+            write!(f, "PARTITION BY {}", columns.join(", "))?;
+        }
+        if !self.order_by.is_empty() {
+            if !self.partition_by.is_empty() {
+                f.write_str(" ")?;
+            }
+            let order: Vec<String> = self.order_by.iter().map(Sort::to_sql).collect();
+            write!(f, "ORDER BY {}", order.join(", "))?;
+        }
+        f.write_str(")")?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {}", quote_identifier(alias))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Window> for String {
+    fn from(window: Window) -> Self {
+        window.to_string()
+    }
+}
+
+/// Entry in the select list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SelectItem {
+    /// Raw projection, emitted verbatim
+    Raw(String),
+    /// Column name, quoted with [`quote_identifier`]
+    Column(String),
+    /// Expression with bound parameters and an optional alias
+    Expr(Expr, Option<String>),
+}
+
+/// Entry in the ORDER BY list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum OrderItem {
+    Sort(Sort),
+    Expr(Expr, crate::SortOrder),
+}
+
+/// Named `WITH` entry
+#[derive(Debug, Serialize, Deserialize)]
+struct CommonTableExpression {
+    name: String,
+    query: Box<QueryBuilder>,
+    /// Recursive member joined to `query` with `UNION ALL`
+    recursive: Option<Box<QueryBuilder>>,
+}
+
 /// Join clause for complex queries
+#[derive(Debug, Serialize, Deserialize)]
 struct JoinClause {
     join_type: crate::JoinType,
     table: String,
@@ -139,6 +267,7 @@ struct JoinClause {
 }
 
 /// Aggregate clause for aggregation queries
+#[derive(Debug, Serialize, Deserialize)]
 struct AggregateClause {
     function: Aggregate,
     column: String,
@@ -149,8 +278,9 @@ impl QueryBuilder {
     /// Create a new query builder
     pub fn new(table: impl Into<String>) -> Self {
         Self {
+            ctes: Vec::new(),
             table: table.into(),
-            select_columns: vec!["*".to_string()],
+            select_columns: vec![SelectItem::Raw("*".to_string())],
             joins: Vec::new(),
             where_clauses: Vec::new(),
             group_by: Vec::new(),
@@ -164,8 +294,272 @@ impl QueryBuilder {
     }
 
     /// Select specific columns
-    pub fn select(mut self, columns: Vec<impl Into<String>>) -> Self {
-        self.select_columns = columns.into_iter().map(|c| c.into()).collect();
+    ///
+    /// Accepts anything iterable over string-like column names, e.g.
+    /// `&["id", "email"]` or `vec!["id", "email"]`.
+    pub fn select<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.select_columns = columns
+            .into_iter()
+            .map(|c| SelectItem::Column(c.as_ref().to_string()))
+            .collect();
+        self
+    }
+
+    /// Select a raw SQL projection such as `"count(*) as n, is_active"`
+    ///
+    /// The expression is emitted verbatim, so it must not contain user input.
+    pub fn select_raw(mut self, expression: impl Into<String>) -> Self {
+        self.select_columns = vec![SelectItem::Raw(expression.into())];
+        self
+    }
+
+    /// Append an expression to the select list under `alias`
+    ///
+    /// Values inside the expression are bound as parameters, ahead of any
+    /// WHERE parameters. Like [`select_window`](Self::select_window), this
+    /// keeps the columns already selected; call [`select`](Self::select)
+    /// first to replace the default `*`.
+    ///
+    /// # Examples
+    ///
+    /// Pivot-style report with conditional aggregates:
+    ///
+    /// ```rust
+    /// use libsql_orm::{case_when, col, func, QueryBuilder};
+    ///
+    /// let active = case_when(col("is_active").eq(true), 1i64).otherwise(0i64);
+    /// let (sql, params) = QueryBuilder::new("users")
+    ///     .select(["country"])
+    ///     .select_expr(func("sum", [active]), "active_users")
+    ///     .select_expr(func("count", [col("*")]), "total_users")
+    ///     .group_by(vec!["country"])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT "country", sum(CASE WHEN "is_active" = ? THEN ? ELSE ? END) AS "active_users", count(*) AS "total_users" FROM "users" GROUP BY "country""#
+    /// );
+    /// assert_eq!(params.len(), 3);
+    /// ```
+    pub fn select_expr(mut self, expr: impl Into<Expr>, alias: impl Into<String>) -> Self {
+        self.select_columns
+            .push(SelectItem::Expr(expr.into(), Some(alias.into())));
+        self
+    }
+
+    /// Select a value from a JSON column as `json_extract(column, path) AS alias`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::QueryBuilder;
+    ///
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .select(["id"])
+    ///     .select_json("prefs", "$.theme", "theme")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT "id", json_extract("prefs", ?) AS "theme" FROM "users""#);
+    /// ```
+    pub fn select_json(
+        self,
+        column: impl Into<String>,
+        path: impl Into<String>,
+        alias: impl Into<String>,
+    ) -> Self {
+        self.select_expr(crate::json_extract(crate::col(column), path), alias)
+    }
+
+    /// Append `group_concat(column, separator)` to the select list
+    ///
+    /// Collects each group's values of `column` into one delimited string,
+    /// aliased as the column's name, so a parent row and its children come
+    /// back in a single query. Read the column into
+    /// [`de::Delimited`](crate::de::Delimited); groups without values, such
+    /// as parents with no children under a left join, are NULL and read as
+    /// empty. For another alias, pass [`group_concat`](crate::group_concat)
+    /// to [`select_expr`](Self::select_expr).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{JoinType, QueryBuilder};
+    ///
+    /// let (sql, params) = QueryBuilder::new("posts")
+    ///     .select(["posts.id", "posts.title"])
+    ///     .join(JoinType::Left, "tags", "tags.post_id = posts.id")
+    ///     .group_by(vec!["posts.id"])
+    ///     .aggregate_group_concat("tags.tag", "|")
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT "posts"."id", "posts"."title", group_concat("tags"."tag", ?) AS "tag" FROM "posts" LEFT JOIN "tags" ON tags.post_id = posts.id GROUP BY "posts"."id""#
+    /// );
+    /// assert_eq!(params.len(), 1);
+    /// ```
+    pub fn aggregate_group_concat(self, column: &str, separator: impl Into<String>) -> Self {
+        let alias = unqualified(column).to_string();
+        self.select_expr(crate::group_concat(crate::col(column), separator), alias)
+    }
+
+    /// Append `json_group_array(column)` to the select list
+    ///
+    /// Collects each group's values of `column` into a JSON array, aliased as
+    /// the column's name, which reads into a `Vec` field. Groups without
+    /// non-NULL values, such as parents with no children under a left join,
+    /// get `[]` rather than `[null]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{JoinType, QueryBuilder};
+    ///
+    /// let (sql, _) = QueryBuilder::new("posts")
+    ///     .select(["posts.id"])
+    ///     .join(JoinType::Left, "tags", "tags.post_id = posts.id")
+    ///     .group_by(vec!["posts.id"])
+    ///     .aggregate_json_group_array("tags.tag")
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT "posts"."id", CASE WHEN count("tags"."tag") = ? THEN json_array() ELSE json_group_array("tags"."tag") END AS "tag" FROM "posts" LEFT JOIN "tags" ON tags.post_id = posts.id GROUP BY "posts"."id""#
+    /// );
+    /// ```
+    pub fn aggregate_json_group_array(self, column: &str) -> Self {
+        let alias = unqualified(column).to_string();
+        let values = crate::json_group_array(crate::col(column));
+        self.select_expr(or_empty_json(column, "json_array", values), alias)
+    }
+
+    /// Append `json_group_object(key, value)` to the select list
+    ///
+    /// Collects each group's `key`/`value` pairs into a JSON object, aliased
+    /// as the value column's name, which reads into a `HashMap` or struct
+    /// field. Groups without keys get `{}`, as with
+    /// [`aggregate_json_group_array`](Self::aggregate_json_group_array).
+    pub fn aggregate_json_group_object(self, key: &str, value: &str) -> Self {
+        let alias = unqualified(value).to_string();
+        let pairs = crate::json_group_object(crate::col(key), crate::col(value));
+        self.select_expr(or_empty_json(key, "json_object", pairs), alias)
+    }
+
+    /// Append a window function expression to the select list
+    ///
+    /// Accepts either a raw expression such as
+    /// `"row_number() OVER (PARTITION BY user_id ORDER BY created_at DESC) AS rn"`
+    /// or a [`Window`]. Unlike [`select_raw`](Self::select_raw) this keeps the
+    /// columns already selected (including the default `*`).
+    ///
+    /// Window functions are standard SQLite; note that the native `turso`
+    /// backend does not execute them yet, while remote libSQL databases do.
+    ///
+    /// # Examples
+    ///
+    /// Latest post per user, by ranking in a CTE and keeping rank 1:
+    ///
+    /// ```rust
+    /// use libsql_orm::{QueryBuilder, FilterOperator, Filter, Sort, Window};
+    ///
+    /// let ranked = QueryBuilder::new("posts").select_window(
+    ///     Window::new("row_number()")
+    ///         .partition_by(["user_id"])
+    ///         .order_by(Sort::desc("created_at"))
+    ///         .alias("rn"),
+    /// );
+    /// let (sql, _) = QueryBuilder::new("ranked")
+    ///     .with("ranked", ranked)
+    ///     .r#where(FilterOperator::Single(Filter::eq("rn", 1i64)))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"WITH "ranked" AS (SELECT *, row_number() OVER (PARTITION BY "user_id" ORDER BY "created_at" DESC) AS "rn" FROM "posts") SELECT * FROM "ranked" WHERE "rn" = ?"#
+    /// );
+    /// ```
+    pub fn select_window(mut self, window: impl Into<String>) -> Self {
+        self.select_columns.push(SelectItem::Raw(window.into()));
+        self
+    }
+
+    /// Add a common table expression
+    ///
+    /// The CTE can then be used as the query's table or in a join. Its
+    /// parameters are bound before those of the main query.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{QueryBuilder, FilterOperator, Filter};
+    ///
+    /// let recent = QueryBuilder::new("posts")
+    ///     .r#where(FilterOperator::Single(Filter::gt("created_at", "2024-01-01")));
+    /// let (sql, params) = QueryBuilder::new("recent")
+    ///     .with("recent", recent)
+    ///     .select(["user_id"])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"WITH "recent" AS (SELECT * FROM "posts" WHERE "created_at" > ?) SELECT "user_id" FROM "recent""#
+    /// );
+    /// assert_eq!(params.len(), 1);
+    /// ```
+    pub fn with(mut self, name: impl Into<String>, query: QueryBuilder) -> Self {
+        self.ctes.push(CommonTableExpression {
+            name: name.into(),
+            query: Box::new(query),
+            recursive: None,
+        });
+        self
+    }
+
+    /// Add a recursive common table expression
+    ///
+    /// Emits `WITH RECURSIVE name AS (anchor UNION ALL recursive)`. The
+    /// recursive member refers back to `name`, typically through a join.
+    ///
+    /// The SQL is standard SQLite; note that the native `turso` backend does
+    /// not execute recursive CTEs yet, while remote libSQL databases do.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{QueryBuilder, FilterOperator, Filter};
+    ///
+    /// // Everyone who reports, directly or indirectly, to employee 1
+    /// let anchor = QueryBuilder::new("employees")
+    ///     .select(["id", "manager_id"])
+    ///     .r#where(FilterOperator::Single(Filter::eq("id", 1i64)));
+    /// let step = QueryBuilder::new("employees")
+    ///     .select(["employees.id", "employees.manager_id"])
+    ///     .inner_join("chain", "employees.manager_id = chain.id");
+    /// let (sql, _) = QueryBuilder::new("chain")
+    ///     .with_recursive("chain", anchor, step)
+    ///     .select(["id"])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"WITH RECURSIVE "chain" AS (SELECT "id", "manager_id" FROM "employees" WHERE "id" = ? UNION ALL SELECT "employees"."id", "employees"."manager_id" FROM "employees" INNER JOIN "chain" ON employees.manager_id = chain.id) SELECT "id" FROM "chain""#
+    /// );
+    /// ```
+    pub fn with_recursive(
+        mut self,
+        name: impl Into<String>,
+        anchor: QueryBuilder,
+        recursive: QueryBuilder,
+    ) -> Self {
+        self.ctes.push(CommonTableExpression {
+            name: name.into(),
+            query: Box::new(anchor),
+            recursive: Some(Box::new(recursive)),
+        });
         self
     }
 
@@ -202,9 +596,46 @@ impl QueryBuilder {
         self
     }
 
+    /// Add an `INNER JOIN`
+    ///
+    /// Filters and sorts may then refer to table-qualified columns such as
+    /// `"posts.title"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{QueryBuilder, FilterOperator, Filter, Sort};
+    ///
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .select(["users.name", "posts.title"])
+    ///     .inner_join("posts", "posts.user_id = users.id")
+    ///     .r#where(FilterOperator::Single(Filter::eq("posts.published", true)))
+    ///     .order_by(Sort::asc("posts.title"))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT "users"."name", "posts"."title" FROM "users" INNER JOIN "posts" ON posts.user_id = users.id WHERE "posts"."published" = ? ORDER BY "posts"."title" ASC"#
+    /// );
+    /// ```
+    pub fn inner_join(self, table: impl Into<String>, condition: impl Into<String>) -> Self {
+        self.join(crate::JoinType::Inner, table, condition)
+    }
+
+    /// Add a `LEFT JOIN`
+    ///
+    /// Columns from the joined table are `NULL` for rows without a match, so
+    /// map them to `Option` fields when executing into a [`FromRow`] struct.
+    pub fn left_join(self, table: impl Into<String>, condition: impl Into<String>) -> Self {
+        self.join(crate::JoinType::Left, table, condition)
+    }
+
     /// Add a where clause
-    pub fn r#where(mut self, filter: FilterOperator) -> Self {
-        self.where_clauses.push(filter);
+    ///
+    /// Accepts a [`FilterOperator`], a bare [`Filter`](crate::Filter), or an
+    /// [`Expr`](crate::Expr); multiple clauses are combined with AND.
+    pub fn r#where(mut self, filter: impl Into<FilterOperator>) -> Self {
+        self.where_clauses.push(filter.into());
         self
     }
 
@@ -215,20 +646,45 @@ impl QueryBuilder {
     }
 
     /// Add a having clause
-    pub fn having(mut self, filter: FilterOperator) -> Self {
-        self.having.push(filter);
+    pub fn having(mut self, filter: impl Into<FilterOperator>) -> Self {
+        self.having.push(filter.into());
         self
     }
 
     /// Add an order by clause
     pub fn order_by(mut self, sort: Sort) -> Self {
-        self.order_by.push(sort);
+        self.order_by.push(OrderItem::Sort(sort));
+        self
+    }
+
+    /// Add an order by clause on an expression, e.g. a `CASE` ranking
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{case_when, col, QueryBuilder, SortOrder};
+    ///
+    /// // Admins first, then moderators, then everyone else
+    /// let rank = case_when(col("role").eq("admin"), 0i64)
+    ///     .when(col("role").eq("moderator"), 1i64)
+    ///     .otherwise(2i64);
+    /// let (sql, _) = QueryBuilder::new("users")
+    ///     .order_by_expr(rank, SortOrder::Asc)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sql,
+    ///     r#"SELECT * FROM "users" ORDER BY CASE WHEN "role" = ? THEN ? WHEN "role" = ? THEN ? ELSE ? END ASC"#
+    /// );
+    /// ```
+    pub fn order_by_expr(mut self, expr: impl Into<Expr>, order: crate::SortOrder) -> Self {
+        self.order_by.push(OrderItem::Expr(expr.into(), order));
         self
     }
 
     /// Add multiple order by clauses
     pub fn order_by_multiple(mut self, sorts: Vec<Sort>) -> Self {
-        self.order_by.extend(sorts);
+        self.order_by.extend(sorts.into_iter().map(OrderItem::Sort));
         self
     }
 
@@ -267,57 +723,67 @@ impl QueryBuilder {
 
     /// Select all columns
     pub fn select_all(mut self) -> Self {
-        self.select_columns = vec!["*".to_string()];
+        self.select_columns = vec![SelectItem::Raw("*".to_string())];
         self
     }
 
     /// Select specific columns
     pub fn select_columns(mut self, columns: &[&str]) -> Self {
-        self.select_columns = columns.iter().map(|&c| c.to_string()).collect();
+        self.select_columns = columns
+            .iter()
+            .map(|&c| SelectItem::Column(c.to_string()))
+            .collect();
         self
     }
 
     /// Select a single column
     pub fn select_column(mut self, column: &str) -> Self {
-        self.select_columns = vec![column.to_string()];
+        self.select_columns = vec![SelectItem::Column(column.to_string())];
         self
     }
 
     /// Select count
     pub fn select_count(mut self) -> Self {
-        self.select_columns = vec!["COUNT(*)".to_string()];
+        self.select_columns = vec![SelectItem::Raw("COUNT(*)".to_string())];
         self
     }
 
     /// Select aggregate
     pub fn select_aggregate(mut self, aggregate: &str) -> Self {
-        self.select_columns = vec![aggregate.to_string()];
+        self.select_columns = vec![SelectItem::Raw(aggregate.to_string())];
         self
     }
 
     /// Select distinct
     pub fn select_distinct(mut self, column: &str) -> Self {
-        self.select_columns = vec![column.to_string()];
+        self.select_columns = vec![SelectItem::Column(column.to_string())];
         self.distinct = true;
         self
     }
 
     /// Add where condition
+    ///
+    /// Equivalent to [`Filter::raw`](crate::Filter::raw): `params` are bound
+    /// to the condition's `?` placeholders.
     pub fn where_condition(
         mut self,
         condition: &str,
-        _params: impl Into<Vec<crate::compat::LibsqlValue>>,
+        params: impl Into<Vec<crate::compat::LibsqlValue>>,
     ) -> Self {
-        // This is a simplified implementation - in a real implementation you'd parse the condition
+        let params = params.into().into_iter().map(Value::from).collect();
         self.where_clauses
-            .push(FilterOperator::Custom(condition.to_string()));
+            .push(FilterOperator::Raw(condition.to_string(), params));
         self
     }
 
     /// Add search
+    ///
+    /// Matches rows whose `field` contains `query`, ignoring case; see
+    /// [`SearchFilter`](crate::SearchFilter). The query is bound as a
+    /// parameter and matched literally, so `%` and `_` are not wildcards.
     pub fn search(mut self, field: &str, query: &str) -> Self {
-        let condition = format!("{field} LIKE '%{query}%'");
-        self.where_clauses.push(FilterOperator::Custom(condition));
+        self.where_clauses
+            .push(crate::SearchFilter::new_single_field(field, query).to_filter_operator());
         self
     }
 
@@ -344,59 +810,59 @@ impl QueryBuilder {
         self
     }
 
-    /// Add having condition
+    /// Add having condition, binding `params` to its `?` placeholders
     pub fn having_condition(
         mut self,
         condition: &str,
-        _params: impl Into<Vec<crate::compat::LibsqlValue>>,
+        params: impl Into<Vec<crate::compat::LibsqlValue>>,
     ) -> Self {
-        // This is a simplified implementation
+        let params = params.into().into_iter().map(Value::from).collect();
         self.having
-            .push(FilterOperator::Custom(condition.to_string()));
+            .push(FilterOperator::Raw(condition.to_string(), params));
         self
     }
 
     /// Add where in clause
+    ///
+    /// Shorthand for `r#where` with [`Filter::in_subquery`](crate::Filter::in_subquery);
+    /// the subquery's parameters are bound along with the outer query's.
     pub fn where_in(mut self, field: &str, subquery: QueryBuilder) -> Self {
-        let (subquery_sql, _) = subquery.build().unwrap_or_default();
-        let condition = format!("{field} IN ({subquery_sql})");
-        self.where_clauses.push(FilterOperator::Custom(condition));
+        self.where_clauses
+            .push(FilterOperator::Single(crate::Filter::in_subquery(
+                field, subquery,
+            )));
         self
     }
 
     /// Execute count query
-    pub async fn execute_count(&self, db: &Database) -> Result<u64> {
+    pub async fn execute_count(&self, db: &impl Executor) -> Result<u64> {
         let (sql, params) = self.build_count()?;
-        let mut rows = db.query(&sql, params).await?;
-
-        if let Some(row) = rows.next().await? {
-            row.get_value(0)
-                .ok()
-                .and_then(|v| match v {
-                    crate::compat::LibsqlValue::Integer(i) => Some(i as u64),
-                    _ => None,
-                })
-                .ok_or_else(|| crate::Error::Query("Failed to get count".to_string()))
-        } else {
-            Err(crate::Error::Query("No count result".to_string()))
-        }
+        let params = params.into_iter().map(Value::from).collect();
+        let rows = db.query_statement(Some(&self.table), &sql, params).await?;
+        crate::model::single_count(rows)
     }
 
-    /// Execute aggregate query
-    pub async fn execute_aggregate(&self, db: &Database) -> Result<Vec<crate::compat::LibsqlRow>> {
-        let (sql, params) = self.build()?;
-        let mut rows = db.query(&sql, params).await?;
-        let mut results = Vec::new();
-        while let Some(row) = rows.next().await? {
-            results.push(row);
-        }
-        Ok(results)
+    /// Execute aggregate query, returning each row keyed by column name
+    pub async fn execute_aggregate(&self, db: &impl Executor) -> Result<Vec<crate::Row>> {
+        let (sql, params) = self.to_sql()?;
+        db.query_statement(Some(&self.table), &sql, params).await
     }
 
     /// Build the SQL query
+    ///
+    /// If the statement would bind more than
+    /// [`MAX_PARAMETERS`](crate::chunking::MAX_PARAMETERS) values, every
+    /// `IN` list is bound as a single array instead of value by value.
     pub fn build(&self) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
-        let mut sql = String::new();
-        let mut params = Vec::new();
+        within_parameter_budget(|lists| self.build_statement(lists))
+    }
+
+    pub(crate) fn build_statement(
+        &self,
+        lists: ListBinding,
+    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        // WITH clause
+        let (mut sql, mut params) = self.build_with_clause(lists)?;
 
         // SELECT clause
         sql.push_str("SELECT ");
@@ -405,22 +871,46 @@ impl QueryBuilder {
         }
 
         if let Some(agg) = &self.aggregate {
-            sql.push_str(&format!("{}({})", agg.function, agg.column));
+            sql.push_str(&format!(
+                "{}({})",
+                agg.function,
+                quote_identifier(&agg.column)
+            ));
             if let Some(alias) = &agg.alias {
-                sql.push_str(&format!(" AS {alias}"));
+                sql.push_str(&format!(" AS {}", quote_identifier(alias)));
             }
         } else {
-            sql.push_str(&self.select_columns.join(", "));
+            for (i, item) in self.select_columns.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(", ");
+                }
+                match item {
+                    SelectItem::Raw(column) => sql.push_str(column),
+                    SelectItem::Column(column) => sql.push_str(&quote_identifier(column)),
+                    SelectItem::Expr(expr, alias) => {
+                        let (expr_sql, expr_params) = expr.build();
+                        sql.push_str(&expr_sql);
+                        params.extend(expr_params);
+                        if let Some(alias) = alias {
+                            sql.push_str(&format!(" AS {}", quote_identifier(alias)));
+                        }
+                    }
+                }
+            }
         }
 
         // FROM clause
-        sql.push_str(&format!(" FROM {}", self.table));
+        sql.push_str(&format!(" FROM {}", quote_identifier(&self.table)));
 
         // JOIN clauses
         for join in &self.joins {
-            sql.push_str(&format!(" {} {}", join.join_type, join.table));
+            sql.push_str(&format!(
+                " {} {}",
+                join.join_type,
+                quote_identifier(&join.table)
+            ));
             if let Some(alias) = &join.alias {
-                sql.push_str(&format!(" AS {alias}"));
+                sql.push_str(&format!(" AS {}", quote_identifier(alias)));
             }
             sql.push_str(&format!(" ON {}", join.condition));
         }
@@ -428,20 +918,21 @@ impl QueryBuilder {
         // WHERE clause
         if !self.where_clauses.is_empty() {
             sql.push_str(" WHERE ");
-            let (where_sql, where_params) = self.build_where_clause(&self.where_clauses)?;
+            let (where_sql, where_params) = build_where_clause(&self.where_clauses, lists)?;
             sql.push_str(&where_sql);
             params.extend(where_params);
         }
 
         // GROUP BY clause
         if !self.group_by.is_empty() {
-            sql.push_str(&format!(" GROUP BY {}", self.group_by.join(", ")));
+            let columns: Vec<String> = self.group_by.iter().map(|c| quote_identifier(c)).collect();
+            sql.push_str(&format!(" GROUP BY {}", columns.join(", ")));
         }
 
         // HAVING clause
         if !self.having.is_empty() {
             sql.push_str(" HAVING ");
-            let (having_sql, having_params) = self.build_where_clause(&self.having)?;
+            let (having_sql, having_params) = build_where_clause(&self.having, lists)?;
             sql.push_str(&having_sql);
             params.extend(having_params);
         }
@@ -449,40 +940,98 @@ impl QueryBuilder {
         // ORDER BY clause
         if !self.order_by.is_empty() {
             sql.push_str(" ORDER BY ");
-            let order_clauses: Vec<String> = self
-                .order_by
-                .iter()
-                .map(|sort| format!("{} {}", sort.column, sort.order))
-                .collect();
-            sql.push_str(&order_clauses.join(", "));
+            for (i, item) in self.order_by.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(", ");
+                }
+                match item {
+                    OrderItem::Sort(sort) => sql.push_str(&sort.to_sql()),
+                    OrderItem::Expr(expr, order) => {
+                        let (expr_sql, expr_params) = expr.build();
+                        sql.push_str(&format!("{expr_sql} {order}"));
+                        params.extend(expr_params);
+                    }
+                }
+            }
         }
 
-        // LIMIT and OFFSET
-        if let Some(limit) = self.limit {
-            sql.push_str(&format!(" LIMIT {limit}"));
+        // LIMIT and OFFSET; SQLite only accepts OFFSET after a LIMIT, and -1
+        // means no limit
+        match (self.limit, self.offset) {
+            (Some(limit), _) => {
+                sql.push_str(" LIMIT ?");
+                params.push(crate::compat::LibsqlValue::Integer(limit.into()));
+            }
+            (None, Some(_)) => sql.push_str(" LIMIT -1"),
+            (None, None) => {}
         }
         if let Some(offset) = self.offset {
-            sql.push_str(&format!(" OFFSET {offset}"));
+            sql.push_str(" OFFSET ?");
+            params.push(crate::compat::LibsqlValue::Integer(offset.into()));
         }
 
         Ok((sql, params))
     }
 
+    /// Render the query without executing it
+    ///
+    /// Returns the same SQL as [`build`](Self::build) with parameters as
+    /// [`Value`]s in placeholder order, which is handy for logging, tests, and
+    /// `EXPLAIN`. Booleans are reported as the integers that get bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libsql_orm::{QueryBuilder, FilterOperator, Filter, Value};
+    ///
+    /// let (sql, params) = QueryBuilder::new("users")
+    ///     .r#where(FilterOperator::Single(Filter::eq("email", "a@example.com")))
+    ///     .limit(1)
+    ///     .to_sql()
+    ///     .unwrap();
+    /// assert_eq!(sql, r#"SELECT * FROM "users" WHERE "email" = ? LIMIT ?"#);
+    /// assert_eq!(
+    ///     params,
+    ///     vec![Value::Text("a@example.com".to_string()), Value::Integer(1)]
+    /// );
+    /// ```
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>)> {
+        let (sql, params) = self.build()?;
+        Ok((sql, params.into_iter().map(Value::from).collect()))
+    }
+
+    /// Render the count query without executing it; see [`to_sql`](Self::to_sql)
+    pub fn to_count_sql(&self) -> Result<(String, Vec<Value>)> {
+        let (sql, params) = self.build_count()?;
+        Ok((sql, params.into_iter().map(Value::from).collect()))
+    }
+
     /// Build a count query
     pub fn build_count(&self) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
-        let mut sql = String::new();
-        let mut params = Vec::new();
+        within_parameter_budget(|lists| self.build_count_statement(lists))
+    }
+
+    fn build_count_statement(
+        &self,
+        lists: ListBinding,
+    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+        // WITH clause
+        let (mut sql, mut params) = self.build_with_clause(lists)?;
 
         sql.push_str("SELECT COUNT(*)");
 
         // FROM clause
-        sql.push_str(&format!(" FROM {}", self.table));
+        sql.push_str(&format!(" FROM {}", quote_identifier(&self.table)));
 
         // JOIN clauses
         for join in &self.joins {
-            sql.push_str(&format!(" {} {}", join.join_type, join.table));
+            sql.push_str(&format!(
+                " {} {}",
+                join.join_type,
+                quote_identifier(&join.table)
+            ));
             if let Some(alias) = &join.alias {
-                sql.push_str(&format!(" AS {alias}"));
+                sql.push_str(&format!(" AS {}", quote_identifier(alias)));
             }
             sql.push_str(&format!(" ON {}", join.condition));
         }
@@ -490,20 +1039,21 @@ impl QueryBuilder {
         // WHERE clause
         if !self.where_clauses.is_empty() {
             sql.push_str(" WHERE ");
-            let (where_sql, where_params) = self.build_where_clause(&self.where_clauses)?;
+            let (where_sql, where_params) = build_where_clause(&self.where_clauses, lists)?;
             sql.push_str(&where_sql);
             params.extend(where_params);
         }
 
         // GROUP BY clause
         if !self.group_by.is_empty() {
-            sql.push_str(&format!(" GROUP BY {}", self.group_by.join(", ")));
+            let columns: Vec<String> = self.group_by.iter().map(|c| quote_identifier(c)).collect();
+            sql.push_str(&format!(" GROUP BY {}", columns.join(", ")));
         }
 
         // HAVING clause
         if !self.having.is_empty() {
             sql.push_str(" HAVING ");
-            let (having_sql, having_params) = self.build_where_clause(&self.having)?;
+            let (having_sql, having_params) = build_where_clause(&self.having, lists)?;
             sql.push_str(&having_sql);
             params.extend(having_params);
         }
@@ -511,352 +1061,404 @@ impl QueryBuilder {
         Ok((sql, params))
     }
 
-    /// Build where clause from filter operators
-    fn build_where_clause(
+    /// Build the leading `WITH` clause, if any CTEs were added
+    fn build_with_clause(
         &self,
-        filters: &[FilterOperator],
+        lists: ListBinding,
     ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
         let mut sql = String::new();
         let mut params = Vec::new();
 
-        for (i, filter) in filters.iter().enumerate() {
-            if i > 0 {
-                sql.push_str(" AND ");
-            }
-            let (filter_sql, filter_params) = self.build_filter_operator(filter)?;
-            sql.push_str(&filter_sql);
-            params.extend(filter_params);
+        if self.ctes.is_empty() {
+            return Ok((sql, params));
         }
 
-        Ok((sql, params))
-    }
-
-    /// Build filter operator
-    fn build_filter_operator(
-        &self,
-        filter: &FilterOperator,
-    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
-        match filter {
-            FilterOperator::Single(filter) => self.build_filter(filter),
-            FilterOperator::And(filters) => {
-                let mut sql = String::new();
-                let mut params = Vec::new();
-                sql.push('(');
-                for (i, filter) in filters.iter().enumerate() {
-                    if i > 0 {
-                        sql.push_str(" AND ");
-                    }
-                    let (filter_sql, filter_params) = self.build_filter_operator(filter)?;
-                    sql.push_str(&filter_sql);
-                    params.extend(filter_params);
-                }
-                sql.push(')');
-                Ok((sql, params))
-            }
-            FilterOperator::Or(filters) => {
-                let mut sql = String::new();
-                let mut params = Vec::new();
-                sql.push('(');
-                for (i, filter) in filters.iter().enumerate() {
-                    if i > 0 {
-                        sql.push_str(" OR ");
-                    }
-                    let (filter_sql, filter_params) = self.build_filter_operator(filter)?;
-                    sql.push_str(&filter_sql);
-                    params.extend(filter_params);
-                }
-                sql.push(')');
-                Ok((sql, params))
-            }
-            FilterOperator::Not(filter) => {
-                let (filter_sql, filter_params) = self.build_filter_operator(filter)?;
-                Ok((format!("NOT ({filter_sql})"), filter_params))
-            }
-            FilterOperator::Custom(condition) => Ok((condition.clone(), vec![])),
+        sql.push_str("WITH ");
+        if self.ctes.iter().any(|cte| cte.recursive.is_some()) {
+            sql.push_str("RECURSIVE ");
         }
-    }
-
-    /// Build individual filter
-    fn build_filter(
-        &self,
-        filter: &crate::Filter,
-    ) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
-        let mut sql = String::new();
-        let mut params = Vec::new();
-
-        match &filter.operator {
-            Operator::IsNull => {
-                sql.push_str(&format!("{} IS NULL", filter.column));
-            }
-            Operator::IsNotNull => {
-                sql.push_str(&format!("{} IS NOT NULL", filter.column));
+        for (i, cte) in self.ctes.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
             }
-            _ => {
-                sql.push_str(&format!("{} {} ", filter.column, filter.operator));
-                match &filter.value {
-                    FilterValue::Single(value) => {
-                        sql.push('?');
-                        params.push(self.value_to_libsql_value(value));
-                    }
-                    FilterValue::Multiple(values) => {
-                        sql.push('(');
-                        for (i, value) in values.iter().enumerate() {
-                            if i > 0 {
-                                sql.push_str(", ");
-                            }
-                            sql.push('?');
-                            params.push(self.value_to_libsql_value(value));
-                        }
-                        sql.push(')');
-                    }
-                    FilterValue::Range(min, max) => {
-                        sql.push_str("? AND ?");
-                        params.push(self.value_to_libsql_value(min));
-                        params.push(self.value_to_libsql_value(max));
-                    }
-                }
+            let (query_sql, query_params) = cte.query.build_statement(lists)?;
+            sql.push_str(&format!("{} AS ({query_sql}", quote_identifier(&cte.name)));
+            params.extend(query_params);
+            if let Some(recursive) = &cte.recursive {
+                let (recursive_sql, recursive_params) = recursive.build_statement(lists)?;
+                sql.push_str(&format!(" UNION ALL {recursive_sql}"));
+                params.extend(recursive_params);
             }
+            sql.push(')');
         }
+        sql.push(' ');
 
         Ok((sql, params))
     }
 
-    /// Convert our Value type to crate::compat::LibsqlValue
-    fn value_to_libsql_value(&self, value: &Value) -> crate::compat::LibsqlValue {
-        match value {
-            Value::Null => crate::compat::LibsqlValue::Null,
-            Value::Integer(i) => crate::compat::LibsqlValue::Integer(*i),
-            Value::Real(f) => crate::compat::LibsqlValue::Real(*f),
-            Value::Text(s) => crate::compat::LibsqlValue::Text(s.clone()),
-            Value::Blob(b) => crate::compat::LibsqlValue::Blob(b.clone()),
-            Value::Boolean(b) => crate::compat::LibsqlValue::Integer(if *b { 1 } else { 0 }),
-        }
-    }
-
     /// Execute the query
-    pub async fn execute<T>(&self, db: &Database) -> Result<Vec<T>>
+    pub async fn execute<T>(&self, db: &impl Executor) -> Result<Vec<T>>
     where
         T: serde::de::DeserializeOwned,
     {
-        let (sql, params) = self.build()?;
-        #[cfg(feature = "turso")]
-        {
-            let mut stmt = db.inner.prepare(&sql).await?;
-            let columns: Vec<String> = stmt
-                .columns()
-                .iter()
-                .map(|column| column.name().to_string())
-                .collect();
+        let (sql, params) = self.to_sql()?;
+        db.query_statement(Some(&self.table), &sql, params)
+            .await?
+            .into_iter()
+            .map(|row| {
+                let row = row
+                    .into_iter()
+                    .map(|(column, value)| {
+                        let value = self.column_value(&column, value);
+                        (column, value)
+                    })
+                    .collect();
+                crate::de::from_row(row)
+            })
+            .collect()
+    }
 
-            let mut rows = if params.is_empty() {
-                stmt.query(()).await?
-            } else {
-                stmt.query(params).await?
+    /// Stream the result as CSV, returning the number of rows written
+    ///
+    /// The first record holds the column names: the selected columns and
+    /// aliases in order, the table's columns for `SELECT *`, or otherwise the
+    /// first row's columns sorted by name. Rows are read
+    /// [`EXPORT_BATCH_SIZE`](crate::model::EXPORT_BATCH_SIZE) at a time and
+    /// written as they arrive, so large results are never held in memory;
+    /// give the query an [`order_by`](Self::order_by) so batches line up.
+    /// `NULL` becomes an empty field, and blobs are written as hex.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{Database, Filter, QueryBuilder, Result, Sort};
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let file = std::fs::File::create("active_users.csv")?;
+    /// let written = QueryBuilder::new("users")
+    ///     .select(["id", "name", "email"])
+    ///     .r#where(Filter::eq("is_active", true))
+    ///     .order_by(Sort::asc("id"))
+    ///     .export_csv(std::io::BufWriter::new(file), db)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_csv<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        db: &impl Executor,
+    ) -> Result<u64> {
+        let batch_size = crate::model::EXPORT_BATCH_SIZE;
+        let start = self.offset.unwrap_or(0);
+        let mut columns = self.csv_columns(db).await?;
+        if let Some(columns) = &columns {
+            crate::csv::write_record(&mut writer, columns)?;
+        }
+
+        let mut written: u64 = 0;
+        loop {
+            let wanted = match self.limit {
+                Some(limit) => batch_size.min(limit.saturating_sub(written as u32)),
+                None => batch_size,
             };
-            let mut results = Vec::new();
-            while let Some(row) = rows.next().await? {
-                let mut map = HashMap::new();
-                for (i, column_name) in columns.iter().enumerate() {
-                    let value = row
-                        .get_value(i)
-                        .ok()
-                        .unwrap_or(crate::compat::LibsqlValue::Null);
-                    map.insert(
-                        column_name.to_string(),
-                        self.libsql_value_to_json_value_for_column(column_name, &value),
-                    );
+            if wanted == 0 {
+                break;
+            }
+            let (sql, params) = self
+                .clone()
+                .limit(wanted)
+                .offset(start + written as u32)
+                .to_sql()?;
+            let rows = db.query_statement(Some(&self.table), &sql, params).await?;
+
+            for row in &rows {
+                if columns.is_none() {
+                    let mut names: Vec<String> = row.keys().cloned().collect();
+                    names.sort();
+                    crate::csv::write_record(&mut writer, &names)?;
+                    columns = Some(names);
                 }
-                let json_value = serde_json::to_value(map)?;
-                let result: T = serde_json::from_value(json_value)?;
-                results.push(result);
+                let fields = columns
+                    .iter()
+                    .flatten()
+                    .map(|column| crate::csv::field(row.get(column).unwrap_or(&Value::Null)));
+                crate::csv::write_record(&mut writer, fields)?;
+                written += 1;
+            }
+            if rows.len() < wanted as usize {
+                break;
             }
-
-            Ok(results)
         }
 
-        #[cfg(not(feature = "turso"))]
-        {
-            let mut rows = db.query(&sql, params).await?;
-
-            let mut results = Vec::new();
-            while let Some(row) = rows.next().await? {
-                let mut map = HashMap::new();
-                for i in 0..row.column_count() {
-                    if let Some(column_name) = row.column_name(i) {
-                        let value = row
-                            .get_value(i)
-                            .ok()
-                            .unwrap_or(crate::compat::LibsqlValue::Null);
-                        map.insert(
-                            column_name.to_string(),
-                            self.libsql_value_to_json_value_for_column(column_name, &value),
-                        );
-                    }
-                }
-                let json_value = serde_json::to_value(map)?;
-                let result: T = serde_json::from_value(json_value)?;
-                results.push(result);
-            }
+        writer.flush()?;
+        Ok(written)
+    }
 
-            Ok(results)
+    /// The CSV header known before any row is read
+    async fn csv_columns(&self, db: &impl Executor) -> Result<Option<Vec<String>>> {
+        let named: Option<Vec<String>> = self
+            .select_columns
+            .iter()
+            .map(|item| match item {
+                SelectItem::Column(column) if !column.ends_with('*') => {
+                    Some(result_column_name(column))
+                }
+                SelectItem::Column(_) => None,
+                SelectItem::Expr(_, alias) => alias.clone(),
+                SelectItem::Raw(_) => None,
+            })
+            .collect();
+        if named.is_some() {
+            return Ok(named);
         }
+        if !self.csv_header_from_table() {
+            return Ok(None);
+        }
+        let mut columns: Vec<(i64, String)> = db
+            .query_statement(
+                None,
+                &format!("PRAGMA table_info({})", quote_name(&self.table)),
+                vec![],
+            )
+            .await?
+            .into_iter()
+            .filter_map(|row| match (row.get("cid"), row.get("name")) {
+                (Some(Value::Integer(cid)), Some(Value::Text(name))) => Some((*cid, name.clone())),
+                _ => None,
+            })
+            .collect();
+        columns.sort();
+        Ok(Some(columns.into_iter().map(|(_, name)| name).collect()))
     }
 
-    pub async fn execute_model<T>(&self, db: &Database) -> Result<Vec<T>>
+    /// Whether the query is a plain `SELECT *` of its table
+    fn csv_header_from_table(&self) -> bool {
+        matches!(self.select_columns.as_slice(), [SelectItem::Raw(star)] if star == "*")
+            && self.joins.is_empty()
+            && self.ctes.is_empty()
+    }
+
+    /// Execute the query and convert each row into a model
+    pub async fn execute_model<T>(&self, db: &impl Executor) -> Result<Vec<T>>
     where
         T: crate::Model,
     {
-        let (sql, params) = self.build()?;
-        #[cfg(feature = "turso")]
-        {
-            let mut stmt = db.inner.prepare(&sql).await?;
-            let columns: Vec<String> = stmt
-                .columns()
-                .iter()
-                .map(|column| column.name().to_string())
-                .collect();
-
-            let mut rows = if params.is_empty() {
-                stmt.query(()).await?
-            } else {
-                stmt.query(params).await?
-            };
-
-            let mut results = Vec::new();
-            while let Some(row) = rows.next().await? {
-                let mut map = HashMap::new();
-                for (i, column_name) in columns.iter().enumerate() {
-                    let value = row
-                        .get_value(i)
-                        .ok()
-                        .unwrap_or(crate::compat::LibsqlValue::Null);
-                    map.insert(
-                        column_name.to_string(),
-                        self.libsql_value_to_model_value(&value),
-                    );
-                }
-                results.push(T::from_map(map)?);
-            }
-
-            Ok(results)
-        }
-
-        #[cfg(not(feature = "turso"))]
-        {
-            let mut rows = db.query(&sql, params).await?;
-
-            let mut results = Vec::new();
-            while let Some(row) = rows.next().await? {
-                let mut map = HashMap::new();
-                for i in 0..row.column_count() {
-                    if let Some(column_name) = row.column_name(i) {
-                        let value = row
-                            .get_value(i)
-                            .ok()
-                            .unwrap_or(crate::compat::LibsqlValue::Null);
-                        map.insert(
-                            column_name.to_string(),
-                            self.libsql_value_to_model_value(&value),
-                        );
-                    }
-                }
-                results.push(T::from_map(map)?);
-            }
+        let (sql, params) = self.to_sql()?;
+        db.query_statement(Some(&self.table), &sql, params)
+            .await?
+            .into_iter()
+            .map(T::from_map)
+            .collect()
+    }
 
-            Ok(results)
-        }
+    /// Execute the query and map each row through [`FromRow`]
+    ///
+    /// Use this with projections, joins, or aggregates whose shape doesn't
+    /// match a model.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libsql_orm::{QueryBuilder, FromRow, Database, Result};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(FromRow, Deserialize)]
+    /// struct ActiveCount {
+    ///     is_active: bool,
+    ///     n: i64,
+    /// }
+    ///
+    /// # async fn example(db: &Database) -> Result<()> {
+    /// let counts = QueryBuilder::new("users")
+    ///     .select_raw("is_active, count(*) AS n")
+    ///     .group_by(vec!["is_active"])
+    ///     .execute_as::<ActiveCount>(db)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_as<T>(&self, db: &impl Executor) -> Result<Vec<T>>
+    where
+        T: FromRow,
+    {
+        let (sql, params) = self.to_sql()?;
+        db.query_statement(Some(&self.table), &sql, params)
+            .await?
+            .into_iter()
+            .map(T::from_row)
+            .collect()
     }
 
     pub async fn execute_model_paginated<T>(
         &self,
-        db: &Database,
+        db: &impl Executor,
         pagination: &Pagination,
     ) -> Result<PaginatedResult<T>>
     where
         T: crate::Model,
     {
-        let total = self.execute_count(db).await?;
+        if !pagination.count_total {
+            let mut data = self
+                .page_query(pagination, pagination.limit() + 1)?
+                .execute_model::<T>(db)
+                .await?;
+            let has_next = data.len() > pagination.limit() as usize;
+            data.truncate(pagination.limit() as usize);
+            return Ok(PaginatedResult::with_has_next(
+                data,
+                pagination.clone(),
+                has_next,
+            ));
+        }
 
-        let data_builder = self
-            .clone()
-            .limit(pagination.limit())
-            .offset(pagination.offset());
+        let (total, estimated) = self.page_total(db, pagination).await?;
+
+        let data_builder = self.page_query(pagination, pagination.limit())?;
 
         let data = data_builder.execute_model::<T>(db).await?;
 
-        Ok(PaginatedResult::with_total(data, pagination.clone(), total))
+        let mut result = PaginatedResult::with_total(data, pagination.clone(), total);
+        result.pagination.total_estimated = estimated;
+        Ok(result)
     }
 
     /// Execute the query with pagination
     pub async fn execute_paginated<T>(
         &self,
-        db: &Database,
+        db: &impl Executor,
         pagination: &Pagination,
     ) -> Result<PaginatedResult<T>>
     where
         T: serde::de::DeserializeOwned,
     {
+        if !pagination.count_total {
+            let mut data = self
+                .page_query(pagination, pagination.limit() + 1)?
+                .execute::<T>(db)
+                .await?;
+            let has_next = data.len() > pagination.limit() as usize;
+            data.truncate(pagination.limit() as usize);
+            return Ok(PaginatedResult::with_has_next(
+                data,
+                pagination.clone(),
+                has_next,
+            ));
+        }
+
         // Get total count
-        let count_builder = QueryBuilder::new(&self.table).select(vec!["COUNT(*) as count"]);
-
-        let (count_sql, count_params) = count_builder.build_count()?;
-        let mut count_rows = db.query(&count_sql, count_params).await?;
-        let total: u64 = if let Some(row) = count_rows.next().await? {
-            row.get_value(0)
-                .ok()
-                .and_then(|v| match v {
-                    crate::compat::LibsqlValue::Integer(i) => Some(i as u64),
-                    _ => None,
-                })
-                .unwrap_or(0)
-        } else {
-            0
-        };
+        let (total, estimated) = self.page_total(db, pagination).await?;
 
         // Get paginated data
-        let data_builder = self
-            .clone()
-            .limit(pagination.limit())
-            .offset(pagination.offset());
+        let data_builder = self.page_query(pagination, pagination.limit())?;
 
         let data = data_builder.execute::<T>(db).await?;
 
-        Ok(PaginatedResult::with_total(data, pagination.clone(), total))
+        let mut result = PaginatedResult::with_total(data, pagination.clone(), total);
+        result.pagination.total_estimated = estimated;
+        Ok(result)
     }
 
-    /// Convert crate::compat::LibsqlValue to serde_json::Value
-    fn libsql_value_to_json_value(&self, value: &crate::compat::LibsqlValue) -> serde_json::Value {
-        match value {
-            crate::compat::LibsqlValue::Null => serde_json::Value::Null,
-            crate::compat::LibsqlValue::Integer(i) => {
-                serde_json::Value::Number(serde_json::Number::from(*i))
+    /// The total for a page under the pagination's [`CountStrategy`], and
+    /// whether it is an estimate
+    async fn page_total(&self, db: &impl Executor, pagination: &Pagination) -> Result<(u64, bool)> {
+        match &pagination.count_strategy {
+            CountStrategy::Exact => Ok((self.execute_count(db).await?, false)),
+            CountStrategy::Cached(cache) => {
+                let (sql, params) = self.build_count()?;
+                let params: Vec<Value> = params.into_iter().map(Value::from).collect();
+                let key = crate::cache::cache_key(&self.table, &sql, &params);
+                if let Some(total) = cache.get(&key) {
+                    return Ok((total, false));
+                }
+                let rows = db.query_statement(Some(&self.table), &sql, params).await?;
+                let total = crate::model::single_count(rows)?;
+                cache.put(key, total);
+                Ok((total, false))
             }
-            crate::compat::LibsqlValue::Real(f) => {
-                if let Some(n) = serde_json::Number::from_f64(*f) {
-                    serde_json::Value::Number(n)
-                } else {
-                    serde_json::Value::Null
+            CountStrategy::Estimated => {
+                let whole_table = self.ctes.is_empty()
+                    && self.joins.is_empty()
+                    && self.where_clauses.is_empty()
+                    && self.group_by.is_empty()
+                    && self.having.is_empty();
+                if whole_table {
+                    if let Some(estimate) = self.estimate_rows(db).await {
+                        return Ok((estimate, true));
+                    }
                 }
+                Ok((self.execute_count(db).await?, false))
             }
-            crate::compat::LibsqlValue::Text(s) => serde_json::Value::String(s.clone()),
-            crate::compat::LibsqlValue::Blob(b) => serde_json::Value::Array(
-                b.iter()
-                    .map(|&byte| serde_json::Value::Number(serde_json::Number::from(byte)))
-                    .collect(),
-            ),
         }
     }
 
-    fn libsql_value_to_json_value_for_column(
-        &self,
-        column_name: &str,
-        value: &crate::compat::LibsqlValue,
-    ) -> serde_json::Value {
+    /// Approximate row count of the table: the `sqlite_stat1` figure from
+    /// the last `ANALYZE`, else `max(rowid)`, which deletes make an
+    /// overestimate
+    async fn estimate_rows(&self, db: &impl Executor) -> Option<u64> {
+        let stats = db
+            .query_statement(
+                None,
+                "SELECT stat FROM sqlite_stat1 WHERE tbl = ? LIMIT 1",
+                vec![Value::Text(self.table.clone())],
+            )
+            .await;
+        if let Ok(rows) = stats {
+            let rows_in_stat = rows
+                .into_iter()
+                .next()
+                .and_then(|row| match row.get("stat") {
+                    Some(Value::Text(stat)) => stat.split_whitespace().next()?.parse().ok(),
+                    _ => None,
+                });
+            if rows_in_stat.is_some() {
+                return rows_in_stat;
+            }
+        }
+
+        let sql = format!("SELECT max(rowid) FROM {}", quote_identifier(&self.table));
+        let rows = db
+            .query_statement(Some(&self.table), &sql, Vec::new())
+            .await
+            .ok()?;
+        match rows.into_iter().next()?.into_values().next()? {
+            Value::Integer(max) => Some(max.max(0) as u64),
+            Value::Null => Some(0),
+            _ => None,
+        }
+    }
+
+    /// The requested page, ordered by the pagination's sorts after any
+    /// existing ORDER BY terms
+    ///
+    /// The sorts usually come from a request, so each must name a column,
+    /// optionally qualified, rather than an expression.
+    fn page_query(&self, pagination: &Pagination, limit: u32) -> Result<QueryBuilder> {
+        if let Some(sort) = pagination
+            .sort
+            .iter()
+            .find(|sort| !sort.column.split('.').all(is_identifier))
+        {
+            return Err(crate::Error::Validation(format!(
+                "Invalid sort column {:?}",
+                sort.column
+            )));
+        }
+        Ok(self
+            .clone()
+            .order_by_multiple(pagination.sort.clone())
+            .limit(limit)
+            .offset(pagination.offset()))
+    }
+
+    /// A column value for [`execute`](Self::execute), with integers in
+    /// boolean-looking columns reported as booleans to untyped targets
+    fn column_value(&self, column_name: &str, value: Value) -> Value {
         match value {
-            crate::compat::LibsqlValue::Integer(i) if self.is_likely_boolean_column(column_name) => {
-                serde_json::Value::Bool(*i != 0)
+            Value::Integer(i) if self.is_likely_boolean_column(column_name) => {
+                Value::Boolean(i != 0)
             }
-            _ => self.libsql_value_to_json_value(value),
+            value => value,
         }
     }
 
@@ -878,21 +1480,418 @@ impl QueryBuilder {
                     | "archived"
             )
     }
+}
 
-    fn libsql_value_to_model_value(&self, value: &crate::compat::LibsqlValue) -> crate::Value {
-        match value {
-            crate::compat::LibsqlValue::Null => crate::Value::Null,
-            crate::compat::LibsqlValue::Integer(i) => crate::Value::Integer(*i),
-            crate::compat::LibsqlValue::Real(f) => crate::Value::Real(*f),
-            crate::compat::LibsqlValue::Text(s) => crate::Value::Text(s.clone()),
-            crate::compat::LibsqlValue::Blob(b) => crate::Value::Blob(b.clone()),
+/// How the `IN` lists of one statement are bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListBinding {
+    /// One placeholder per value, up to [`MAX_INLINE_LIST_PARAMS`]
+    Inline,
+    /// Every list as a single array parameter
+    Array,
+}
+
+/// Build a statement with inline lists, rebuilding it with array-bound
+/// lists when it would bind more than
+/// [`MAX_PARAMETERS`](crate::chunking::MAX_PARAMETERS) values; lists that
+/// have no array form still bind value by value
+pub(crate) fn within_parameter_budget(
+    build: impl Fn(ListBinding) -> Result<(String, Vec<crate::compat::LibsqlValue>)>,
+) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+    let (sql, params) = build(ListBinding::Inline)?;
+    if params.len() <= crate::chunking::MAX_PARAMETERS {
+        return Ok((sql, params));
+    }
+    build(ListBinding::Array)
+}
+
+/// Build where clause from filter operators
+pub(crate) fn build_where_clause(
+    filters: &[FilterOperator],
+    lists: ListBinding,
+) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+    let mut sql = String::new();
+    let mut params = Vec::new();
+
+    for (i, filter) in filters.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(" AND ");
+        }
+        let (filter_sql, filter_params) = build_filter_operator(filter, lists)?;
+        sql.push_str(&filter_sql);
+        params.extend(filter_params);
+    }
+
+    Ok((sql, params))
+}
+
+/// Build filter operator
+fn build_filter_operator(
+    filter: &FilterOperator,
+    lists: ListBinding,
+) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+    match filter {
+        FilterOperator::Single(filter) => build_filter(filter, lists),
+        // An empty group is vacuously true for AND and false for OR
+        FilterOperator::And(filters) if filters.is_empty() => Ok(("1 = 1".to_string(), vec![])),
+        FilterOperator::And(filters) => {
+            let mut sql = String::new();
+            let mut params = Vec::new();
+            sql.push('(');
+            for (i, filter) in filters.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(" AND ");
+                }
+                let (filter_sql, filter_params) = build_filter_operator(filter, lists)?;
+                sql.push_str(&filter_sql);
+                params.extend(filter_params);
+            }
+            sql.push(')');
+            Ok((sql, params))
+        }
+        // An empty group is vacuously true for AND and false for OR
+        FilterOperator::Or(filters) if filters.is_empty() => Ok(("1 = 0".to_string(), vec![])),
+        FilterOperator::Or(filters) => {
+            let mut sql = String::new();
+            let mut params = Vec::new();
+            sql.push('(');
+            for (i, filter) in filters.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(" OR ");
+                }
+                let (filter_sql, filter_params) = build_filter_operator(filter, lists)?;
+                sql.push_str(&filter_sql);
+                params.extend(filter_params);
+            }
+            sql.push(')');
+            Ok((sql, params))
+        }
+        FilterOperator::Not(filter) => {
+            let (filter_sql, filter_params) = build_filter_operator(filter, lists)?;
+            Ok((format!("NOT ({filter_sql})"), filter_params))
+        }
+        FilterOperator::Custom(condition) => Ok((condition.clone(), vec![])),
+        FilterOperator::Raw(fragment, values) => {
+            let placeholders = count_placeholders(fragment);
+            if placeholders != values.len() {
+                return Err(crate::Error::Query(format!(
+                    "raw filter `{fragment}` has {placeholders} placeholders but {} parameters",
+                    values.len()
+                )));
+            }
+            let params = values.iter().map(value_to_libsql_value).collect();
+            Ok((format!("({fragment})"), params))
+        }
+        FilterOperator::Expr(expr) => Ok(expr.build()),
+        FilterOperator::Exists(subquery) => {
+            let (subquery_sql, subquery_params) = subquery.build_statement(lists)?;
+            Ok((format!("EXISTS ({subquery_sql})"), subquery_params))
+        }
+    }
+}
+
+/// Build individual filter
+fn build_filter(
+    filter: &crate::Filter,
+    lists: ListBinding,
+) -> Result<(String, Vec<crate::compat::LibsqlValue>)> {
+    let mut sql = String::new();
+    let mut params = Vec::new();
+
+    // Collations do not affect pattern operators, so fold both sides instead;
+    // REGEXP gets the pattern's own `(?i)` flag, since lowering it would
+    // change what classes such as `\D` and `\W` match
+    let regexp_nocase = filter.case_insensitive && is_regexp(&filter.operator);
+    let lowered = filter.case_insensitive
+        && matches!(
+            filter.operator,
+            Operator::Like | Operator::NotLike | Operator::Glob | Operator::NotGlob
+        );
+    let mut quoted = quote_identifier(&filter.column);
+    if let FilterValue::JsonPath(path, _) = &filter.value {
+        quoted = format!("json_extract({quoted}, ?)");
+        params.push(crate::compat::LibsqlValue::Text(path.clone()));
+    }
+    let column = if lowered {
+        format!("lower({quoted})")
+    } else if filter.case_insensitive && !regexp_nocase {
+        format!("{quoted} COLLATE NOCASE")
+    } else {
+        quoted.clone()
+    };
+
+    match &filter.operator {
+        Operator::IsNull => {
+            sql.push_str(&format!("{quoted} IS NULL"));
+        }
+        Operator::IsNotNull => {
+            sql.push_str(&format!("{quoted} IS NOT NULL"));
+        }
+        Operator::JsonContains => {
+            let (FilterValue::Single(value) | FilterValue::JsonPath(_, value)) = &filter.value
+            else {
+                return Err(crate::Error::Query(format!(
+                    "JSON containment on {} takes a single value",
+                    filter.column
+                )));
+            };
+            let element = if filter.case_insensitive {
+                "\"value\" COLLATE NOCASE"
+            } else {
+                "\"value\""
+            };
+            sql.push_str(&format!(
+                "EXISTS (SELECT 1 FROM json_each({quoted}) WHERE {element} = ?)"
+            ));
+            params.push(value_to_libsql_value(value));
+        }
+        _ => {
+            sql.push_str(&format!("{} {} ", column, filter.operator));
+            match &filter.value {
+                FilterValue::Single(Value::Text(pattern)) if regexp_nocase => {
+                    sql.push('?');
+                    params.push(crate::compat::LibsqlValue::Text(format!("(?i){pattern}")));
+                }
+                FilterValue::Single(value) | FilterValue::JsonPath(_, value) => {
+                    sql.push_str(if lowered { "lower(?)" } else { "?" });
+                    params.push(value_to_libsql_value(value));
+                    if filter.escaped
+                        && matches!(filter.operator, Operator::Like | Operator::NotLike)
+                    {
+                        sql.push_str(r" ESCAPE '\'");
+                    }
+                }
+                FilterValue::Multiple(values) if values.is_empty() => {
+                    // `IN ()` is not portable; render a constant condition instead
+                    sql.clear();
+                    sql.push_str(match filter.operator {
+                        Operator::NotIn => "1 = 1",
+                        _ => "1 = 0",
+                    });
+                }
+                FilterValue::Multiple(values)
+                    if lists == ListBinding::Array || values.len() > MAX_INLINE_LIST_PARAMS =>
+                {
+                    match list_array(values) {
+                        Some((select, array)) => {
+                            sql.push_str(select);
+                            params.push(crate::compat::LibsqlValue::Text(array));
+                        }
+                        None => push_placeholders(&mut sql, &mut params, values),
+                    }
+                }
+                FilterValue::Multiple(values) => {
+                    push_placeholders(&mut sql, &mut params, values);
+                }
+                FilterValue::Range(min, max) => {
+                    sql.push_str("? AND ?");
+                    params.push(value_to_libsql_value(min));
+                    params.push(value_to_libsql_value(max));
+                }
+                FilterValue::Subquery(subquery) => {
+                    let (subquery_sql, subquery_params) = subquery.build_statement(lists)?;
+                    sql.push_str(&format!("({subquery_sql})"));
+                    params.extend(subquery_params);
+                }
+            }
+        }
+    }
+
+    Ok((sql, params))
+}
+
+#[cfg(feature = "regexp")]
+fn is_regexp(operator: &Operator) -> bool {
+    matches!(operator, Operator::Regexp)
+}
+
+#[cfg(not(feature = "regexp"))]
+fn is_regexp(_operator: &Operator) -> bool {
+    false
+}
+
+/// The last part of a possibly table-qualified column name
+fn unqualified(column: &str) -> &str {
+    column.rsplit('.').next().unwrap_or(column)
+}
+
+/// `aggregate`, or the empty JSON value from `empty` when the group has no
+/// non-NULL `column`, where `aggregate` would give `[null]` or NULL
+fn or_empty_json(column: &str, empty: &str, aggregate: Expr) -> Expr {
+    crate::case_when(
+        crate::func("count", [crate::col(column)]).eq(0i64),
+        crate::func(empty, Vec::<Expr>::new()),
+    )
+    .otherwise(aggregate)
+}
+
+/// Count `?` placeholders outside quoted literals and identifiers
+fn count_placeholders(sql: &str) -> usize {
+    let mut count = 0;
+    let mut quote = None;
+    for c in sql.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '[' => quote = Some(']'),
+                '?' => count += 1,
+                _ => {}
+            },
+        }
+    }
+    count
+}
+
+/// Lists longer than this are bound as one JSON array and expanded with
+/// `json_each` instead of one placeholder per value
+pub const MAX_INLINE_LIST_PARAMS: usize = 500;
+
+fn push_placeholders(
+    sql: &mut String,
+    params: &mut Vec<crate::compat::LibsqlValue>,
+    values: &[Value],
+) {
+    sql.push('(');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
         }
+        sql.push('?');
+        params.push(value_to_libsql_value(value));
+    }
+    sql.push(')');
+}
+
+/// A subquery expanding one bound array parameter into `values`, and that
+/// array, or `None` when the list has no array form
+///
+/// Lists of blobs are sent as hex strings and decoded with `unhex`; other
+/// lists are sent as JSON, which fails for non-finite floats and lists that
+/// mix blobs with other values.
+fn list_array(values: &[Value]) -> Option<(&'static str, String)> {
+    let hex: Option<Vec<serde_json::Value>> = values
+        .iter()
+        .map(|value| match value {
+            Value::Blob(blob) => Some(serde_json::Value::String(
+                blob.iter().map(|byte| format!("{byte:02x}")).collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+    match hex {
+        Some(hex) => Some((
+            "(SELECT unhex(value) FROM json_each(?))",
+            serde_json::Value::Array(hex).to_string(),
+        )),
+        None => json_array(values).map(|array| ("(SELECT value FROM json_each(?))", array)),
+    }
+}
+
+/// Encode a list as a JSON array for `json_each`, or `None` when a value
+/// (a blob or non-finite float) has no JSON representation
+fn json_array(values: &[Value]) -> Option<String> {
+    let items = values
+        .iter()
+        .map(|value| match value {
+            Value::Null => Some(serde_json::Value::Null),
+            Value::Integer(i) => Some(serde_json::Value::from(*i)),
+            Value::Real(f) => serde_json::Number::from_f64(*f).map(serde_json::Value::Number),
+            Value::Text(s) => Some(serde_json::Value::String(s.clone())),
+            Value::Boolean(b) => Some(serde_json::Value::from(i64::from(*b))),
+            Value::Blob(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(serde_json::Value::Array(items).to_string())
+}
+
+/// Quote a table, column, or index name for use in SQL
+///
+/// Plain names become `"name"` and table-qualified ones `"posts"."title"`
+/// (or `"posts".*`), so reserved words such as `order` and `group` work as
+/// names. `*`, names that are already quoted, and anything else that isn't
+/// an identifier, such as `COUNT(*)` or `lower(email)`, are returned as
+/// given, so SQL fragments written by hand keep working. Those are emitted
+/// verbatim: check names that come from user input with
+/// [`Model::column`](crate::Model::column) first.
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::quote_identifier;
+///
+/// assert_eq!(quote_identifier("order"), r#""order""#);
+/// assert_eq!(quote_identifier("posts.title"), r#""posts"."title""#);
+/// assert_eq!(quote_identifier("posts.*"), r#""posts".*"#);
+/// assert_eq!(quote_identifier("COUNT(*)"), "COUNT(*)");
+/// ```
+pub fn quote_identifier(name: &str) -> String {
+    if name.starts_with(['"', '`', '[']) {
+        return name.to_string();
+    }
+    let parts: Vec<&str> = name.split('.').collect();
+    let last = parts.len() - 1;
+    let quotable = parts
+        .iter()
+        .enumerate()
+        .all(|(i, part)| is_identifier(part) || (i == last && i > 0 && *part == "*"));
+    if !quotable {
+        return name.to_string();
+    }
+    parts
+        .iter()
+        .map(|part| match *part {
+            "*" => "*".to_string(),
+            part => format!(r#""{part}""#),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The name SQLite gives the result column of a selected column, such as
+/// `name` for `users.name` or `n` for `count AS n`
+fn result_column_name(column: &str) -> String {
+    let words: Vec<&str> = column.split_whitespace().collect();
+    let name = match words.as_slice() {
+        [.., as_keyword, alias] if as_keyword.eq_ignore_ascii_case("as") => alias,
+        _ => column.rsplit('.').next().unwrap_or(column),
+    };
+    name.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_string()
+}
+
+/// Quote a name known to be a single identifier, such as a model's table or
+/// column, whatever characters it contains
+pub(crate) fn quote_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Whether `name` is a bare SQL identifier
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Convert our Value type to crate::compat::LibsqlValue
+pub(crate) fn value_to_libsql_value(value: &Value) -> crate::compat::LibsqlValue {
+    match value {
+        Value::Null => crate::compat::LibsqlValue::Null,
+        Value::Integer(i) => crate::compat::LibsqlValue::Integer(*i),
+        Value::Real(f) => crate::compat::LibsqlValue::Real(*f),
+        Value::Text(s) => crate::compat::LibsqlValue::Text(s.clone()),
+        Value::Blob(b) => crate::compat::LibsqlValue::Blob(b.clone()),
+        Value::Boolean(b) => crate::compat::LibsqlValue::Integer(if *b { 1 } else { 0 }),
     }
 }
 
 impl Clone for QueryBuilder {
     fn clone(&self) -> Self {
         Self {
+            ctes: self.ctes.clone(),
             table: self.table.clone(),
             select_columns: self.select_columns.clone(),
             joins: self.joins.clone(),
@@ -908,6 +1907,16 @@ impl Clone for QueryBuilder {
     }
 }
 
+impl Clone for CommonTableExpression {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            query: self.query.clone(),
+            recursive: self.recursive.clone(),
+        }
+    }
+}
+
 impl Clone for JoinClause {
     fn clone(&self) -> Self {
         Self {
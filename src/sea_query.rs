@@ -0,0 +1,172 @@
+//! sea-query integration, behind the `sea-query` feature
+//!
+//! For SQL the [`QueryBuilder`](crate::QueryBuilder) can't express, build the
+//! statement with [sea-query](https://docs.rs/sea-query) and run it through
+//! any [`Executor`]: [`SeaQueryExt::execute_seaquery`] for writes and
+//! [`SeaQueryExt::query_seaquery`] for anything returning rows, mapped
+//! through [`FromRow`] (models included). Values stay bound parameters.
+//!
+//! The two builders also mix:
+//!
+//! - a sea-query [`Condition`] or [`Expr`](::sea_query::Expr) converts into
+//!   a [`FilterOperator`], so it can be passed to
+//!   [`QueryBuilder::r#where`](crate::QueryBuilder::r#where)
+//! - [`name`] turns a sea-query identifier into the column or table name the
+//!   `QueryBuilder` takes, and a model's [`table_name`](crate::Model::table_name)
+//!   and [`columns`](crate::Model::columns) are `&'static str`s, which
+//!   sea-query accepts as identifiers
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::sea_query::SeaQueryExt;
+//! use libsql_orm::{Database, Model, QueryBuilder, Result};
+//! use sea_query::{Expr, ExprTrait, Order, Query};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String, email: String }
+//!
+//! # async fn example(db: &Database) -> Result<()> {
+//! let insert = Query::insert()
+//!     .into_table(User::table_name())
+//!     .columns(["name", "email"])
+//!     .values_panic(["Alice".into(), "alice@example.com".into()])
+//!     .to_owned();
+//! db.execute_seaquery(&insert).await?;
+//!
+//! let select = Query::select()
+//!     .column(sea_query::Asterisk)
+//!     .from(User::table_name())
+//!     .and_where(Expr::col("email").like("%@example.com"))
+//!     .order_by("name", Order::Asc)
+//!     .to_owned();
+//! let users: Vec<User> = db.query_seaquery(&select).await?;
+//!
+//! let active = QueryBuilder::new(User::table_name())
+//!     .r#where(Expr::col("name").is_in(["Alice", "Bob"]))
+//!     .execute_model::<User>(db)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Executor, FilterOperator, FromRow, Result, Value};
+use ::sea_query::{
+    Condition, IntoIden, QueryBuilder as _, QueryStatementWriter, SqliteQueryBuilder,
+};
+
+/// Render a statement as SQLite SQL with `?` placeholders and its values
+pub fn build(statement: &impl QueryStatementWriter) -> (String, Vec<Value>) {
+    let (sql, values) = statement.build(SqliteQueryBuilder);
+    (sql, values.into_iter().map(Value::from).collect())
+}
+
+/// The plain name of a sea-query identifier, for [`QueryBuilder`](crate::QueryBuilder)
+/// methods that take table or column names
+pub fn name(iden: impl IntoIden) -> String {
+    iden.into_iden().to_string()
+}
+
+/// Run sea-query statements on any [`Executor`]
+#[allow(async_fn_in_trait)]
+pub trait SeaQueryExt: Executor {
+    /// Run a statement, returning the number of affected rows
+    async fn execute_seaquery(&self, statement: &impl QueryStatementWriter) -> Result<u64> {
+        let (sql, params) = build(statement);
+        self.execute_statement(None, &sql, params).await
+    }
+
+    /// Run a statement that returns rows, such as a `SELECT` or an `INSERT`
+    /// with `RETURNING`, and map each row through [`FromRow`]
+    async fn query_seaquery<T: FromRow>(
+        &self,
+        statement: &impl QueryStatementWriter,
+    ) -> Result<Vec<T>> {
+        let (sql, params) = build(statement);
+        self.query_statement(None, &sql, params)
+            .await?
+            .into_iter()
+            .map(T::from_row)
+            .collect()
+    }
+}
+
+impl<E: Executor + ?Sized> SeaQueryExt for E {}
+
+/// Converts by SQLite's storage classes: unsigned integers beyond `i64`
+/// become reals, enums and characters become text, and typed values from
+/// other sea-query features (dates, JSON, decimals) become the text SQLite
+/// would store for them.
+impl From<::sea_query::Value> for Value {
+    fn from(value: ::sea_query::Value) -> Self {
+        use ::sea_query::Value as Sea;
+        let value = match value {
+            Sea::Bool(b) => b.map(Value::Boolean),
+            Sea::TinyInt(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::SmallInt(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::Int(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::BigInt(i) => i.map(Value::Integer),
+            Sea::TinyUnsigned(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::SmallUnsigned(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::Unsigned(i) => i.map(|i| Value::Integer(i.into())),
+            Sea::BigUnsigned(i) => {
+                i.map(|i| i64::try_from(i).map_or(Value::Real(i as f64), Value::Integer))
+            }
+            Sea::Float(f) => f.map(|f| Value::Real(f.into())),
+            Sea::Double(f) => f.map(Value::Real),
+            Sea::String(s) => s.map(Value::Text),
+            Sea::Char(c) => c.map(|c| Value::Text(c.to_string())),
+            Sea::Enum(e) => match e {
+                ::sea_query::OptionEnum::Some(e) => Some(Value::Text(e.value.into_owned())),
+                ::sea_query::OptionEnum::None(_) => None,
+            },
+            Sea::Bytes(b) => b.map(Value::Blob),
+            #[allow(unreachable_patterns)]
+            other => {
+                let literal = SqliteQueryBuilder.value_to_string(&other);
+                match literal
+                    .strip_prefix('\'')
+                    .and_then(|l| l.strip_suffix('\''))
+                {
+                    Some(text) => Some(Value::Text(text.replace("''", "'"))),
+                    None if literal == "NULL" => None,
+                    None => Some(Value::Text(literal)),
+                }
+            }
+        };
+        value.unwrap_or(Value::Null)
+    }
+}
+
+impl From<Value> for ::sea_query::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => ::sea_query::Value::String(None),
+            Value::Integer(i) => i.into(),
+            Value::Real(f) => f.into(),
+            Value::Text(s) => s.into(),
+            Value::Blob(b) => b.into(),
+            Value::Boolean(b) => b.into(),
+        }
+    }
+}
+
+impl From<Condition> for FilterOperator {
+    fn from(condition: Condition) -> Self {
+        let statement = ::sea_query::Query::select()
+            .expr(::sea_query::Expr::cust("1"))
+            .cond_where(condition)
+            .to_owned();
+        let (sql, params) = build(&statement);
+        match sql.split_once(" WHERE ") {
+            Some((_, condition)) => FilterOperator::Raw(condition.to_string(), params),
+            // An empty `Condition::all()` matches everything
+            None => FilterOperator::Custom("1 = 1".to_string()),
+        }
+    }
+}
+
+impl From<::sea_query::Expr> for FilterOperator {
+    fn from(expr: ::sea_query::Expr) -> Self {
+        Condition::all().add(expr).into()
+    }
+}
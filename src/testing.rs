@@ -0,0 +1,545 @@
+//! Test utilities for code built on libsql-orm
+//!
+//! [`TestDatabase`] creates a throwaway in-memory database with the tables
+//! of the given models, so integration tests don't have to repeat schema
+//! setup. [`TestDatabase::run_in_rollback`] runs a block inside a transaction
+//! that is always rolled back, letting tests share one database without
+//! seeing each other's rows.
+//!
+//! [`Factory`] builds model instances with generated defaults, replacing
+//! hand-written `user(...)` helpers; derive [`FactoryDefaults`] with
+//! `#[derive(Factory)]`.
+//!
+//! [`MockDatabase`] needs no SQLite at all: it records the statements a
+//! piece of code runs through an [`Executor`] and answers with scripted
+//! rows, for unit tests of service code.
+//!
+//! [`assert_sql_eq!`](crate::assert_sql_eq) and
+//! [`assert_sql_snapshot!`](crate::assert_sql_snapshot) pin down the SQL the
+//! builders generate, compared after [`normalize_sql`], and
+//! [`full_table_scans`] asks SQLite whether a query would read every row.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::testing::TestDatabase;
+//! use libsql_orm::{Model, Result};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct User { id: Option<i64>, name: String }
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct Post { id: Option<i64>, title: String }
+//!
+//! # async fn example() -> Result<()> {
+//! let db = TestDatabase::new::<(User, Post)>().await?;
+//!
+//! let count = db
+//!     .run_in_rollback(|db| async move {
+//!         User { id: None, name: "Alice".to_string() }.create(db).await?;
+//!         User::count(db).await
+//!     })
+//!     .await??;
+//! assert_eq!(count, 1);
+//! assert_eq!(User::count(&db).await?, 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ChangeEvent, Database, Error, Executor, Model, Result, Row, Value};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// A set of models whose tables [`TestDatabase::new`] creates
+///
+/// Implemented for tuples of up to eight models, e.g. `(User,)` or
+/// `(User, Post)`.
+pub trait ModelSet {
+    /// `CREATE TABLE` and index statements for every model, in order
+    fn schema_sql() -> Vec<String>;
+}
+
+macro_rules! impl_model_set {
+    ($($model:ident),+) => {
+        impl<$($model: Model),+> ModelSet for ($($model,)+) {
+            fn schema_sql() -> Vec<String> {
+                let mut sql = Vec::new();
+                $(
+                    sql.push($model::migration_sql());
+                    sql.extend($model::index_sql());
+                )+
+                sql
+            }
+        }
+    };
+}
+
+impl_model_set!(A);
+impl_model_set!(A, B);
+impl_model_set!(A, B, C);
+impl_model_set!(A, B, C, D);
+impl_model_set!(A, B, C, D, E);
+impl_model_set!(A, B, C, D, E, F);
+impl_model_set!(A, B, C, D, E, F, G);
+impl_model_set!(A, B, C, D, E, F, G, H);
+
+/// An in-memory database with the schema of a [`ModelSet`]
+///
+/// Dereferences to [`Database`], so it can be passed wherever a
+/// `&Database` is expected.
+#[cfg(feature = "turso")]
+pub struct TestDatabase {
+    db: Database,
+}
+
+#[cfg(feature = "turso")]
+impl TestDatabase {
+    /// Open `:memory:` and create the tables and indexes of `M`
+    pub async fn new<M: ModelSet>() -> Result<Self> {
+        let db = Database::new_local(":memory:").await?;
+        for sql in M::schema_sql() {
+            db.execute(&sql, vec![]).await?;
+        }
+        Ok(Self { db })
+    }
+
+    /// Run `test` in a transaction that is rolled back afterwards
+    ///
+    /// The rollback happens whether `test` succeeds or returns an error, so
+    /// the database is left as it was. Change events are held back while
+    /// `test` runs and dropped with the rollback, so subscribers never see
+    /// the rolled-back changes.
+    pub async fn run_in_rollback<'a, F, Fut, T>(&'a self, test: F) -> Result<T>
+    where
+        F: FnOnce(&'a Database) -> Fut,
+        Fut: Future<Output = T> + 'a,
+    {
+        self.db.execute("BEGIN", vec![]).await?;
+        self.db.changes.hold();
+        let output = test(&self.db).await;
+        self.db.changes.discard_held();
+        self.db
+            .execute("ROLLBACK", vec![])
+            .await
+            .map_err(|e| Error::DatabaseError(format!("Failed to roll back test: {e}")))?;
+        Ok(output)
+    }
+
+    /// The underlying database
+    pub fn into_inner(self) -> Database {
+        self.db
+    }
+}
+
+#[cfg(feature = "turso")]
+impl std::ops::Deref for TestDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        &self.db
+    }
+}
+
+#[cfg(feature = "turso")]
+impl Executor for TestDatabase {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        self.db.execute_statement(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        self.db.query_statement(table, sql, params).await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        self.db.emit_changes(events);
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.db.in_transaction()
+    }
+}
+
+/// Default field values for [`Factory`], usually derived with `#[derive(Factory)]`
+pub trait FactoryDefaults: Sized {
+    /// The `n`th instance; `n` is unique within the process, starting at 1
+    fn factory_default(n: u64) -> Self;
+}
+
+static FACTORY_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+type Override<M> = Box<dyn Fn(&mut M, u64)>;
+
+/// Builds model instances for tests
+///
+/// Every instance starts from [`FactoryDefaults::factory_default`] with a
+/// fresh sequence number, then has the overrides applied in order.
+/// `#[derive(Factory)]` on `User` adds a `UserFactory` alias for
+/// `Factory<User>`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::{Database, Factory, Model, Result};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Model, Factory, Clone, Serialize, Deserialize)]
+/// struct User {
+///     pub id: Option<i64>,
+///     pub name: String,
+///     #[factory(sequence = "user{n}@example.com")]
+///     pub email: String,
+///     pub age: Option<i64>,
+/// }
+///
+/// # async fn example(db: &Database) -> Result<()> {
+/// let alice = UserFactory::new()
+///     .with(|u| u.name = "Alice".to_string())
+///     .create(db)
+///     .await?;
+/// let adults = UserFactory::new()
+///     .with(|u| u.age = Some(30))
+///     .create_many(50, db)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Factory<M> {
+    overrides: Vec<Override<M>>,
+}
+
+impl<M: FactoryDefaults> Default for Factory<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: FactoryDefaults> Factory<M> {
+    /// A factory producing the defaults unchanged
+    pub fn new() -> Self {
+        Self {
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Change every built instance
+    pub fn with(mut self, apply: impl Fn(&mut M) + 'static) -> Self {
+        self.overrides.push(Box::new(move |model, _| apply(model)));
+        self
+    }
+
+    /// Change every built instance using its sequence number, e.g. for unique fields
+    pub fn with_sequence(mut self, apply: impl Fn(&mut M, u64) + 'static) -> Self {
+        self.overrides.push(Box::new(apply));
+        self
+    }
+
+    /// Build one instance without saving it
+    pub fn build(&self) -> M {
+        let n = FACTORY_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let mut model = M::factory_default(n);
+        for apply in &self.overrides {
+            apply(&mut model, n);
+        }
+        model
+    }
+
+    /// Build `count` instances without saving them
+    pub fn build_many(&self, count: usize) -> Vec<M> {
+        (0..count).map(|_| self.build()).collect()
+    }
+}
+
+impl<M: FactoryDefaults + Model> Factory<M> {
+    /// Build one instance and insert it with [`Model::create`]
+    pub async fn create(&self, db: &impl Executor) -> Result<M> {
+        self.build().create(db).await
+    }
+
+    /// Build `count` instances and insert them with [`Model::bulk_create`]
+    pub async fn create_many(&self, count: usize, db: &impl Executor) -> Result<Vec<M>> {
+        M::bulk_create(&self.build_many(count), db).await
+    }
+}
+
+/// A statement run against a [`MockDatabase`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedStatement {
+    /// Table the model or builder ran it for, if any
+    pub table: Option<String>,
+    /// The SQL text
+    pub sql: String,
+    /// Bound parameters, in order
+    pub params: Vec<Value>,
+}
+
+/// An [`Executor`] that records statements and returns scripted results
+///
+/// Queries pop the next result pushed with [`push_rows`](Self::push_rows)
+/// or [`push_query_error`](Self::push_query_error), and return no rows once
+/// those run out. Other statements, including `BEGIN` and `COMMIT`, pop
+/// results pushed with [`push_affected`](Self::push_affected) or
+/// [`push_execute_error`](Self::push_execute_error), and report one
+/// affected row once those run out. Change events go to
+/// [`events`](Self::events).
+///
+/// # Examples
+///
+/// ```
+/// use libsql_orm::testing::MockDatabase;
+/// use libsql_orm::{Model, Row, Value};
+/// # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+/// # struct User { id: Option<i64>, name: String }
+///
+/// # async fn example() -> libsql_orm::Result<()> {
+/// let db = MockDatabase::new();
+/// db.push_rows(vec![Row::from([
+///     ("id".to_string(), Value::Integer(7)),
+///     ("name".to_string(), Value::Text("Alice".to_string())),
+/// ])]);
+///
+/// let user = User::find_by_id(7, &db).await?.unwrap();
+/// assert_eq!(user.name, "Alice");
+/// assert_eq!(db.statements()[0].params, vec![Value::Integer(7)]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MockDatabase {
+    state: Mutex<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    statements: Vec<RecordedStatement>,
+    rows: VecDeque<Result<Vec<Row>>>,
+    affected: VecDeque<Result<u64>>,
+    events: Vec<ChangeEvent>,
+}
+
+impl MockDatabase {
+    /// Create a mock with nothing scripted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `rows` from the next query
+    pub fn push_rows(&self, rows: Vec<Row>) -> &Self {
+        self.state().rows.push_back(Ok(rows));
+        self
+    }
+
+    /// Fail the next query with `error`
+    pub fn push_query_error(&self, error: Error) -> &Self {
+        self.state().rows.push_back(Err(error));
+        self
+    }
+
+    /// Report `count` affected rows from the next non-query statement
+    pub fn push_affected(&self, count: u64) -> &Self {
+        self.state().affected.push_back(Ok(count));
+        self
+    }
+
+    /// Fail the next non-query statement with `error`
+    pub fn push_execute_error(&self, error: Error) -> &Self {
+        self.state().affected.push_back(Err(error));
+        self
+    }
+
+    /// Every statement run so far, oldest first
+    pub fn statements(&self) -> Vec<RecordedStatement> {
+        self.state().statements.clone()
+    }
+
+    /// SQL of every statement run so far, oldest first
+    pub fn sql(&self) -> Vec<String> {
+        self.state()
+            .statements
+            .iter()
+            .map(|statement| statement.sql.clone())
+            .collect()
+    }
+
+    /// Change events emitted so far, oldest first
+    pub fn events(&self) -> Vec<ChangeEvent> {
+        self.state().events.clone()
+    }
+
+    /// Forget recorded statements and events, keeping scripted results
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.statements.clear();
+        state.events.clear();
+    }
+
+    fn record(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> MutexGuard<'_, MockState> {
+        let mut state = self.state();
+        state.statements.push(RecordedStatement {
+            table: table.map(str::to_string),
+            sql: sql.to_string(),
+            params,
+        });
+        state
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Executor for MockDatabase {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        self.record(table, sql, params)
+            .affected
+            .pop_front()
+            .unwrap_or(Ok(1))
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        self.record(table, sql, params)
+            .rows
+            .pop_front()
+            .unwrap_or(Ok(Vec::new()))
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        let events = events();
+        self.state().events.extend(events);
+    }
+}
+
+/// Collapse SQL formatting differences so statements compare by shape
+///
+/// Runs of whitespace become one space, spaces just inside parentheses and
+/// before commas are dropped, and a trailing `;` is removed. Quoted strings
+/// and identifiers are left untouched.
+pub fn normalize_sql(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut space = false;
+    for c in sql.chars() {
+        if let Some(open) = quote {
+            normalized.push(c);
+            if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+        if space && !normalized.is_empty() && !normalized.ends_with('(') && c != ')' && c != ',' {
+            normalized.push(' ');
+        }
+        space = false;
+        if matches!(c, '\'' | '"' | '`') {
+            quote = Some(c);
+        }
+        normalized.push(c);
+    }
+    normalized.trim_end_matches(';').trim_end().to_string()
+}
+
+/// Compare SQL against a snapshot file, writing it if missing
+///
+/// Used by [`assert_sql_snapshot!`](crate::assert_sql_snapshot). Both
+/// sides are compared after [`normalize_sql`]. When the file does not exist,
+/// or the `LIBSQL_ORM_UPDATE_SNAPSHOTS` environment variable is set, the
+/// normalized SQL is written instead and the check passes.
+pub fn check_sql_snapshot(path: &Path, sql: &str) -> std::result::Result<(), String> {
+    let actual = normalize_sql(sql);
+    if !path.exists() || std::env::var_os("LIBSQL_ORM_UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        return std::fs::write(path, format!("{actual}\n"))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()));
+    }
+
+    let expected = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let expected = normalize_sql(&expected);
+    if actual == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "SQL snapshot {} does not match\nexpected: {expected}\n  actual: {actual}\n\
+         Re-run with LIBSQL_ORM_UPDATE_SNAPSHOTS=1 to accept the new SQL",
+        path.display()
+    ))
+}
+
+/// The `EXPLAIN QUERY PLAN` detail lines of a query, e.g. `SCAN users`
+pub async fn query_plan(db: &impl Executor, sql: &str, params: Vec<Value>) -> Result<Vec<String>> {
+    let rows = db
+        .query_statement(None, &format!("EXPLAIN QUERY PLAN {sql}"), params)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|mut row| match row.remove("detail") {
+            Some(Value::Text(detail)) => Some(detail),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Plan steps that read a whole table instead of searching an index
+///
+/// An empty result means every table in the query is reached through an
+/// index or the primary key, on the current schema.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libsql_orm::testing::{full_table_scans, TestDatabase};
+/// use libsql_orm::{Filter, QueryBuilder, Result};
+///
+/// # async fn example(db: &TestDatabase) -> Result<()> {
+/// let (sql, params) = QueryBuilder::new("users")
+///     .r#where(Filter::eq("email", "alice@example.com"))
+///     .to_sql()?;
+/// assert_eq!(full_table_scans(db, &sql, params).await?, Vec::<String>::new());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn full_table_scans(
+    db: &impl Executor,
+    sql: &str,
+    params: Vec<Value>,
+) -> Result<Vec<String>> {
+    Ok(query_plan(db, sql, params)
+        .await?
+        .into_iter()
+        .filter(|detail| detail.starts_with("SCAN ") && !detail.contains(" USING "))
+        .collect())
+}
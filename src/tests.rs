@@ -101,4 +101,1312 @@ mod value_tests {
         let value: Value = none_bool.into();
         assert_eq!(value, Value::Null);
     }
+
+    #[test]
+    fn test_chrono_values_match_serde_text() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        let ts = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+        let expected = serde_json::to_value(ts).unwrap();
+        assert_eq!(Value::from(ts), Value::from(expected));
+        assert_eq!(
+            Value::from(ts + chrono::Duration::milliseconds(250)),
+            Value::Text("2024-03-01T12:30:00.250Z".to_string())
+        );
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(Value::from(day), Value::Text("2024-03-01".to_string()));
+        let naive = day.and_hms_opt(8, 0, 0).unwrap();
+        assert_eq!(
+            Value::from(naive),
+            Value::from(serde_json::to_value(naive).unwrap())
+        );
+    }
+}
+
+#[cfg(test)]
+mod query_builder_tests {
+    use crate::QueryBuilder;
+
+    #[test]
+    fn test_select_accepts_arrays() {
+        let (sql, params) = QueryBuilder::new("users")
+            .select(["id", "email"])
+            .build()
+            .unwrap();
+        assert_eq!(sql, r#"SELECT "id", "email" FROM "users""#);
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_select_raw_projection() {
+        let (sql, _) = QueryBuilder::new("users")
+            .select_raw("count(*) as n, is_active")
+            .group_by(vec!["is_active"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT count(*) as n, is_active FROM "users" GROUP BY "is_active""#
+        );
+    }
+
+    #[test]
+    fn test_left_join_with_qualified_sort() {
+        let (sql, params) = QueryBuilder::new("users")
+            .select(["users.name", "posts.title"])
+            .left_join("posts", "posts.user_id = users.id")
+            .order_by(crate::Sort::desc("posts.id"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "users"."name", "posts"."title" FROM "users" LEFT JOIN "posts" ON posts.user_id = users.id ORDER BY "posts"."id" DESC"#
+        );
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_multi_column_sort_with_nulls_and_collation() {
+        use crate::Sort;
+
+        let (sql, _) = QueryBuilder::new("users")
+            .order_by_multiple(vec![
+                Sort::desc("age").nulls_last(),
+                Sort::asc("name").collate("NOCASE"),
+                Sort::asc("id").nulls_first(),
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" ORDER BY ("age" IS NULL) ASC, "age" DESC, "name" COLLATE NOCASE ASC, ("id" IS NULL) DESC, "id" ASC"#
+        );
+    }
+
+    #[test]
+    fn test_in_list_edge_cases() {
+        use crate::{Filter, Value};
+
+        let (sql, params) = QueryBuilder::new("users")
+            .r#where(Filter::in_list("id", Vec::<i64>::new()))
+            .r#where(Filter::not_in("role", Vec::<String>::new()))
+            .to_sql()
+            .unwrap();
+        assert_eq!(sql, r#"SELECT * FROM "users" WHERE 1 = 0 AND 1 = 1"#);
+        assert!(params.is_empty());
+
+        let blobs = vec![Value::Blob(vec![1]); crate::query::MAX_INLINE_LIST_PARAMS + 1];
+        let (sql, params) = QueryBuilder::new("files")
+            .r#where(Filter::in_list("hash", blobs))
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "files" WHERE "hash" IN (SELECT unhex(value) FROM json_each(?))"#
+        );
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_json_filters_are_plain_filters() {
+        use crate::filters::FilterValue;
+        use crate::{Filter, Operator, Value};
+
+        let filters = [
+            Filter::json_eq("prefs", "$.theme", "Dark").nocase(),
+            Filter::new(
+                "prefs",
+                Operator::Gt,
+                FilterValue::JsonPath("$.volume".to_string(), Value::Integer(5)),
+            ),
+            Filter::json_contains("tags", "Rust").nocase(),
+        ];
+        let mut query = QueryBuilder::new("users");
+        for filter in filters {
+            query = query.r#where(filter);
+        }
+        let (sql, params) = query.to_sql().unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM \"users\" WHERE json_extract(\"prefs\", ?) COLLATE NOCASE = ? \
+             AND json_extract(\"prefs\", ?) > ? \
+             AND EXISTS (SELECT 1 FROM json_each(\"tags\") WHERE \"value\" COLLATE NOCASE = ?)"
+        );
+        assert_eq!(
+            params,
+            vec![
+                Value::Text("$.theme".to_string()),
+                Value::Text("Dark".to_string()),
+                Value::Text("$.volume".to_string()),
+                Value::Integer(5),
+                Value::Text("Rust".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lists_share_the_statement_parameter_budget() {
+        use crate::{Filter, FilterOperator, UpdateBuilder};
+
+        let ids: Vec<i64> = (0..400).collect();
+        let (sql, params) = QueryBuilder::new("users")
+            .r#where(Filter::in_list("id", ids.clone()))
+            .r#where(Filter::eq("name", "a"))
+            .to_sql()
+            .unwrap();
+        assert!(sql.starts_with(r#"SELECT * FROM "users" WHERE "id" IN (?, ?"#));
+        assert_eq!(params.len(), 401);
+
+        let lists = FilterOperator::And(vec![
+            Filter::in_list("id", ids.clone()).into(),
+            Filter::not_in("id", ids.clone()).into(),
+            Filter::in_list("id", ids).into(),
+        ]);
+        let (sql, params) = QueryBuilder::new("users")
+            .r#where(lists.clone())
+            .r#where(Filter::eq("name", "a"))
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" WHERE ("id" IN (SELECT value FROM json_each(?)) AND "id" NOT IN (SELECT value FROM json_each(?)) AND "id" IN (SELECT value FROM json_each(?))) AND "name" = ?"#
+        );
+        assert_eq!(params.len(), 4);
+
+        let (_, params) = UpdateBuilder::table("users")
+            .set("is_active", false)
+            .r#where(lists)
+            .to_sql()
+            .unwrap();
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn test_negated_trees_and_empty_groups() {
+        use crate::{Filter, FilterOperator};
+
+        let tree = crate::filter_op!(
+            not,
+            FilterOperator::And(vec![
+                Filter::eq("a", 1i64).into(),
+                FilterOperator::Or(vec![]),
+            ])
+        );
+        let (sql, params) = QueryBuilder::new("t")
+            .r#where(tree)
+            .r#where(FilterOperator::And(vec![]))
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "t" WHERE NOT (("a" = ? AND 1 = 0)) AND 1 = 1"#
+        );
+        assert_eq!(params.len(), 1);
+
+        let single = FilterOperator::from(Filter::eq("a", 1i64));
+        assert!(matches!(!!single, FilterOperator::Single(_)));
+    }
+
+    #[test]
+    fn test_raw_conditions_bind_their_params() {
+        use crate::{compat::LibsqlValue, Filter};
+
+        let (sql, params) = QueryBuilder::new("users")
+            .where_condition("age > ?", vec![LibsqlValue::Integer(18)])
+            .r#where(Filter::raw("instr(email, ?) > 0", ["@example.com"]))
+            .group_by(vec!["role"])
+            .having_condition("count(*) >= ?", vec![LibsqlValue::Integer(2)])
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" WHERE (age > ?) AND (instr(email, ?) > 0) GROUP BY "role" HAVING (count(*) >= ?)"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                LibsqlValue::Integer(18),
+                LibsqlValue::Text("@example.com".to_string()),
+                LibsqlValue::Integer(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_placeholder_count_ignores_quoted_text() {
+        use crate::Filter;
+
+        let ok = QueryBuilder::new("t")
+            .r#where(Filter::raw(
+                r#"a = ? AND "col?" = '?' AND [x?] = ?"#,
+                [1i64, 2],
+            ))
+            .build();
+        assert!(ok.is_ok());
+
+        let err = QueryBuilder::new("t")
+            .r#where(Filter::raw("a = ?", Vec::<i64>::new()))
+            .build();
+        assert!(matches!(err, Err(crate::Error::Query(_))));
+    }
+
+    #[cfg(feature = "regexp")]
+    #[test]
+    fn test_nocase_regexp_sets_the_inline_flag() {
+        let (sql, params) = QueryBuilder::new("users")
+            .r#where(crate::Filter::regexp("code", r"^\D+\d$").nocase())
+            .to_sql()
+            .unwrap();
+        assert_eq!(sql, r#"SELECT * FROM "users" WHERE "code" REGEXP ?"#);
+        assert_eq!(params, vec![crate::Value::Text(r"(?i)^\D+\d$".to_string())]);
+    }
+
+    #[test]
+    fn test_glob_and_regexp_operators() {
+        use crate::Filter;
+
+        let (sql, _) = QueryBuilder::new("files")
+            .r#where(Filter::not_glob("path", "*.tmp"))
+            .r#where(Filter::glob("name", "READ*").nocase())
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "files" WHERE "path" NOT GLOB ? AND lower("name") GLOB lower(?)"#
+        );
+
+        #[cfg(feature = "regexp")]
+        {
+            let (sql, _) = QueryBuilder::new("users")
+                .r#where(Filter::regexp("email", r"^[a-z]+@example\.com$"))
+                .to_sql()
+                .unwrap();
+            assert_eq!(sql, r#"SELECT * FROM "users" WHERE "email" REGEXP ?"#);
+        }
+    }
+
+    #[test]
+    fn test_tuple_comparison_expands_lexicographically() {
+        use crate::{Filter, Value};
+
+        let (sql, params) = QueryBuilder::new("t")
+            .r#where(Filter::tuple_le(("a", "b", "c"), (1i64, "x", 2.5)))
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "t" WHERE ("a" < ? OR ("a" = ? AND "b" < ?) OR ("a" = ? AND "b" = ? AND "c" <= ?))"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                Value::Integer(1),
+                Value::Integer(1),
+                Value::Text("x".to_string()),
+                Value::Integer(1),
+                Value::Text("x".to_string()),
+                Value::Real(2.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subquery_params_follow_sql_order() {
+        use crate::{compat::LibsqlValue, Filter, FilterOperator};
+
+        let banned = QueryBuilder::new("banned_users")
+            .select(["user_id"])
+            .r#where(FilterOperator::Single(Filter::eq("reason", "spam")));
+        let recent = QueryBuilder::new("comments")
+            .r#where(FilterOperator::Custom(
+                "comments.post_id = posts.id".to_string(),
+            ))
+            .r#where(FilterOperator::Single(Filter::gt("comments.id", 10i64)));
+        let (sql, params) = QueryBuilder::new("posts")
+            .r#where(FilterOperator::Single(Filter::eq("published", true)))
+            .r#where(FilterOperator::Single(Filter::not_in_subquery(
+                "user_id", banned,
+            )))
+            .r#where(FilterOperator::exists(recent))
+            .r#where(FilterOperator::Single(Filter::lt("id", 99i64)))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "posts" WHERE "published" = ? AND "user_id" NOT IN (SELECT "user_id" FROM "banned_users" WHERE "reason" = ?) AND EXISTS (SELECT * FROM "comments" WHERE comments.post_id = posts.id AND "comments"."id" > ?) AND "id" < ?"#
+        );
+        let params: Vec<String> = params
+            .into_iter()
+            .map(|p| match p {
+                LibsqlValue::Integer(i) => i.to_string(),
+                LibsqlValue::Text(s) => s,
+                other => format!("{other:?}"),
+            })
+            .collect();
+        assert_eq!(params, vec!["1", "spam", "10", "99"]);
+    }
+
+    #[test]
+    fn test_cte_params_precede_main_query_and_reach_count() {
+        use crate::{Filter, FilterOperator};
+
+        let adults =
+            QueryBuilder::new("users").r#where(FilterOperator::Single(Filter::ge("age", 18i64)));
+        let query = QueryBuilder::new("adults")
+            .with("adults", adults)
+            .r#where(FilterOperator::Single(Filter::eq("is_active", true)));
+
+        let (sql, params) = query.build().unwrap();
+        assert_eq!(
+            sql,
+            r#"WITH "adults" AS (SELECT * FROM "users" WHERE "age" >= ?) SELECT * FROM "adults" WHERE "is_active" = ?"#
+        );
+        assert_eq!(params.len(), 2);
+
+        let (count_sql, count_params) = query.build_count().unwrap();
+        assert_eq!(
+            count_sql,
+            r#"WITH "adults" AS (SELECT * FROM "users" WHERE "age" >= ?) SELECT COUNT(*) FROM "adults" WHERE "is_active" = ?"#
+        );
+        assert_eq!(count_params.len(), 2);
+    }
+
+    #[test]
+    fn test_select_window_appends_to_selected_columns() {
+        let (sql, _) = QueryBuilder::new("posts")
+            .select(["id", "user_id"])
+            .select_window(
+                "row_number() OVER (PARTITION BY user_id ORDER BY created_at DESC) as rn",
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "id", "user_id", row_number() OVER (PARTITION BY user_id ORDER BY created_at DESC) as rn FROM "posts""#
+        );
+    }
+
+    #[test]
+    fn test_window_without_partition() {
+        let window = crate::Window::new("sum(amount)").order_by(crate::Sort::asc("id"));
+        assert_eq!(
+            window.to_string(),
+            r#"sum(amount) OVER (ORDER BY "id" ASC)"#
+        );
+        assert_eq!(
+            crate::Window::new("count(*)").to_string(),
+            "count(*) OVER ()"
+        );
+    }
+
+    #[test]
+    fn test_to_sql_reports_bound_values() {
+        use crate::{Filter, FilterOperator, Value};
+
+        let query = QueryBuilder::new("users")
+            .r#where(FilterOperator::Single(Filter::eq("is_active", true)))
+            .r#where(FilterOperator::Single(Filter::between("age", 18i64, 65i64)));
+        let (sql, params) = query.to_sql().unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" WHERE "is_active" = ? AND "age" BETWEEN ? AND ?"#
+        );
+        assert_eq!(
+            params,
+            vec![Value::Integer(1), Value::Integer(18), Value::Integer(65)]
+        );
+
+        let (count_sql, count_params) = query.to_count_sql().unwrap();
+        assert!(count_sql.starts_with(r#"SELECT COUNT(*) FROM "users" WHERE"#));
+        assert_eq!(count_params.len(), 3);
+    }
+
+    #[test]
+    fn test_select_and_order_expr_params_follow_sql_order() {
+        use crate::{case_when, col, func, SortOrder, Value};
+
+        let high = case_when(col("score").gt(90i64), 1i64).otherwise(0i64);
+        let (sql, params) = QueryBuilder::new("users")
+            .select(["team"])
+            .select_expr(func("sum", [high]), "high_scorers")
+            .r#where(col("is_active").eq(true))
+            .group_by(vec!["team"])
+            .order_by_expr(
+                case_when(col("team").eq("core"), 0i64).otherwise(1i64),
+                SortOrder::Asc,
+            )
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "team", sum(CASE WHEN "score" > ? THEN ? ELSE ? END) AS "high_scorers" FROM "users" WHERE "is_active" = ? GROUP BY "team" ORDER BY CASE WHEN "team" = ? THEN ? ELSE ? END ASC"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                Value::Integer(90),
+                Value::Integer(1),
+                Value::Integer(0),
+                Value::Integer(1),
+                Value::Text("core".to_string()),
+                Value::Integer(0),
+                Value::Integer(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quote_identifier() {
+        use crate::quote_identifier;
+
+        assert_eq!(quote_identifier("order"), r#""order""#);
+        assert_eq!(quote_identifier("posts.title"), r#""posts"."title""#);
+        assert_eq!(quote_identifier("posts.*"), r#""posts".*"#);
+        assert_eq!(quote_identifier(r#""already""#), r#""already""#);
+        assert_eq!(quote_identifier("count(*)"), "count(*)");
+        assert_eq!(quote_identifier("*"), "*");
+    }
+}
+
+#[cfg(test)]
+mod mutation_tests {
+    use crate::{DeleteBuilder, Filter, FilterOperator, InsertBuilder, UpdateBuilder};
+
+    #[test]
+    fn test_insert_builder_keeps_column_order() {
+        let (sql, params) = InsertBuilder::table("users")
+            .value("name", "Alice")
+            .values([("email", "alice@example.com"), ("role", "admin")])
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "users" ("name", "email", "role") VALUES (?, ?, ?)"#
+        );
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_builder_default_values() {
+        let (sql, params) = InsertBuilder::table("counters").build().unwrap();
+        assert_eq!(sql, r#"INSERT INTO "counters" DEFAULT VALUES"#);
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_update_builder_binds_set_before_where() {
+        let (sql, params) = UpdateBuilder::table("users")
+            .set("name", "Bob")
+            .set("is_active", false)
+            .r#where(FilterOperator::Single(Filter::in_values(
+                "id",
+                vec![1i64, 2],
+            )))
+            .build()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"UPDATE "users" SET "name" = ?, "is_active" = ? WHERE "id" IN (?, ?)"#
+        );
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn test_update_builder_requires_assignments() {
+        assert!(UpdateBuilder::table("users").build().is_err());
+    }
+
+    #[test]
+    fn test_mutation_to_sql() {
+        let (sql, params) = UpdateBuilder::table("users")
+            .set("name", "Bob")
+            .r#where(FilterOperator::Single(Filter::eq("id", 7i64)))
+            .to_sql()
+            .unwrap();
+        assert_eq!(sql, r#"UPDATE "users" SET "name" = ? WHERE "id" = ?"#);
+        assert_eq!(
+            params,
+            vec![
+                crate::Value::Text("Bob".to_string()),
+                crate::Value::Integer(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delete_builder_without_where() {
+        let (sql, params) = DeleteBuilder::table("sessions").build().unwrap();
+        assert_eq!(sql, r#"DELETE FROM "sessions""#);
+        assert!(params.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod de_tests {
+    use crate::{de, Row, Value};
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Account {
+        id: Option<i64>,
+        #[serde(rename = "display_name")]
+        name: String,
+        is_active: bool,
+        score: f64,
+        nickname: Option<String>,
+        role: Role,
+        avatar: Vec<u8>,
+        settings: HashMap<String, i64>,
+        created_at: chrono::DateTime<chrono::Utc>,
+        #[serde(default)]
+        visits: u32,
+    }
+
+    fn row(columns: Vec<(&str, Value)>) -> Row {
+        columns
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_row_converts_by_field_type() {
+        let account: Account = de::from_row(row(vec![
+            ("id", Value::Integer(7)),
+            ("display_name", Value::Text("Alice".to_string())),
+            ("is_active", Value::Integer(1)),
+            ("score", Value::Integer(3)),
+            ("nickname", Value::Null),
+            ("role", Value::Text("admin".to_string())),
+            ("avatar", Value::Blob(vec![1, 2, 3])),
+            ("settings", Value::Text(r#"{"theme":2}"#.to_string())),
+            (
+                "created_at",
+                Value::Text("2024-05-01T12:00:00Z".to_string()),
+            ),
+            ("unknown_column", Value::Real(1.5)),
+        ]))
+        .unwrap();
+
+        assert_eq!(account.id, Some(7));
+        assert_eq!(account.name, "Alice");
+        assert!(account.is_active);
+        assert_eq!(account.score, 3.0);
+        assert_eq!(account.nickname, None);
+        assert_eq!(account.role, Role::Admin);
+        assert_eq!(account.avatar, vec![1, 2, 3]);
+        assert_eq!(account.settings["theme"], 2);
+        assert_eq!(account.created_at.to_rfc3339(), "2024-05-01T12:00:00+00:00");
+        assert_eq!(account.visits, 0);
+    }
+
+    #[test]
+    fn test_from_row_reports_missing_and_mistyped_fields() {
+        let err = de::from_row::<Account>(row(vec![("id", Value::Integer(1))])).unwrap_err();
+        assert!(
+            matches!(err, crate::Error::Serialization(ref msg) if msg.contains("missing field"))
+        );
+
+        let err = de::from_value::<i64>(Value::Text("seven".to_string())).unwrap_err();
+        assert!(matches!(err, crate::Error::Serialization(_)));
+        assert_eq!(
+            de::from_value::<Option<bool>>(Value::Integer(0)).unwrap(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_delimited_reads_group_concat_results() {
+        use de::Delimited;
+
+        let tags: Delimited<String, ';'> = de::from_value(Value::Text("a;b".to_string())).unwrap();
+        assert_eq!(*tags, ["a", "b"]);
+        let ids: Delimited<i64> = de::from_value(Value::Integer(7)).unwrap();
+        assert_eq!(*ids, [7]);
+        let none: Delimited<i64> = de::from_value(Value::Null).unwrap();
+        assert!(none.is_empty());
+        assert!(de::from_value::<Delimited<i64>>(Value::Text("1,x".to_string())).is_err());
+        assert_eq!(
+            serde_json::to_value(Delimited::<i64, '|'>(vec![1, 2])).unwrap(),
+            "1|2"
+        );
+    }
+}
+
+#[cfg(test)]
+mod chunking_tests {
+    use crate::chunking::{insert_statements, ChunkLimits};
+    use crate::Value;
+
+    #[test]
+    fn test_split_respects_both_limits() {
+        let limits = ChunkLimits {
+            max_parameters: 4,
+            max_payload_bytes: 10,
+        };
+        let items = [1usize, 1, 1, 1, 1, 9, 20, 1];
+        let sizes: Vec<usize> = limits
+            .split(&items, |&bytes| (1, bytes))
+            .iter()
+            .map(|chunk| chunk.len())
+            .collect();
+        // Four parameters, then 1 + 9 bytes, then the oversized item alone
+        assert_eq!(sizes, vec![4, 2, 1, 1]);
+        assert!(limits.split(&[] as &[usize], |_| (1, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_insert_statements_group_rows_by_columns() {
+        let row = |columns: &[&str]| -> Vec<(String, Value)> {
+            columns
+                .iter()
+                .map(|c| (c.to_string(), Value::Integer(1)))
+                .collect()
+        };
+        let rows = vec![row(&["a", "b"]), row(&["a", "b"]), row(&["a"]), row(&[])];
+        let limits = ChunkLimits {
+            max_parameters: 3,
+            ..ChunkLimits::default()
+        };
+        let statements: Vec<(String, usize)> = insert_statements("t", &rows, limits)
+            .into_iter()
+            .map(|(sql, params)| (sql, params.len()))
+            .collect();
+        assert_eq!(
+            statements,
+            vec![
+                (r#"INSERT INTO "t" ("a", "b") VALUES (?, ?)"#.to_string(), 2),
+                (r#"INSERT INTO "t" ("a", "b") VALUES (?, ?)"#.to_string(), 2),
+                (r#"INSERT INTO "t" ("a") VALUES (?)"#.to_string(), 1),
+                (r#"INSERT INTO "t" DEFAULT VALUES"#.to_string(), 0),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod expr_tests {
+    use crate::{col, length, lower, val, Value};
+
+    #[test]
+    fn test_expr_binds_values_in_order() {
+        let expr = col("age")
+            .gt(30i64)
+            .and(lower(col("name")).eq("alice").or(col("score").is_null()));
+        let (sql, params) = expr.to_sql();
+        assert_eq!(
+            sql,
+            r#"("age" > ?) AND ((lower("name") = ?) OR ("score" IS NULL))"#
+        );
+        assert_eq!(
+            params,
+            vec![Value::Integer(30), Value::Text("alice".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_expr_arithmetic_keeps_grouping() {
+        let (sql, params) = ((col("a") + 1i64) * col("b") - val(2i64)).to_sql();
+        assert_eq!(sql, r#"(("a" + ?) * "b") - ?"#);
+        assert_eq!(params, vec![Value::Integer(1), Value::Integer(2)]);
+    }
+
+    #[test]
+    fn test_expr_not_and_in_list() {
+        let (sql, params) = (!length(col("name")).in_list([3i64, 4])).to_sql();
+        assert_eq!(sql, r#"NOT (length("name") IN (?, ?))"#);
+        assert_eq!(params.len(), 2);
+    }
+
+    #[test]
+    fn test_case_without_else() {
+        use crate::case_when;
+
+        let (sql, params) = case_when(col("age").lt(18i64), "minor")
+            .when(col("age").ge(65i64), "senior")
+            .end()
+            .to_sql();
+        assert_eq!(
+            sql,
+            r#"CASE WHEN "age" < ? THEN ? WHEN "age" >= ? THEN ? END"#
+        );
+        assert_eq!(params.len(), 4);
+    }
+
+    #[test]
+    fn test_expr_hostile_string_is_bound() {
+        let (sql, params) = col("name").eq("x' OR '1'='1").to_sql();
+        assert_eq!(sql, r#""name" = ?"#);
+        assert_eq!(params, vec![Value::Text("x' OR '1'='1".to_string())]);
+    }
+}
+
+#[cfg(test)]
+mod analyzer_tests {
+    use crate::QueryAnalyzer;
+
+    #[test]
+    fn test_analyzer_flags_repeated_statement_once() {
+        let mut analyzer = QueryAnalyzer::new().threshold(3);
+        let sql = "SELECT * FROM posts WHERE user_id = ?";
+
+        assert!(analyzer.record(sql).is_none());
+        assert!(analyzer.record(sql).is_none());
+        let flagged = analyzer.record(sql).expect("third repetition is flagged");
+        assert_eq!(flagged.table.as_deref(), Some("posts"));
+        assert_eq!(flagged.count, 3);
+
+        assert!(analyzer.record(sql).is_none());
+        assert_eq!(analyzer.repeated_queries()[0].count, 4);
+    }
+
+    #[test]
+    fn test_analyzer_window_forgets_old_statements() {
+        let mut analyzer = QueryAnalyzer::new().threshold(3).window(3);
+        let sql = "SELECT * FROM posts WHERE user_id = ?";
+
+        analyzer.record(sql);
+        analyzer.record("SELECT * FROM users");
+        analyzer.record("SELECT * FROM tags");
+        analyzer.record(sql);
+        assert!(analyzer
+            .record(r#"UPDATE "users" SET "name" = ? WHERE "id" = ?"#)
+            .is_none());
+        assert!(analyzer.repeated_queries().is_empty());
+    }
+
+    #[test]
+    fn test_analyzer_ignores_transaction_control() {
+        let mut analyzer = QueryAnalyzer::new().threshold(2);
+        assert!(analyzer.record("BEGIN").is_none());
+        assert!(analyzer.record("BEGIN").is_none());
+        assert!(analyzer.repeated_queries().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod search_tests {
+    use crate::{SearchFilter, Value};
+
+    #[test]
+    fn test_fts_query_ranks_by_bm25() {
+        let search = SearchFilter::new("rust orm", vec!["title"]).fts("posts_fts");
+        let (sql, params) = search.fts_query("posts_fts", "posts", "id");
+        assert_eq!(
+            sql,
+            "SELECT \"posts\".*, -bm25(\"posts_fts\") AS \"__search_score\" FROM \"posts\" \
+             JOIN \"posts_fts\" ON \"posts_fts\".rowid = \"posts\".\"id\" \
+             WHERE \"posts_fts\" MATCH ? ORDER BY \"__search_score\" DESC"
+        );
+        assert_eq!(params, vec![Value::Text("\"rust\" \"orm\"".to_string())]);
+    }
+
+    #[test]
+    fn test_fts_terms_are_quoted_unless_raw() {
+        let search = SearchFilter::new("c++ \"NEAR(a b)\"  OR", vec!["body"]);
+        let (_, params) = search.fts_query("posts_fts", "posts", "id");
+        assert_eq!(
+            params,
+            vec![Value::Text(
+                "\"c++\" \"\"\"NEAR(a\" \"b)\"\"\" \"OR\"".to_string()
+            )]
+        );
+
+        let (_, params) = search.raw_query(true).fts_query("posts_fts", "posts", "id");
+        assert_eq!(
+            params,
+            vec![Value::Text("c++ \"NEAR(a b)\"  OR".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fts_exact_match_quotes_phrase() {
+        let search = SearchFilter::new("say \"hi\"", vec!["body"]).exact_match(true);
+        let (_, params) = search.fts_query("posts_fts", "posts", "id");
+        assert_eq!(params, vec![Value::Text("\"say \"\"hi\"\"\"".to_string())]);
+    }
+
+    #[test]
+    fn test_case_sensitive_search_uses_instr() {
+        let search = SearchFilter::new("Rust", vec!["title"]).case_sensitive(true);
+        let (sql, params) = crate::QueryBuilder::new("posts")
+            .r#where(search.to_filter_operator())
+            .to_sql()
+            .unwrap();
+        assert_eq!(sql, r#"SELECT * FROM "posts" WHERE instr("title", ?) > ?"#);
+        assert_eq!(params[0], Value::Text("Rust".to_string()));
+    }
+
+    #[test]
+    fn test_nocase_applies_to_any_operator() {
+        use crate::Filter;
+
+        let (sql, _) = crate::QueryBuilder::new("users")
+            .r#where(Filter::in_values("role", vec!["Admin", "Owner"]).nocase())
+            .r#where(Filter::not_like("name", "bot%").nocase())
+            .to_sql()
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "users" WHERE "role" COLLATE NOCASE IN (?, ?) AND lower("name") NOT LIKE lower(?)"#
+        );
+    }
+
+    #[test]
+    fn test_like_fallback_scores_matching_columns() {
+        let search = SearchFilter::new("rust", vec!["title", "body"]);
+        let (sql, params) = search.like_ranked_query("posts").to_sql().unwrap();
+        assert_eq!(
+            sql,
+            "SELECT *, CASE WHEN like(lower(?), lower(\"title\"), ?) THEN ? ELSE ? END \
+             + CASE WHEN like(lower(?), lower(\"body\"), ?) THEN ? ELSE ? END AS \"__search_score\" \
+             FROM \"posts\" WHERE (lower(\"title\") LIKE lower(?) ESCAPE '\\' OR lower(\"body\") LIKE lower(?) ESCAPE '\\') \
+             ORDER BY \"__search_score\" DESC"
+        );
+        assert_eq!(params.len(), 10);
+    }
+}
+
+#[cfg(test)]
+mod binding_tests {
+    use crate::{col, val, Filter, FilterOperator, QueryBuilder, SearchFilter, Value};
+    use proptest::prelude::*;
+
+    /// Strings built from SQL metacharacters, plus arbitrary Unicode
+    fn hostile() -> impl Strategy<Value = String> {
+        prop_oneof![
+            r#"[%_\\'"?;:@$()\[\]`\-*/ a-zA-Z0-9]{0,24}"#,
+            Just("'; DROP TABLE users; --".to_string()),
+            Just(r#"" OR "1"="1"#.to_string()),
+            any::<String>(),
+        ]
+    }
+
+    /// Every builder feature that takes a value, rendered with `text`,
+    /// `number`, and `page`
+    fn queries(text: &str, number: i64, page: u32) -> Vec<(String, Vec<Value>)> {
+        let many: Vec<String> = (0..600).map(|i| format!("{text}{i}")).collect();
+        let builders = [
+            QueryBuilder::new("users")
+                .r#where(Filter::eq("name", text))
+                .r#where(Filter::ne("age", number))
+                .r#where(Filter::eq_nocase("email", text))
+                .limit(page)
+                .offset(page),
+            QueryBuilder::new("users")
+                .r#where(Filter::like("name", text))
+                .r#where(Filter::ilike("email", text))
+                .r#where(Filter::not_like("name", text).nocase())
+                .r#where(Filter::glob("name", text))
+                .r#where(Filter::contains("name", text))
+                .r#where(Filter::starts_with("name", text).nocase())
+                .r#where(Filter::ends_with("name", text)),
+            QueryBuilder::new("users")
+                .r#where(Filter::in_values("name", vec![text, text]))
+                .r#where(Filter::not_in("name", many))
+                .r#where(Filter::between("age", number, number))
+                .r#where(Filter::raw("age > ?", [number]))
+                .r#where(Filter::json_contains("tags", text))
+                .having(FilterOperator::Single(Filter::gt("age", number))),
+            QueryBuilder::new("users").search("name", text),
+            QueryBuilder::new("users")
+                .r#where(SearchFilter::new(text, vec!["name", "email"]).to_filter_operator())
+                .r#where(
+                    SearchFilter::new(text, vec!["name"])
+                        .case_sensitive(true)
+                        .to_filter_operator(),
+                )
+                .r#where(
+                    SearchFilter::new(text, vec!["name"])
+                        .exact_match(true)
+                        .to_filter_operator(),
+                ),
+            SearchFilter::new(text, vec!["name", "email"]).like_ranked_query("users"),
+            QueryBuilder::new("users")
+                .select_expr(col("name").concat(text), "labelled")
+                .r#where((col("age") + number).gt(val(number)))
+                .order_by_expr(col("name").eq(text), crate::SortOrder::Desc)
+                .offset(page),
+        ];
+        builders
+            .iter()
+            .flat_map(|builder| [builder.to_sql().unwrap(), builder.to_count_sql().unwrap()])
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_values_never_reach_the_sql(
+            a in hostile(),
+            b in hostile(),
+            x in any::<i64>(),
+            y in any::<i64>(),
+            page in any::<u32>(),
+        ) {
+            for ((sql_a, params_a), (sql_b, params_b)) in
+                queries(&a, x, page).into_iter().zip(queries(&b, y, page.wrapping_add(1)))
+            {
+                // The SQL text depends only on the query's shape
+                prop_assert_eq!(&sql_a, &sql_b);
+                prop_assert_eq!(params_a.len(), params_b.len());
+                prop_assert_eq!(sql_a.matches('?').count(), params_a.len());
+            }
+        }
+
+        #[test]
+        fn test_escaped_patterns_round_trip(text in hostile()) {
+            let (_, params) = QueryBuilder::new("users")
+                .r#where(Filter::contains("name", &text))
+                .to_sql()
+                .unwrap();
+            let Value::Text(pattern) = &params[0] else {
+                panic!("pattern should be text");
+            };
+            let inner = &pattern[1..pattern.len() - 1];
+            let mut unescaped = String::new();
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => unescaped.push(chars.next().expect("dangling escape")),
+                    '%' | '_' => panic!("unescaped wildcard in {pattern:?}"),
+                    c => unescaped.push(c),
+                }
+            }
+            prop_assert_eq!(unescaped, text);
+        }
+
+        #[test]
+        fn test_collations_stay_a_single_name(collation in hostile()) {
+            let sql = crate::Sort::asc("name").collate(&collation).to_sql();
+            let name = sql
+                .strip_prefix("\"name\" COLLATE ")
+                .and_then(|rest| rest.strip_suffix(" ASC"))
+                .expect("collation should sit between column and order");
+            if name != collation {
+                prop_assert_eq!(name, crate::query::quote_name(&collation));
+            }
+
+            let sort = serde_json::from_value::<crate::Sort>(serde_json::json!({
+                "column": "name",
+                "order": "Asc",
+                "collation": collation,
+            }));
+            prop_assert_eq!(sort.is_ok(), name == collation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod migration_tests {
+    use crate::migrations::{foreign_keys, split_statements, ForeignKey};
+    use crate::templates;
+
+    #[test]
+    fn test_foreign_keys_parse_column_and_table_constraints() {
+        let keys = foreign_keys(
+            r#"CREATE TABLE "posts" ("id" INTEGER PRIMARY KEY, "user_id" INTEGER REFERENCES "users" ("id"), editor INTEGER REFERENCES users, PRIMARY KEY (id), CONSTRAINT fk_team FOREIGN KEY (team_id, org_id) REFERENCES teams (id, org), CHECK (id > 0))"#,
+        );
+        let key = |columns: &[&str], parent: &str, parent_columns: &[&str]| ForeignKey {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            parent: parent.to_string(),
+            parent_columns: parent_columns.iter().map(|c| c.to_string()).collect(),
+        };
+        assert_eq!(
+            keys,
+            vec![
+                key(&["user_id"], "users", &["id"]),
+                key(&["editor"], "users", &[]),
+                key(&["team_id", "org_id"], "teams", &["id", "org"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trigger_templates() {
+        let touch = templates::updated_at_trigger("posts", "updated_at");
+        assert_eq!(
+            touch.sql,
+            "CREATE TRIGGER \"posts_touch_updated_at\" AFTER UPDATE ON \"posts\" FOR EACH ROW \
+             WHEN NEW.\"updated_at\" IS OLD.\"updated_at\" \
+             BEGIN UPDATE \"posts\" SET \"updated_at\" = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') \
+             WHERE rowid = NEW.rowid; END"
+        );
+
+        let audit = templates::create_trigger(
+            "log_user_delete",
+            "users",
+            "AFTER DELETE",
+            "INSERT INTO audit_log (user_id) VALUES (OLD.id);",
+        );
+        assert_eq!(
+            audit.sql,
+            r#"CREATE TRIGGER "log_user_delete" AFTER DELETE ON "users" FOR EACH ROW BEGIN INSERT INTO audit_log (user_id) VALUES (OLD.id); END"#
+        );
+        assert_eq!(
+            templates::drop_trigger("log_user_delete").sql,
+            r#"DROP TRIGGER "log_user_delete""#
+        );
+    }
+
+    #[test]
+    fn test_split_statements_keeps_trigger_bodies_together() {
+        let script = "CREATE TABLE a (id INTEGER);\n\
+             CREATE TRIGGER t AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); DELETE FROM c; END;\n\
+             INSERT INTO a VALUES (';');";
+        assert_eq!(
+            split_statements(script),
+            vec![
+                "CREATE TABLE a (id INTEGER)",
+                "CREATE TRIGGER t AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); DELETE FROM c; END",
+                "INSERT INTO a VALUES (';')",
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod options_tests {
+    use crate::{CancellationToken, QueryOptions, Result};
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_timeout_wakes_a_stalled_statement() {
+        let options = QueryOptions::new().timeout(Duration::from_millis(20));
+        let err = options
+            .run(std::future::pending::<Result<()>>())
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_cancel_wakes_a_stalled_statement() {
+        let token = CancellationToken::new();
+        let options = QueryOptions::new().cancellation_token(token.clone());
+        let cancel = async {
+            tokio::task::yield_now().await;
+            token.cancel();
+        };
+        let (result, ()) = tokio::join!(options.run(std::future::pending::<Result<()>>()), cancel);
+        assert!(result.unwrap_err().is_cancelled());
+        assert!(options
+            .run(async { Ok(()) })
+            .await
+            .unwrap_err()
+            .is_cancelled());
+        assert!(QueryOptions::new().run(async { Ok(1) }).await.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_finished_statements_unregister_their_wakers() {
+        let token = CancellationToken::new();
+        let options = QueryOptions::new().cancellation_token(token.clone());
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let options = options.clone();
+                tokio::spawn(async move {
+                    options
+                        .run(async {
+                            tokio::task::yield_now().await;
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(token.registered_wakers(), 0);
+
+        // A dropped statement unregisters too
+        let mut stalled = Box::pin(options.run(std::future::pending::<Result<()>>()));
+        std::future::poll_fn(|cx| {
+            assert!(std::future::Future::poll(stalled.as_mut(), cx).is_pending());
+            std::task::Poll::Ready(())
+        })
+        .await;
+        assert_eq!(token.registered_wakers(), 1);
+        drop(stalled);
+        assert_eq!(token.registered_wakers(), 0);
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use crate::Error;
+
+    #[test]
+    fn test_busy_errors_are_retryable() {
+        let err = Error::from(turso::Error::Busy("database is locked".to_string()));
+        assert!(matches!(err, Error::Busy(_)));
+        assert!(err.is_busy());
+        assert!(err.is_retryable());
+        assert!(!err.is_transient_network());
+
+        let wrapped = Error::statement(err, Some("users"), "INSERT INTO users", vec![]);
+        assert!(wrapped.is_busy());
+        assert!(
+            Error::DatabaseError("Migration x failed: database is locked".to_string()).is_busy()
+        );
+    }
+
+    #[test]
+    fn test_network_errors_are_transient() {
+        let err = Error::from(turso::Error::IoError(std::io::ErrorKind::TimedOut));
+        assert!(matches!(err, Error::Connection(_)));
+        assert!(err.is_transient_network());
+        assert!(Error::Sql("sync failed: HTTP 503 Service Unavailable".to_string()).is_retryable());
+        assert!(!err.is_busy());
+    }
+
+    #[test]
+    fn test_status_codes_need_structured_context() {
+        for transient in [
+            "sync failed: status 503",
+            "request failed with status code 502",
+            "unexpected HTTP 504",
+            "timed out",
+            "connection timed out",
+        ] {
+            assert!(
+                Error::Sql(transient.to_string()).is_transient_network(),
+                "{transient}"
+            );
+        }
+        for unrelated in [
+            "UNIQUE constraint failed: orders.id = 5031",
+            "no such table: invoices_503",
+            "Value 5042 out of range",
+            "lock wait timed out after job 504",
+        ] {
+            assert!(
+                !Error::Sql(unrelated.to_string()).is_transient_network(),
+                "{unrelated}"
+            );
+            assert!(
+                !Error::Sql(unrelated.to_string()).is_retryable(),
+                "{unrelated}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_logic_errors_are_not_retryable() {
+        let constraint = Error::from(turso::Error::Constraint(
+            "UNIQUE constraint failed: users.email".to_string(),
+        ));
+        assert!(!constraint.is_retryable());
+        assert!(!Error::Sql("no such table: users".to_string()).is_retryable());
+        assert!(!Error::Validation("request timed out".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_status_codes() {
+        let constraint = Error::from(turso::Error::Constraint(
+            "UNIQUE constraint failed: users.email".to_string(),
+        ));
+        assert_eq!(constraint.status_code(), 409);
+        assert_eq!(
+            Error::statement(constraint, Some("users"), "INSERT INTO users", vec![]).status_code(),
+            409
+        );
+        assert_eq!(Error::NotFound("user 1".to_string()).status_code(), 404);
+        assert_eq!(
+            Error::InvalidFields(crate::ValidationErrors::new()).status_code(),
+            422
+        );
+        assert_eq!(
+            Error::Busy("database is locked".to_string()).status_code(),
+            503
+        );
+        assert_eq!(
+            Error::Sql("no such table: users".to_string()).status_code(),
+            500
+        );
+    }
+}
+
+#[cfg(test)]
+mod validation_tests {
+    use crate::validation;
+    use crate::ValidationErrors;
+
+    #[test]
+    fn test_validators_report_by_field() {
+        let mut errors = ValidationErrors::new();
+        validation::email(&mut errors, "email", "alice@example.com");
+        validation::email(
+            &mut errors,
+            "backup_email",
+            &Some("alice@localhost".to_string()),
+        );
+        validation::length(&mut errors, "name", "Zoë", Some(3), None);
+        validation::length(&mut errors, "bio", "too long", None, Some(3));
+        validation::range(&mut errors, "age", &None::<i64>, Some(0.0), None);
+        validation::range(&mut errors, "score", &-0.5, Some(0.0), Some(1.0));
+
+        assert_eq!(
+            errors.fields().keys().collect::<Vec<_>>(),
+            vec!["backup_email", "bio", "score"]
+        );
+        assert_eq!(
+            errors.to_string(),
+            "backup_email must be a valid email address; bio must be at most 3 characters; \
+             score must be between 0 and 1"
+        );
+        assert_eq!(
+            serde_json::to_value(&errors).unwrap()["bio"][0]["code"],
+            "length"
+        );
+    }
+}
+
+#[cfg(test)]
+mod testing_tests {
+    use crate::testing::{check_sql_snapshot, normalize_sql};
+
+    #[test]
+    fn test_normalize_sql_ignores_layout_but_not_literals() {
+        assert_eq!(
+            normalize_sql(
+                "SELECT  id,\n       name\nFROM users\nWHERE ( age > ? )\n  AND name = 'a  b' ;\n"
+            ),
+            "SELECT id, name FROM users WHERE (age > ?) AND name = 'a  b'"
+        );
+        assert_eq!(
+            normalize_sql("SELECT \"first  name\" FROM t WHERE note = 'it''s  here'"),
+            "SELECT \"first  name\" FROM t WHERE note = 'it''s  here'"
+        );
+    }
+
+    #[test]
+    fn test_sql_snapshot_written_then_compared() {
+        let path = std::env::temp_dir()
+            .join(format!("libsql-orm-snapshots-{}", std::process::id()))
+            .join("users.sql");
+        let _ = std::fs::remove_file(&path);
+
+        check_sql_snapshot(&path, "SELECT *\n  FROM users").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "SELECT * FROM users\n"
+        );
+        check_sql_snapshot(&path, "SELECT * FROM users;").unwrap();
+
+        let err = check_sql_snapshot(&path, "SELECT id FROM users").unwrap_err();
+        assert!(err.contains("expected: SELECT * FROM users"));
+        assert!(err.contains("  actual: SELECT id FROM users"));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
 }
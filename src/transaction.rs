@@ -0,0 +1,152 @@
+//! Explicit transactions that models and builders can run inside
+//!
+//! [`Database::begin`] starts a transaction and returns a [`Transaction`],
+//! which is an [`Executor`]: the same `create`, `update`, `find_*`, and
+//! builder `execute` calls used against a [`Database`] run inside the
+//! transaction when given `&tx` instead. Bulk operations notice the open
+//! transaction and skip their own `BEGIN`/`COMMIT`.
+//!
+//! Change events from models are held back until [`Transaction::commit`]
+//! succeeds, and dropped on [`Transaction::rollback`], so subscribers never
+//! see changes that were rolled back.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{Database, Executor, Model, Result};
+//! # #[derive(libsql_orm::Model, Clone, serde::Serialize, serde::Deserialize)]
+//! # struct Account { id: Option<i64>, balance: i64 }
+//!
+//! async fn deposit(db: &impl Executor, account: &Account, amount: i64) -> Result<Account> {
+//!     let mut account = account.clone();
+//!     account.balance += amount;
+//!     account.update(db).await
+//! }
+//!
+//! # async fn example(db: &Database, from: Account, to: Account) -> Result<()> {
+//! let tx = db.begin().await?;
+//! deposit(&tx, &from, -100).await?;
+//! deposit(&tx, &to, 100).await?;
+//! tx.commit().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ChangeEvent, Database, Executor, Result, Row, Value};
+use std::sync::Mutex;
+
+/// An open transaction on a [`Database`]
+///
+/// Finish it with [`commit`](Self::commit) or [`rollback`](Self::rollback).
+/// Dropping it unfinished cannot roll back right away (that needs an
+/// `await`), so a warning is logged and the database rolls the transaction
+/// back before it runs its next statement; the held change events are
+/// discarded.
+pub struct Transaction<'a> {
+    db: &'a Database,
+    events: Mutex<Vec<ChangeEvent>>,
+    finished: bool,
+}
+
+impl Database {
+    /// Start a transaction
+    pub async fn begin(&self) -> Result<Transaction<'_>> {
+        self.execute_for(None, "BEGIN", vec![]).await?;
+        Ok(Transaction {
+            db: self,
+            events: Mutex::new(Vec::new()),
+            finished: false,
+        })
+    }
+}
+
+impl<'a> Transaction<'a> {
+    /// The database this transaction runs on
+    pub fn database(&self) -> &'a Database {
+        self.db
+    }
+
+    /// Commit the transaction, then deliver its change events
+    ///
+    /// If COMMIT fails, the transaction is rolled back and its events are
+    /// discarded.
+    pub async fn commit(mut self) -> Result<()> {
+        if let Err(e) = self.db.execute_for(None, "COMMIT", vec![]).await {
+            // A failed COMMIT, such as a deferred constraint, leaves the
+            // transaction open
+            self.finished = !self.db.in_transaction()
+                || self.db.execute_for(None, "ROLLBACK", vec![]).await.is_ok();
+            return Err(e);
+        }
+        self.finished = true;
+        let events = std::mem::take(&mut *self.events_guard());
+        self.db.emit_changes(|| events);
+        Ok(())
+    }
+
+    /// Roll the transaction back, discarding its change events
+    pub async fn rollback(mut self) -> Result<()> {
+        self.db.execute_for(None, "ROLLBACK", vec![]).await?;
+        self.finished = true;
+        Ok(())
+    }
+
+    fn events_guard(&self) -> std::sync::MutexGuard<'_, Vec<ChangeEvent>> {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            log::warn!(
+                "Transaction dropped without commit or rollback; it is rolled back before the next statement"
+            );
+            self.db.mark_dropped_transaction();
+        }
+    }
+}
+
+impl Executor for Transaction<'_> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<u64> {
+        self.db.execute_statement(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<Row>> {
+        self.db.query_statement(table, sql, params).await
+    }
+
+    async fn query_statement_with_options(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<Value>,
+        options: &crate::QueryOptions,
+    ) -> Result<Vec<Row>> {
+        self.db
+            .query_statement_with_options(table, sql, params, options)
+            .await
+    }
+
+    fn emit_changes(&self, events: impl FnOnce() -> Vec<ChangeEvent>) {
+        if self.db.has_subscribers() {
+            self.events_guard().extend(events());
+        }
+    }
+
+    fn in_transaction(&self) -> bool {
+        true
+    }
+}
@@ -8,6 +8,7 @@
 //! - [`Value`] - Represents any database value with automatic type conversion
 //! - [`Row`] - Type alias for a database row (HashMap of column names to values)
 //! - [`SortOrder`] - Ascending or descending sort order
+//! - [`NullsOrder`] - Placement of NULLs in a sort
 //! - [`Aggregate`] - SQL aggregate functions (COUNT, SUM, AVG, etc.)
 //! - [`JoinType`] - SQL join types (INNER, LEFT, RIGHT, FULL)
 //! - [`Operator`] - SQL comparison operators
@@ -170,6 +171,35 @@ impl From<serde_json::Value> for Value {
     }
 }
 
+/// Timestamps are stored as RFC 3339 text in UTC, the same format serde
+/// produces for model fields, so range filters compare lexicographically
+impl From<chrono::DateTime<chrono::Utc>> for Value {
+    fn from(v: chrono::DateTime<chrono::Utc>) -> Self {
+        Value::Text(v.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+}
+
+impl From<chrono::NaiveDateTime> for Value {
+    fn from(v: chrono::NaiveDateTime) -> Self {
+        Value::Text(format!("{v:?}"))
+    }
+}
+
+impl From<chrono::NaiveDate> for Value {
+    fn from(v: chrono::NaiveDate) -> Self {
+        Value::Text(v.format("%Y-%m-%d").to_string())
+    }
+}
+
+impl From<Option<chrono::DateTime<chrono::Utc>>> for Value {
+    fn from(v: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        match v {
+            Some(dt) => dt.into(),
+            None => Value::Null,
+        }
+    }
+}
+
 /// Sort order for queries
 ///
 /// Specifies whether query results should be sorted in ascending or descending order.
@@ -198,6 +228,25 @@ impl std::fmt::Display for SortOrder {
     }
 }
 
+/// Placement of NULL values in a sort
+///
+/// SQLite puts NULLs first in ascending order and last in descending order;
+/// use this to override that per column.
+///
+/// # Examples
+///
+/// ```rust
+/// use libsql_orm::{NullsOrder, Sort};
+///
+/// let sort = Sort::desc("last_login").nulls(NullsOrder::Last);
+/// assert_eq!(sort.to_sql(), r#"("last_login" IS NULL) ASC, "last_login" DESC"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
 /// Aggregate functions
 ///
 /// SQL aggregate functions for performing calculations on sets of values.
@@ -308,6 +357,12 @@ pub enum Operator {
     IsNotNull,
     Between,
     NotBetween,
+    Glob,
+    NotGlob,
+    #[cfg(feature = "regexp")]
+    Regexp,
+    /// The JSON array in the column has an element equal to the value
+    JsonContains,
 }
 
 impl std::fmt::Display for Operator {
@@ -327,6 +382,11 @@ impl std::fmt::Display for Operator {
             Operator::IsNotNull => write!(f, "IS NOT NULL"),
             Operator::Between => write!(f, "BETWEEN"),
             Operator::NotBetween => write!(f, "NOT BETWEEN"),
+            Operator::Glob => write!(f, "GLOB"),
+            Operator::NotGlob => write!(f, "NOT GLOB"),
+            #[cfg(feature = "regexp")]
+            Operator::Regexp => write!(f, "REGEXP"),
+            Operator::JsonContains => write!(f, "CONTAINS"),
         }
     }
 }
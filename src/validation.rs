@@ -0,0 +1,263 @@
+//! Declarative field validation for models
+//!
+//! Fields of a `#[derive(Model)]` struct can carry `#[orm_validate(...)]`
+//! rules. They are checked by [`Model::validate`](crate::Model::validate),
+//! which [`create`](crate::Model::create), [`update`](crate::Model::update),
+//! and the bulk and upsert variants call before any SQL runs. Failures are
+//! collected into [`ValidationErrors`], keyed by field, and returned as
+//! [`Error::InvalidFields`](crate::Error::InvalidFields).
+//!
+//! # Rules
+//!
+//! - `email` - a plausible `local@domain.tld` address
+//! - `length(min = 3, max = 80)` - length of a string in characters
+//! - `range(min = 0, max = 150)` - bounds of a number, inclusive
+//! - `custom = "path::to::fn"` - a function taking the field by reference and
+//!   returning `Result<(), String>`
+//!
+//! `None` values of `Option` fields pass every rule.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libsql_orm::{Database, Error, Model, Result};
+//! use serde::{Deserialize, Serialize};
+//!
+//! fn no_spaces(username: &str) -> std::result::Result<(), String> {
+//!     if username.contains(' ') {
+//!         return Err("must not contain spaces".to_string());
+//!     }
+//!     Ok(())
+//! }
+//!
+//! #[derive(Model, Clone, Serialize, Deserialize)]
+//! struct User {
+//!     pub id: Option<i64>,
+//!     #[orm_validate(length(min = 3, max = 80), custom = "no_spaces")]
+//!     pub username: String,
+//!     #[orm_validate(email)]
+//!     pub email: String,
+//!     #[orm_validate(range(min = 0))]
+//!     pub age: Option<i64>,
+//! }
+//!
+//! # async fn example(db: &Database, user: User) -> Result<()> {
+//! match user.create(db).await {
+//!     Err(Error::InvalidFields(errors)) => {
+//!         for (field, failures) in errors.fields() {
+//!             println!("{field}: {}", failures[0].message);
+//!         }
+//!     }
+//!     other => {
+//!         other?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A single failed rule on a field
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FieldError {
+    /// Name of the rule that failed: `email`, `length`, `range`, or `custom`
+    pub code: String,
+    /// Human-readable description of the failure
+    pub message: String,
+}
+
+/// Validation failures of a model, keyed by field name
+///
+/// Serializes as `{"field": [{"code": ..., "message": ...}]}`, which can be
+/// returned to API clients as is.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct ValidationErrors {
+    fields: BTreeMap<String, Vec<FieldError>>,
+}
+
+impl ValidationErrors {
+    /// Create an empty set of errors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a failure for `field`
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.fields
+            .entry(field.to_string())
+            .or_default()
+            .push(FieldError {
+                code: code.to_string(),
+                message: message.into(),
+            });
+    }
+
+    /// Whether no failures were recorded
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Failures for one field, if it has any
+    pub fn get(&self, field: &str) -> Option<&[FieldError]> {
+        self.fields.get(field).map(Vec::as_slice)
+    }
+
+    /// All failures, sorted by field name
+    pub fn fields(&self) -> &BTreeMap<String, Vec<FieldError>> {
+        &self.fields
+    }
+
+    /// `Ok(())` when empty, otherwise `Err(self)`
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (field, errors) in &self.fields {
+            for error in errors {
+                if !first {
+                    write!(f, "; ")?;
+                }
+                write!(f, "{field} {}", error.message)?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Field types the built-in rules can inspect
+///
+/// Implemented for strings, numbers, and `Option`s of them; `None` skips
+/// every rule.
+pub trait ValidateValue {
+    /// The value as text, for `email` and `length`
+    fn as_text(&self) -> Option<&str> {
+        None
+    }
+
+    /// The value as a number, for `range`
+    fn as_number(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl ValidateValue for String {
+    fn as_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl ValidateValue for str {
+    fn as_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+macro_rules! impl_validate_number {
+    ($($ty:ty),*) => {
+        $(
+            impl ValidateValue for $ty {
+                fn as_number(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_validate_number!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl<T: ValidateValue> ValidateValue for Option<T> {
+    fn as_text(&self) -> Option<&str> {
+        self.as_ref().and_then(T::as_text)
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        self.as_ref().and_then(T::as_number)
+    }
+}
+
+/// Check the `email` rule
+pub fn email<V: ValidateValue + ?Sized>(errors: &mut ValidationErrors, field: &str, value: &V) {
+    if let Some(text) = value.as_text() {
+        if !is_email(text) {
+            errors.add(field, "email", "must be a valid email address");
+        }
+    }
+}
+
+/// Check the `length` rule, counting characters
+pub fn length<V: ValidateValue + ?Sized>(
+    errors: &mut ValidationErrors,
+    field: &str,
+    value: &V,
+    min: Option<usize>,
+    max: Option<usize>,
+) {
+    let Some(text) = value.as_text() else {
+        return;
+    };
+    let len = text.chars().count();
+    let message = match (min, max) {
+        (Some(min), Some(max)) if len < min || len > max => {
+            format!("must be between {min} and {max} characters")
+        }
+        (Some(min), _) if len < min => format!("must be at least {min} characters"),
+        (_, Some(max)) if len > max => format!("must be at most {max} characters"),
+        _ => return,
+    };
+    errors.add(field, "length", message);
+}
+
+/// Check the `range` rule, with inclusive bounds
+pub fn range<V: ValidateValue + ?Sized>(
+    errors: &mut ValidationErrors,
+    field: &str,
+    value: &V,
+    min: Option<f64>,
+    max: Option<f64>,
+) {
+    let Some(number) = value.as_number() else {
+        return;
+    };
+    let message = match (min, max) {
+        (Some(min), Some(max)) if number < min || number > max => {
+            format!("must be between {min} and {max}")
+        }
+        (Some(min), _) if number < min => format!("must be at least {min}"),
+        (_, Some(max)) if number > max => format!("must be at most {max}"),
+        _ => return,
+    };
+    errors.add(field, "range", message);
+}
+
+/// Check a `custom` rule's result
+pub fn custom(errors: &mut ValidationErrors, field: &str, result: Result<(), String>) {
+    if let Err(message) = result {
+        errors.add(field, "custom", message);
+    }
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !text.chars().any(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty())
+}
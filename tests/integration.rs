@@ -1,25 +1,78 @@
+use libsql_orm::testing::{MockDatabase, TestDatabase};
 use libsql_orm::{
-    Database, Filter, FilterOperator, MigrationBuilder, MigrationManager, Model, Pagination,
-    QueryBuilder, SearchFilter, Sort, SortOrder,
+    case_when, coalesce, col, func, length, lower, templates, val, Database, DeleteBuilder,
+    Factory, Filter, FilterOperator, FromRow, InsertBuilder, MigrationBuilder, MigrationManager,
+    Model, Pagination, QueryBuilder, SearchFilter, Sort, SortOrder, UpdateBuilder,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Once;
 
 static LOGGER: Once = Once::new();
 
-#[derive(Model, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Model, Factory, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[table_name("users")]
 struct User {
     #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
     pub id: Option<i64>,
     pub name: String,
+    #[factory(sequence = "user{n}@example.com")]
     pub email: String,
     #[orm_column(type = "INTEGER")]
     pub age: Option<i64>,
     #[orm_column(type = "REAL")]
     pub score: Option<f64>,
     #[orm_column(type = "INTEGER")]
+    #[factory(default = "true")]
+    pub is_active: bool,
+}
+
+fn no_spaces(username: &str) -> Result<(), String> {
+    if username.contains(' ') {
+        return Err("must not contain spaces".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Model, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[table_name("signups")]
+struct Signup {
+    pub id: Option<i64>,
+    #[orm_validate(length(min = 3, max = 20), custom = "no_spaces")]
+    pub username: String,
+    #[orm_validate(email)]
+    pub email: String,
+    #[orm_validate(range(min = 13, max = 120))]
+    pub age: Option<i64>,
+}
+
+#[derive(FromRow, Debug, Deserialize)]
+struct NameAndEmail {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(FromRow, Debug, Deserialize)]
+struct UserPostTitle {
+    pub name: String,
+    pub title: Option<String>,
+}
+
+#[derive(FromRow, Debug, Deserialize)]
+struct ActivityReport {
+    pub total: i64,
+    pub active: i64,
+    pub inactive: i64,
+}
+
+#[derive(FromRow, Debug, Deserialize)]
+struct ColumnName {
+    pub name: String,
+}
+
+#[derive(FromRow, Debug, Deserialize)]
+struct ActiveCount {
     pub is_active: bool,
+    pub n: i64,
 }
 
 fn init_logger() {
@@ -28,11 +81,9 @@ fn init_logger() {
     });
 }
 
-async fn setup_db() -> libsql_orm::Result<Database> {
+async fn setup_db() -> libsql_orm::Result<TestDatabase> {
     init_logger();
-    let db = Database::new_local(":memory:").await?;
-    db.execute(&User::migration_sql(), vec![]).await?;
-    Ok(db)
+    TestDatabase::new::<(User,)>().await
 }
 
 fn user(name: &str, email: &str, age: Option<i64>, score: Option<f64>, is_active: bool) -> User {
@@ -288,15 +339,64 @@ async fn bulk_delete_rows() {
         .await
         .unwrap();
 
-    let deleted_count = User::bulk_delete(&[a.id.unwrap(), b.id.unwrap()], &db)
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = events.clone();
+    db.subscribe(move |event| sink.lock().unwrap().push(event.primary_key));
+
+    // Keys without a row are neither counted nor reported
+    let deleted_count = User::bulk_delete(&[a.id.unwrap(), b.id.unwrap(), 999_999], &db)
         .await
         .unwrap();
     assert_eq!(deleted_count, 2);
+    assert_eq!(*events.lock().unwrap(), vec![a.id, b.id]);
     let all = User::find_all(&db).await.unwrap();
     assert_eq!(all.len(), 1);
     assert_eq!(all[0].id, c.id);
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn failed_bulk_writes_roll_back() {
+    let db = setup_db().await.unwrap();
+    db.execute(
+        "CREATE UNIQUE INDEX idx_users_email ON users (email)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let a = insert_and_get_real(&db, &user("A", "a@example.com", None, None, true))
+        .await
+        .unwrap();
+    let b = insert_and_get_real(&db, &user("B", "b@example.com", None, None, true))
+        .await
+        .unwrap();
+
+    let created = User::bulk_create(
+        &[
+            user("C", "c@example.com", None, None, true),
+            user("D", "a@example.com", None, None, true),
+        ],
+        &db,
+    )
+    .await;
+    assert!(created.is_err());
+    assert!(!db.in_transaction());
+    assert_eq!(User::count(&db).await.unwrap(), 2);
+
+    // The first update ran before the second failed
+    let renamed = User {
+        name: "Ann".to_string(),
+        ..a.clone()
+    };
+    let clashing = User {
+        email: a.email.clone(),
+        ..b
+    };
+    assert!(User::bulk_update(&[renamed, clashing], &db).await.is_err());
+    assert!(!db.in_transaction());
+    let a = User::find_by_id(a.id.unwrap(), &db).await.unwrap().unwrap();
+    assert_eq!(a.name, "A");
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn delete_where_removes_matching_rows() {
     let db = setup_db().await.unwrap();
@@ -307,14 +407,78 @@ async fn delete_where_removes_matching_rows() {
         .await
         .unwrap();
 
-    let _ = User::delete_where(FilterOperator::Single(Filter::gt("age", 30i64)), &db)
+    let deleted = User::delete_where(FilterOperator::Single(Filter::gt("age", 30i64)), &db)
         .await
         .unwrap();
+    assert_eq!(deleted, 1);
     let all = User::find_all(&db).await.unwrap();
     assert_eq!(all.len(), 1);
     assert_eq!(all[0].name, "DW1");
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn mutation_builders_insert_update_delete() {
+    let db = setup_db().await.unwrap();
+    for (name, age) in [("MB1", 20i64), ("MB2", 35), ("MB3", 50)] {
+        let inserted = InsertBuilder::table("users")
+            .value("name", name)
+            .value("email", format!("{}@example.com", name.to_lowercase()))
+            .value("age", age)
+            .value("is_active", true)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+    }
+
+    let updated = UpdateBuilder::table("users")
+        .set("is_active", false)
+        .r#where(FilterOperator::Single(Filter::gt("age", 30i64)))
+        .execute(&db)
+        .await
+        .unwrap();
+    assert_eq!(updated, 2);
+    let inactive = User::count_where(FilterOperator::Single(Filter::eq("is_active", false)), &db)
+        .await
+        .unwrap();
+    assert_eq!(inactive, 2);
+
+    let deleted = DeleteBuilder::table("users")
+        .r#where(FilterOperator::Single(Filter::eq("is_active", false)))
+        .execute(&db)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 2);
+    let remaining = User::find_all(&db).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].name, "MB1");
+}
+
+#[test]
+fn model_statements_render_without_executing() {
+    let mut u = user("Stmt", "stmt@example.com", Some(30), None, true);
+
+    let (sql, params) = u.insert_statement().unwrap();
+    assert_eq!(
+        sql,
+        r#"INSERT INTO "users" ("id", "name", "email", "age", "score", "is_active") VALUES (?, ?, ?, ?, ?, ?)"#
+    );
+    assert_eq!(params.len(), 6);
+
+    assert!(u.update_statement().is_err());
+    u.id = Some(5);
+    let (sql, params) = u.update_statement().unwrap();
+    assert_eq!(
+        sql,
+        r#"UPDATE "users" SET "name" = ?, "email" = ?, "age" = ?, "score" = ?, "is_active" = ? WHERE "id" = ?"#
+    );
+    assert_eq!(params.last(), Some(&libsql_orm::Value::Integer(5)));
+
+    let (sql, params) = u.delete_statement().unwrap();
+    assert_eq!(sql, r#"DELETE FROM "users" WHERE "id" = ?"#);
+    assert_eq!(params, vec![libsql_orm::Value::Integer(5)]);
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn count_empty_table() {
     let db = setup_db().await.unwrap();
@@ -385,6 +549,49 @@ async fn find_paginated_last_page() {
     assert_eq!(result.pagination.total_pages, Some(3));
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn find_paginated_without_total() {
+    let db = setup_db().await.unwrap();
+    for i in 0..5 {
+        let email = format!("p3_{i}@example.com");
+        insert_and_get_real(&db, &user("Pg", &email, Some(i), None, true))
+            .await
+            .unwrap();
+    }
+
+    let page = Pagination::new(2, 2).without_total();
+    let result = User::find_paginated(&page, &db).await.unwrap();
+    assert_eq!(result.data.len(), 2);
+    assert_eq!(result.pagination.total, None);
+    assert_eq!(result.pagination.total_pages, None);
+    assert!(result.pagination.has_next());
+
+    let page = Pagination::new(3, 2).without_total();
+    let result = User::find_paginated(&page, &db).await.unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert!(!result.pagination.has_next());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn execute_paginated_counts_filtered_rows() {
+    let db = setup_db().await.unwrap();
+    for i in 0..5 {
+        let email = format!("p4_{i}@example.com");
+        insert_and_get_real(&db, &user("Pg", &email, Some(i), None, i % 2 == 0))
+            .await
+            .unwrap();
+    }
+
+    let result = QueryBuilder::new("users")
+        .r#where(Filter::eq("is_active", true))
+        .execute_paginated::<User>(&db, &Pagination::new(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(result.data.len(), 2);
+    assert_eq!(result.pagination.total, Some(3));
+    assert_eq!(result.pagination.total_pages, Some(2));
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn query_builder_select_all() {
     let db = setup_db().await.unwrap();
@@ -443,162 +650,3633 @@ async fn query_builder_order_limit_offset() {
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn query_builder_execute_count() {
+async fn query_builder_select_projection_into_from_row() {
     let db = setup_db().await.unwrap();
-    insert_and_get_real(&db, &user("QC1", "qc1@example.com", Some(25), None, true))
-        .await
-        .unwrap();
-    insert_and_get_real(&db, &user("QC2", "qc2@example.com", Some(50), None, true))
+    insert_and_get_real(&db, &user("Proj", "proj@example.com", Some(25), None, true))
         .await
         .unwrap();
 
-    let count = QueryBuilder::new("users")
-        .r#where(FilterOperator::Single(Filter::gt("age", 30i64)))
-        .execute_count(&db)
+    let rows = QueryBuilder::new("users")
+        .select(["name", "email"])
+        .execute_as::<NameAndEmail>(&db)
         .await
         .unwrap();
-    assert_eq!(count, 1);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Proj");
+    assert_eq!(rows[0].email, "proj@example.com");
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn search_filter_across_multiple_columns() {
+async fn query_builder_select_raw_aggregate_into_from_row() {
     let db = setup_db().await.unwrap();
-    insert_and_get_real(&db, &user("Search Me", "sm@example.com", None, None, true))
+    insert_and_get_real(&db, &user("RA1", "ra1@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("RA2", "ra2@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("RA3", "ra3@example.com", None, None, false))
         .await
         .unwrap();
-    insert_and_get_real(
-        &db,
-        &user("Another", "needle@example.com", None, None, true),
-    )
-    .await
-    .unwrap();
 
-    let search = SearchFilter::new("needle", vec!["name", "email"]);
-    let result = User::search(&search, None, &db).await.unwrap();
-    assert_eq!(result.data.len(), 1);
-    assert_eq!(result.data[0].email, "needle@example.com");
+    let rows = QueryBuilder::new("users")
+        .select_raw("is_active, count(*) AS n")
+        .group_by(vec!["is_active"])
+        .order_by(Sort::desc("is_active"))
+        .execute_as::<ActiveCount>(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].is_active);
+    assert_eq!(rows[0].n, 2);
+    assert!(!rows[1].is_active);
+    assert_eq!(rows[1].n, 1);
+}
+
+async fn create_posts_table(db: &Database) -> libsql_orm::Result<()> {
+    db.execute(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER NOT NULL, title TEXT NOT NULL, published INTEGER NOT NULL)",
+        vec![],
+    )
+    .await?;
+    Ok(())
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_or_update_creates_when_no_pk() {
+async fn query_builder_inner_join_into_from_row() {
     let db = setup_db().await.unwrap();
-    let u = user("COU", "cou@example.com", Some(44), None, true);
-    let _ = u.create_or_update(&db).await.unwrap();
-    assert_eq!(User::count(&db).await.unwrap(), 1);
+    create_posts_table(&db).await.unwrap();
+    let alice = insert_and_get_real(&db, &user("Alice", "alice@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("Bob", "bob@example.com", None, None, true))
+        .await
+        .unwrap();
+    for (title, published) in [("Draft", 0i64), ("Hello", 1), ("World", 1)] {
+        db.execute(
+            "INSERT INTO posts (user_id, title, published) VALUES (?, ?, ?)",
+            vec![
+                libsql_orm::compat::integer_value(alice.id.unwrap()),
+                libsql_orm::compat::text_value(title.to_string()),
+                libsql_orm::compat::integer_value(published),
+            ],
+        )
+        .await
+        .unwrap();
+    }
+
+    let rows = QueryBuilder::new("users")
+        .select(["users.name", "posts.title"])
+        .inner_join("posts", "posts.user_id = users.id")
+        .r#where(FilterOperator::Single(Filter::eq("posts.published", true)))
+        .order_by(Sort::desc("posts.title"))
+        .execute_as::<UserPostTitle>(&db)
+        .await
+        .unwrap();
+    let titles: Vec<_> = rows.iter().map(|r| r.title.as_deref().unwrap()).collect();
+    assert_eq!(titles, vec!["World", "Hello"]);
+    assert!(rows.iter().all(|r| r.name == "Alice"));
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_or_update_updates_when_pk_exists() {
+async fn query_builder_left_join_keeps_unmatched_rows() {
     let db = setup_db().await.unwrap();
-    let mut existing = insert_and_get_real(&db, &user("Old", "old@example.com", Some(20), None, true))
+    create_posts_table(&db).await.unwrap();
+    insert_and_get_real(&db, &user("Lonely", "lonely@example.com", None, None, true))
         .await
         .unwrap();
-    existing.name = "New".to_string();
-    existing.age = Some(21);
 
-    let _ = existing.create_or_update(&db).await.unwrap();
-    let fetched = User::find_by_id(existing.id.unwrap(), &db).await.unwrap().unwrap();
-    assert_eq!(fetched.name, "New");
-    assert_eq!(fetched.age, Some(21));
+    let rows = QueryBuilder::new("users")
+        .select(["users.name", "posts.title"])
+        .left_join("posts", "posts.user_id = users.id")
+        .execute_as::<UserPostTitle>(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Lonely");
+    assert!(rows[0].title.is_none());
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn data_round_trip_null_fields() {
+async fn query_builder_in_subquery_and_exists_filters() {
     let db = setup_db().await.unwrap();
-    let row = insert_and_get_real(
-        &db,
-        &user("Nulls", "nulls@example.com", None, None, true),
+    create_posts_table(&db).await.unwrap();
+    let author = insert_and_get_real(&db, &user("Author", "author@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("Reader", "reader@example.com", None, None, true))
+        .await
+        .unwrap();
+    db.execute(
+        "INSERT INTO posts (user_id, title, published) VALUES (?, ?, ?)",
+        vec![
+            libsql_orm::compat::integer_value(author.id.unwrap()),
+            libsql_orm::compat::text_value("Hello".to_string()),
+            libsql_orm::compat::integer_value(1),
+        ],
     )
     .await
     .unwrap();
-    assert_eq!(row.age, None);
-    assert_eq!(row.score, None);
+
+    let published_authors = QueryBuilder::new("posts")
+        .select(["user_id"])
+        .r#where(FilterOperator::Single(Filter::eq("published", true)));
+    let authors = QueryBuilder::new("users")
+        .r#where(FilterOperator::Single(Filter::in_subquery(
+            "id",
+            published_authors,
+        )))
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[0].name, "Author");
+
+    let posts_by_user = QueryBuilder::new("posts")
+        .r#where(FilterOperator::Custom(
+            "posts.user_id = users.id".to_string(),
+        ))
+        .r#where(FilterOperator::Single(Filter::eq("posts.title", "Hello")));
+    let readers = QueryBuilder::new("users")
+        .r#where(FilterOperator::not_exists(posts_by_user))
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    assert_eq!(readers.len(), 1);
+    assert_eq!(readers[0].name, "Reader");
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn data_round_trip_float_values() {
+async fn query_builder_with_cte_executes_and_counts() {
     let db = setup_db().await.unwrap();
-    let row = insert_and_get_real(
+    insert_and_get_real(
         &db,
-        &user("Float", "float@example.com", Some(10), Some(42.125), true),
+        &user("Young", "young@example.com", Some(15), None, true),
     )
     .await
     .unwrap();
-    assert_eq!(row.score, Some(42.125));
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn data_round_trip_boolean_false() {
-    let db = setup_db().await.unwrap();
-    let row = insert_and_get_real(
+    insert_and_get_real(
         &db,
-        &user("False", "false@example.com", Some(10), None, false),
+        &user("Adult", "adult@example.com", Some(30), None, true),
     )
     .await
     .unwrap();
-    assert!(!row.is_active);
+    insert_and_get_real(
+        &db,
+        &user("Idle", "idle@example.com", Some(40), None, false),
+    )
+    .await
+    .unwrap();
+
+    let adults =
+        QueryBuilder::new("users").r#where(FilterOperator::Single(Filter::ge("age", 18i64)));
+    let query = QueryBuilder::new("adults")
+        .with("adults", adults)
+        .r#where(FilterOperator::Single(Filter::eq("is_active", true)));
+
+    let rows = query.execute_model::<User>(&db).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Adult");
+    assert_eq!(query.execute_count(&db).await.unwrap(), 1);
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn data_round_trip_special_chars() {
+async fn query_builder_execute_count() {
     let db = setup_db().await.unwrap();
-    let special_name = "O'Reilly & Sons (R&D)";
-    let row = insert_and_get_real(
+    insert_and_get_real(&db, &user("QC1", "qc1@example.com", Some(25), None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("QC2", "qc2@example.com", Some(50), None, true))
+        .await
+        .unwrap();
+
+    let count = QueryBuilder::new("users")
+        .r#where(FilterOperator::Single(Filter::gt("age", 30i64)))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn find_by_sql_maps_rows_into_models() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("Sql1", "sql1@example.com", Some(20), None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(
         &db,
-        &user(special_name, "special@example.com", Some(10), None, true),
+        &user("Sql2", "sql2@example.com", Some(40), None, false),
     )
     .await
     .unwrap();
-    assert_eq!(row.name, special_name);
-}
 
-#[tokio::test(flavor = "current_thread")]
-async fn data_round_trip_unicode_text() {
-    let db = setup_db().await.unwrap();
-    let unicode_name = "\u{4f60}\u{597d} \u{4e16}\u{754c}";
-    let row = insert_and_get_real(
+    let rows = User::find_by_sql(
+        "SELECT * FROM users WHERE age > ? AND is_active = ?",
+        vec![10i64.into(), false.into()],
         &db,
-        &user(unicode_name, "unicode@example.com", Some(10), None, true),
     )
     .await
     .unwrap();
-    assert_eq!(row.name, unicode_name);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Sql2");
+    assert!(!rows[0].is_active);
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn migrations_init_execute_and_get_executed() {
-    let db = Database::new_local(":memory:").await.unwrap();
-    let manager = MigrationManager::new(db);
-    manager.init().await.unwrap();
-
-    let migration = MigrationBuilder::new("create_projects")
-        .up("CREATE TABLE projects (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL)")
-        .build();
-    manager.execute_migration(&migration).await.unwrap();
+async fn query_as_maps_raw_sql_into_from_row() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("Raw", "raw@example.com", None, None, true))
+        .await
+        .unwrap();
 
-    let mut rows = manager
-        .database()
-        .query(
-            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'projects'",
-            vec![],
+    let rows: Vec<NameAndEmail> = db
+        .query_as(
+            "SELECT name, email FROM users WHERE name = ?",
+            vec!["Raw".into()],
         )
         .await
         .unwrap();
-    assert!(rows.next().await.unwrap().is_some());
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].email, "raw@example.com");
 
-    let executed = manager.get_executed_migrations().await.unwrap();
-    assert_eq!(executed.len(), 1);
-    assert_eq!(executed[0].name, "create_projects");
+    let err = db
+        .query_as::<NameAndEmail>("SELECT name FROM users", vec![])
+        .await;
+    assert!(err.is_err());
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn edge_case_find_all_on_empty_table() {
-    let db = setup_db().await.unwrap();
-    let all = User::find_all(&db).await.unwrap();
-    assert!(all.is_empty());
-}
+async fn column_constants_work_in_filters_sorts_and_selects() {
+    assert_eq!(User::ID, "id");
+    assert_eq!(User::IS_ACTIVE, "is_active");
+    assert_eq!(
+        User::columns(),
+        vec![
+            User::ID,
+            User::NAME,
+            User::EMAIL,
+            User::AGE,
+            User::SCORE,
+            User::IS_ACTIVE
+        ]
+    );
 
-#[tokio::test(flavor = "current_thread")]
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Const1", "const1@example.com", Some(20), None, true),
+    )
+    .await
+    .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Const2", "const2@example.com", Some(40), None, true),
+    )
+    .await
+    .unwrap();
+
+    let rows = QueryBuilder::new(User::table_name())
+        .select([User::NAME, User::EMAIL])
+        .r#where(FilterOperator::Single(Filter::eq(User::IS_ACTIVE, true)))
+        .order_by(Sort::new(User::AGE, SortOrder::Desc))
+        .execute_as::<NameAndEmail>(&db)
+        .await
+        .unwrap();
+    let names: Vec<_> = rows.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["Const2", "Const1"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn expression_filters_execute() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Ann", "ANN@Example.com", Some(20), Some(1.5), true),
+    )
+    .await
+    .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Bartholomew", "bart@example.com", Some(40), None, true),
+    )
+    .await
+    .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Cy", "cy@example.com", Some(60), Some(9.0), false),
+    )
+    .await
+    .unwrap();
+
+    let rows = QueryBuilder::new("users")
+        .r#where(lower(col("email")).eq("ann@example.com"))
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "Ann");
+
+    let rows = QueryBuilder::new("users")
+        .r#where(
+            length(col("name"))
+                .gt(3i64)
+                .or(coalesce([col("score"), val(0.0)]).ge(5.0)),
+        )
+        .order_by(Sort::asc("name"))
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    let names: Vec<_> = rows.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["Bartholomew", "Cy"]);
+
+    let count = User::count_where(
+        (col("age") * 2i64)
+            .ge(80i64)
+            .and(col("is_active").eq(true))
+            .into(),
+        &db,
+    )
+    .await
+    .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn case_expressions_in_select_and_order() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Case1", "case1@example.com", Some(10), None, true),
+    )
+    .await
+    .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Case2", "case2@example.com", Some(70), None, true),
+    )
+    .await
+    .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Case3", "case3@example.com", Some(40), None, false),
+    )
+    .await
+    .unwrap();
+
+    let report = QueryBuilder::new("users")
+        .select(["count(*) AS total"])
+        .select_expr(
+            func(
+                "sum",
+                [case_when(col("is_active").eq(true), 1i64).otherwise(0i64)],
+            ),
+            "active",
+        )
+        .select_expr(
+            func(
+                "sum",
+                [case_when(col("is_active").eq(false), 1i64).otherwise(0i64)],
+            ),
+            "inactive",
+        )
+        .execute_as::<ActivityReport>(&db)
+        .await
+        .unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].total, 3);
+    assert_eq!(report[0].active, 2);
+    assert_eq!(report[0].inactive, 1);
+
+    let ordered = QueryBuilder::new("users")
+        .order_by_expr(
+            case_when(col("age").ge(60i64), 0i64)
+                .when(col("age").lt(18i64), 1i64)
+                .otherwise(2i64),
+            SortOrder::Asc,
+        )
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    let names: Vec<_> = ordered.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["Case2", "Case1", "Case3"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn search_filter_across_multiple_columns() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("Search Me", "sm@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Another", "needle@example.com", None, None, true),
+    )
+    .await
+    .unwrap();
+
+    let search = SearchFilter::new("needle", vec!["name", "email"]);
+    let result = User::search(&search, None, &db).await.unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.data[0].email, "needle@example.com");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn paginated_finders_honor_multi_column_sort() {
+    let db = setup_db().await.unwrap();
+    for (name, email, age) in [
+        ("bob", "b@example.com", Some(30)),
+        ("Alice", "a@example.com", None),
+        ("carol", "c@example.com", Some(30)),
+        ("Dave", "d@example.com", Some(40)),
+    ] {
+        insert_and_get_real(&db, &user(name, email, age, None, true))
+            .await
+            .unwrap();
+    }
+
+    let page = Pagination::new(1, 10).with_sort(vec![
+        Sort::desc("age").nulls_last(),
+        Sort::asc("name").collate("NOCASE"),
+    ]);
+    let result = User::find_paginated(&page, &db).await.unwrap();
+    let names: Vec<&str> = result.data.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, ["Dave", "bob", "carol", "Alice"]);
+
+    let search = SearchFilter::new("example", vec!["email"]);
+    let page =
+        Pagination::new(1, 2).with_sort(vec![Sort::asc("age").nulls_last(), Sort::desc("name")]);
+    let result = User::search(&search, Some(&page), &db).await.unwrap();
+    let names: Vec<&str> = result.data.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, ["carol", "bob"]);
+    assert_eq!(result.pagination.total, Some(4));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn finders_reject_unknown_sort_and_filter_columns() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("ada", "ada@example.com", None, None, true))
+        .await
+        .unwrap();
+
+    let sort: Sort =
+        serde_json::from_str(r#"{"column": "id;DROP TABLE users--", "order": "Asc"}"#).unwrap();
+    let err = User::list(Some(vec![sort.clone()]), None, &db)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+
+    let page = Pagination::new(1, 10).with_sort(vec![sort.clone()]);
+    let err = User::find_paginated(&page, &db).await.unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+    let err = QueryBuilder::new("users")
+        .execute_paginated::<User>(&db, &page)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+
+    let filter = FilterOperator::Single(Filter::eq("1=1 OR name", "x"));
+    let err = User::list_where(filter.clone(), None, None, &db)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+    let err = User::count_where(filter, &db).await.unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+
+    assert_eq!(User::count(&db).await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn search_ranked_falls_back_to_like_without_fts_table() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("ada", "ada@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("Grace", "grace@example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(
+        &db,
+        &user("Linus", "torvalds@linux-foundation.org", None, None, true),
+    )
+    .await
+    .unwrap();
+
+    let search = SearchFilter::new("a", vec!["name", "email"]).fts("users_fts");
+    let hits = User::search_ranked(&search, None, &db).await.unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[0].score, 2.0);
+    assert_eq!(hits[2].model.name, "Linus");
+    assert_eq!(hits[2].score, 1.0);
+
+    let search = SearchFilter::new("example", vec!["name", "email"]);
+    let page = Pagination::new(2, 1);
+    let hits = User::search_ranked(&search, Some(&page), &db)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].score, 1.0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn case_insensitive_filters_and_search() {
+    let db = setup_db().await.unwrap();
+    insert_and_get_real(&db, &user("Alice", "Alice@Example.com", None, None, true))
+        .await
+        .unwrap();
+    insert_and_get_real(&db, &user("bob", "bob@example.com", None, None, true))
+        .await
+        .unwrap();
+
+    let found = User::find_where(Filter::eq_nocase("email", "alice@example.com").into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Alice");
+
+    let found = User::find_where(Filter::ilike("name", "BO%").into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+
+    let exact = SearchFilter::new("ALICE", vec!["name"]).exact_match(true);
+    let result = User::search(&exact, None, &db).await.unwrap();
+    assert_eq!(result.data.len(), 1);
+
+    let sensitive = SearchFilter::new("alice", vec!["name"]).case_sensitive(true);
+    let result = User::search(&sensitive, None, &db).await.unwrap();
+    assert!(result.data.is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn in_list_filters_expand_large_lists() {
+    let db = setup_db().await.unwrap();
+    let mut ids = Vec::new();
+    for name in ["a", "b", "c"] {
+        let email = format!("{name}@example.com");
+        let row = insert_and_get_real(&db, &user(name, &email, None, None, true))
+            .await
+            .unwrap();
+        ids.push(row.id.unwrap());
+    }
+
+    let found = User::find_where(Filter::in_list("name", ["a", "c"]).into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+
+    let mut many: Vec<i64> = (10_000..12_000).collect();
+    many.push(ids[1]);
+    let found = User::find_where(Filter::in_list("id", many.clone()).into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "b");
+
+    let found = User::find_where(Filter::not_in("id", many).into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+
+    let none = User::find_where(Filter::in_list("id", Vec::<i64>::new()).into(), &db)
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+
+    // Three lists under the inline threshold still exceed the statement limit
+    let mut near: Vec<i64> = (20_000..20_400).collect();
+    near.push(ids[2]);
+    let filter = FilterOperator::And(vec![
+        Filter::in_list("id", near.clone()).into(),
+        Filter::not_in("id", (30_000..30_400).collect::<Vec<i64>>()).into(),
+        Filter::in_list("id", near).into(),
+    ]);
+    let found = User::find_where(filter, &db).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "c");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn in_list_filters_match_large_blob_lists() {
+    let db = setup_db().await.unwrap();
+    db.execute("CREATE TABLE files (hash BLOB NOT NULL)", vec![])
+        .await
+        .unwrap();
+    for hash in [vec![0u8, 1], vec![0xff], vec![]] {
+        db.execute(
+            "INSERT INTO files (hash) VALUES (?)",
+            vec![libsql_orm::compat::blob_value(hash)],
+        )
+        .await
+        .unwrap();
+    }
+
+    let mut hashes: Vec<libsql_orm::Value> = (0..1000u32)
+        .map(|i| libsql_orm::Value::Blob(i.to_be_bytes().to_vec()))
+        .collect();
+    hashes.push(libsql_orm::Value::Blob(vec![0xff]));
+    hashes.push(libsql_orm::Value::Blob(vec![]));
+    let count = QueryBuilder::new("files")
+        .r#where(Filter::in_list("hash", hashes))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn between_filters_date_ranges() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let db = setup_db().await.unwrap();
+    db.execute(
+        "CREATE TABLE events (id INTEGER PRIMARY KEY, happened_at TEXT NOT NULL)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    for day in 0..10 {
+        InsertBuilder::table("events")
+            .value("happened_at", start + Duration::days(day))
+            .execute(&db)
+            .await
+            .unwrap();
+    }
+
+    let inside = QueryBuilder::new("events")
+        .r#where(Filter::between(
+            "happened_at",
+            start + Duration::days(2),
+            start + Duration::days(4),
+        ))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(inside, 3);
+
+    let outside = QueryBuilder::new("events")
+        .r#where(Filter::not_between(
+            "happened_at",
+            start + Duration::days(2),
+            start + Duration::days(4),
+        ))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(outside, 7);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn json_filters_and_extraction() {
+    #[derive(FromRow, Debug, Deserialize)]
+    struct ProfileTheme {
+        handle: String,
+        theme: Option<String>,
+    }
+
+    let db = setup_db().await.unwrap();
+    db.execute(
+        "CREATE TABLE profiles (id INTEGER PRIMARY KEY, handle TEXT NOT NULL, prefs TEXT, tags TEXT)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    for (handle, prefs, tags) in [
+        (
+            "ada",
+            r#"{"theme":"dark","beta":true}"#,
+            r#"["rust","math"]"#,
+        ),
+        ("bob", r#"{"theme":"light","beta":false}"#, r#"["go"]"#),
+        ("cy", r#"{}"#, r#"[]"#),
+    ] {
+        InsertBuilder::table("profiles")
+            .value("handle", handle)
+            .value("prefs", prefs)
+            .value("tags", tags)
+            .execute(&db)
+            .await
+            .unwrap();
+    }
+
+    let dark = QueryBuilder::new("profiles")
+        .r#where(Filter::json_eq("prefs", "$.theme", "dark"))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(dark, 1);
+
+    let beta = QueryBuilder::new("profiles")
+        .r#where(Filter::json_eq("prefs", "$.beta", true))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(beta, 1);
+
+    let rustaceans = QueryBuilder::new("profiles")
+        .r#where(Filter::json_contains("profiles.tags", "rust"))
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(rustaceans, 1);
+
+    let themes = QueryBuilder::new("profiles")
+        .select(["handle"])
+        .select_json("prefs", "$.theme", "theme")
+        .order_by(Sort::asc("handle"))
+        .execute_as::<ProfileTheme>(&db)
+        .await
+        .unwrap();
+    assert_eq!(themes.len(), 3);
+    assert_eq!(themes[0].handle, "ada");
+    assert_eq!(themes[0].theme.as_deref(), Some("dark"));
+    assert_eq!(themes[2].theme, None);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn raw_filters_compose_with_the_dsl() {
+    let db = setup_db().await.unwrap();
+    for (name, email, age) in [
+        ("Al", "al@example.com", Some(40)),
+        ("Beatrice", "bea@example.com", Some(20)),
+        ("Cy", "cy@example.com", Some(20)),
+    ] {
+        insert_and_get_real(&db, &user(name, email, age, None, true))
+            .await
+            .unwrap();
+    }
+
+    let found = User::find_where(
+        FilterOperator::And(vec![
+            Filter::eq("age", 20i64).into(),
+            Filter::raw("length(name) > ?", [3i64]),
+        ]),
+        &db,
+    )
+    .await
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Beatrice");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn glob_filters_are_case_sensitive() {
+    let db = setup_db().await.unwrap();
+    for (name, email) in [("Readme", "r@example.com"), ("readme", "l@example.com")] {
+        insert_and_get_real(&db, &user(name, email, None, None, true))
+            .await
+            .unwrap();
+    }
+
+    let found = User::find_where(Filter::glob("name", "R*").into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Readme");
+
+    let found = User::find_where(Filter::glob("name", "READ*").into(), &db)
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+
+    let found = User::find_where(Filter::glob("name", "READ*").nocase().into(), &db)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn tuple_comparison_drives_keyset_pagination() {
+    let db = setup_db().await.unwrap();
+    for (i, age) in [30, 20, 30, 20, 40].into_iter().enumerate() {
+        let email = format!("k{i}@example.com");
+        insert_and_get_real(&db, &user("K", &email, Some(age), None, true))
+            .await
+            .unwrap();
+    }
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<(i64, i64)> = None;
+    loop {
+        let mut query = QueryBuilder::new("users")
+            .order_by(Sort::asc("age"))
+            .order_by(Sort::asc("id"))
+            .limit(2);
+        if let Some((age, id)) = cursor {
+            query = query.r#where(Filter::tuple_gt(("age", "id"), (age, id)));
+        }
+        let page = query.execute::<User>(&db).await.unwrap();
+        let Some(last) = page.last() else { break };
+        cursor = Some((last.age.unwrap(), last.id.unwrap()));
+        seen.extend(page.iter().map(|u| (u.age.unwrap(), u.id.unwrap())));
+    }
+
+    let mut expected = seen.clone();
+    expected.sort();
+    assert_eq!(seen.len(), 5);
+    assert_eq!(seen, expected);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_or_update_creates_when_no_pk() {
+    let db = setup_db().await.unwrap();
+    let u = user("COU", "cou@example.com", Some(44), None, true);
+    let _ = u.create_or_update(&db).await.unwrap();
+    assert_eq!(User::count(&db).await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_or_update_updates_when_pk_exists() {
+    let db = setup_db().await.unwrap();
+    let mut existing = insert_and_get_real(&db, &user("Old", "old@example.com", Some(20), None, true))
+        .await
+        .unwrap();
+    existing.name = "New".to_string();
+    existing.age = Some(21);
+
+    let _ = existing.create_or_update(&db).await.unwrap();
+    let fetched = User::find_by_id(existing.id.unwrap(), &db).await.unwrap().unwrap();
+    assert_eq!(fetched.name, "New");
+    assert_eq!(fetched.age, Some(21));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn data_round_trip_null_fields() {
+    let db = setup_db().await.unwrap();
+    let row = insert_and_get_real(
+        &db,
+        &user("Nulls", "nulls@example.com", None, None, true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(row.age, None);
+    assert_eq!(row.score, None);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn data_round_trip_float_values() {
+    let db = setup_db().await.unwrap();
+    let row = insert_and_get_real(
+        &db,
+        &user("Float", "float@example.com", Some(10), Some(42.125), true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(row.score, Some(42.125));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn data_round_trip_boolean_false() {
+    let db = setup_db().await.unwrap();
+    let row = insert_and_get_real(
+        &db,
+        &user("False", "false@example.com", Some(10), None, false),
+    )
+    .await
+    .unwrap();
+    assert!(!row.is_active);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn data_round_trip_special_chars() {
+    let db = setup_db().await.unwrap();
+    let special_name = "O'Reilly & Sons (R&D)";
+    let row = insert_and_get_real(
+        &db,
+        &user(special_name, "special@example.com", Some(10), None, true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(row.name, special_name);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn data_round_trip_unicode_text() {
+    let db = setup_db().await.unwrap();
+    let unicode_name = "\u{4f60}\u{597d} \u{4e16}\u{754c}";
+    let row = insert_and_get_real(
+        &db,
+        &user(unicode_name, "unicode@example.com", Some(10), None, true),
+    )
+    .await
+    .unwrap();
+    assert_eq!(row.name, unicode_name);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migrations_init_execute_and_get_executed() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migration = MigrationBuilder::new("create_projects")
+        .up("CREATE TABLE projects (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL)")
+        .build();
+    manager.execute_migration(&migration).await.unwrap();
+
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'projects'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_some());
+
+    let executed = manager.get_executed_migrations().await.unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].name, "create_projects");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rollback_migration_runs_down_sql() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migration = MigrationBuilder::new("create_projects")
+        .up("CREATE TABLE projects (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL)")
+        .down("DROP TABLE projects")
+        .build();
+    manager.execute_migration(&migration).await.unwrap();
+
+    let executed = manager.get_executed_migrations().await.unwrap();
+    assert_eq!(executed[0].down_sql.as_deref(), Some("DROP TABLE projects"));
+
+    manager.rollback_migration(&migration.id).await.unwrap();
+
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'projects'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_none());
+    assert!(manager.get_executed_migrations().await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rollback_migration_without_down_sql_is_rejected() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migration = MigrationBuilder::new("create_projects")
+        .up("CREATE TABLE projects (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL)")
+        .build();
+    manager.execute_migration(&migration).await.unwrap();
+
+    let result = manager.rollback_migration(&migration.id).await;
+    assert!(matches!(result, Err(libsql_orm::Error::Validation(_))));
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 1);
+
+    let missing = manager.rollback_migration("no-such-id").await;
+    assert!(matches!(missing, Err(libsql_orm::Error::NotFound(_))));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn init_adds_down_sql_column_to_existing_table() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute(
+        "CREATE TABLE migrations (id TEXT PRIMARY KEY, name TEXT NOT NULL, sql TEXT NOT NULL, created_at TEXT NOT NULL, executed_at TEXT)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+    manager.init().await.unwrap();
+
+    let migration = templates::create_table("projects", &[("id", "INTEGER PRIMARY KEY")]);
+    manager.execute_migration(&migration).await.unwrap();
+    manager.rollback_migration(&migration.id).await.unwrap();
+    assert!(manager.get_executed_migrations().await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rollback_steps_and_rollback_to_walk_history_in_reverse() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let tables = ["alpha", "beta", "gamma", "delta"];
+    for table in tables {
+        let migration = templates::create_table(table, &[("id", "INTEGER PRIMARY KEY")]);
+        manager.execute_migration(&migration).await.unwrap();
+    }
+
+    let reverted = manager.rollback(1).await.unwrap();
+    let names: Vec<&str> = reverted.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["create_table_delta"]);
+
+    let reverted = manager.rollback_to("create_table_alpha").await.unwrap();
+    let names: Vec<&str> = reverted.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["create_table_gamma", "create_table_beta"]);
+
+    let executed = manager.get_executed_migrations().await.unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].name, "create_table_alpha");
+
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name IN ('beta', 'gamma', 'delta')",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_none());
+
+    let missing = manager.rollback_to("create_table_delta").await;
+    assert!(matches!(missing, Err(libsql_orm::Error::NotFound(_))));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rollback_follows_versions_and_takes_the_lock() {
+    let path = std::env::temp_dir().join(format!(
+        "libsql-orm-rollback-{}.db",
+        libsql_orm::Uuid::new_v4()
+    ));
+    let path = path.to_str().unwrap().to_string();
+    let manager = MigrationManager::new(Database::new_local(&path).await.unwrap());
+    let other = MigrationManager::new(Database::new_local(&path).await.unwrap());
+    manager.init().await.unwrap();
+
+    // One batch shares its executed_at second, so only versions order it
+    let migrations: Vec<_> = [
+        "20240103000000_gamma",
+        "20240102000000_beta",
+        "20240101000000_alpha",
+    ]
+    .into_iter()
+    .map(|name| {
+        let table = name.rsplit('_').next().unwrap();
+        libsql_orm::Migration::embedded(
+            name,
+            &format!("CREATE TABLE {table} (id INTEGER PRIMARY KEY)"),
+            Some(&format!("DROP TABLE {table}")),
+        )
+    })
+    .collect();
+    manager.run_migrations(migrations).await.unwrap();
+
+    assert!(other.try_lock().await.unwrap());
+    let locked = manager.rollback(1).await;
+    assert!(matches!(locked, Err(libsql_orm::Error::Validation(_))));
+    let locked = manager.rollback_to("20240101000000_alpha").await;
+    assert!(matches!(locked, Err(libsql_orm::Error::Validation(_))));
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 3);
+    other.unlock().await.unwrap();
+
+    let reverted = manager.rollback(1).await.unwrap();
+    let names: Vec<&str> = reverted.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["20240103000000_gamma"]);
+    let reverted = manager.rollback_to("20240101000000").await.unwrap();
+    let names: Vec<&str> = reverted.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["20240102000000_beta"]);
+    assert!(other.try_lock().await.unwrap());
+    other.unlock().await.unwrap();
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rollback_steps_checks_down_sql_before_running() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let irreversible = MigrationBuilder::new("create_alpha")
+        .up("CREATE TABLE alpha (id INTEGER PRIMARY KEY)")
+        .build();
+    manager.execute_migration(&irreversible).await.unwrap();
+    let reversible = templates::create_table("beta", &[("id", "INTEGER PRIMARY KEY")]);
+    manager.execute_migration(&reversible).await.unwrap();
+
+    let result = manager.rollback(2).await;
+    assert!(matches!(result, Err(libsql_orm::Error::Validation(_))));
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn embedded_migrations_run_once() {
+    let migrations = libsql_orm::embed_migrations!("tests/migrations");
+    let names: Vec<&str> = migrations.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "20240101000000_create_projects",
+            "20240102000000_create_tasks",
+            "20240103000000_index_tasks",
+        ]
+    );
+    assert_eq!(
+        migrations[1].down_sql.as_deref(),
+        Some("DROP TABLE tasks\n")
+    );
+    assert!(migrations[2].down_sql.is_none());
+
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+    manager.run_migrations(migrations).await.unwrap();
+    manager
+        .run_migrations(libsql_orm::embed_migrations!("tests/migrations"))
+        .await
+        .unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 3);
+
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'index' AND name = 'idx_tasks_project_id'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_some());
+}
+
+#[test]
+fn load_migrations_dir_matches_embedded_migrations() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/migrations");
+    let loaded = MigrationManager::load_migrations_dir(dir).unwrap();
+    let embedded = libsql_orm::embed_migrations!("tests/migrations");
+    assert_eq!(loaded.len(), embedded.len());
+    for (loaded, embedded) in loaded.iter().zip(&embedded) {
+        assert_eq!(loaded.id, embedded.id);
+        assert_eq!(loaded.sql, embedded.sql);
+        assert_eq!(loaded.down_sql, embedded.down_sql);
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn verify_detects_changed_applied_migrations() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let original = libsql_orm::Migration::embedded(
+        "20240101_create_projects",
+        "CREATE TABLE projects (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    manager.execute_migration(&original).await.unwrap();
+    manager
+        .verify(std::slice::from_ref(&original))
+        .await
+        .unwrap();
+
+    let edited = libsql_orm::Migration::embedded(
+        "20240101_create_projects",
+        "CREATE TABLE projects (id INTEGER PRIMARY KEY, name TEXT)",
+        None,
+    );
+    let pending = libsql_orm::Migration::embedded(
+        "20240102_create_tasks",
+        "CREATE TABLE tasks (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    let result = manager.verify(&[edited, pending]).await;
+    match result {
+        Err(libsql_orm::Error::Validation(message)) => {
+            assert!(message.contains("20240101_create_projects"));
+            assert!(!message.contains("20240102_create_tasks"));
+        }
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn diff_models_creates_missing_tables() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migrations = libsql_orm::generate_migration_diff!(manager, User, Signup)
+        .await
+        .unwrap();
+    assert_eq!(migrations.len(), 2);
+    assert!(migrations[0]
+        .sql
+        .starts_with(r#"CREATE TABLE IF NOT EXISTS "users""#));
+    assert!(migrations[1].name.ends_with("_create_table_signups_02"));
+    // Diff versions are plain timestamps, one second apart
+    let versions: Vec<i64> = migrations.iter().map(|m| m.version.unwrap()).collect();
+    assert_eq!(versions[0].to_string().len(), 14);
+    assert!(versions[0] < versions[1]);
+    manager.run_migrations(migrations).await.unwrap();
+
+    let again = libsql_orm::generate_migration_diff!(manager, User, Signup)
+        .await
+        .unwrap();
+    assert!(again.is_empty());
+
+    // A migration written afterwards is not older than the diffs
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let later = MigrationBuilder::new(&MigrationManager::generate_migration_name("add_index"))
+        .up("CREATE INDEX users_by_name ON users (name)")
+        .build();
+    manager.run_migrations(vec![later]).await.unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 3);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn diff_models_adds_columns_in_place() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT, email TEXT, age INTEGER)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migrations = libsql_orm::generate_migration_diff!(manager, User)
+        .await
+        .unwrap();
+    assert_eq!(migrations.len(), 1);
+    assert_eq!(
+        migrations[0].sql,
+        "ALTER TABLE \"users\" ADD COLUMN \"score\" REAL;\nALTER TABLE \"users\" ADD COLUMN \"is_active\" INTEGER"
+    );
+    manager.run_migrations(migrations.clone()).await.unwrap();
+    assert!(libsql_orm::generate_migration_diff!(manager, User)
+        .await
+        .unwrap()
+        .is_empty());
+
+    manager.rollback_migration(&migrations[0].id).await.unwrap();
+    let columns: Vec<String> = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM pragma_table_info('users')", vec![])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(columns, vec!["id", "name", "email", "age"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn diff_models_rebuilds_tables_alter_cannot_change() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT, email TEXT, age TEXT, score REAL, is_active INTEGER, legacy TEXT)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    db.execute("CREATE INDEX idx_users_email ON users (email)", vec![])
+        .await
+        .unwrap();
+    db.execute("CREATE INDEX idx_users_legacy ON users (legacy)", vec![])
+        .await
+        .unwrap();
+    db.execute(
+        "INSERT INTO users (name, email, age, score, is_active, legacy) VALUES ('Ada', 'ada@example.com', '36', 1.5, 1, 'x')",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migrations = libsql_orm::generate_migration_diff!(manager, User)
+        .await
+        .unwrap();
+    assert_eq!(migrations.len(), 1);
+    assert!(migrations[0]
+        .sql
+        .contains(r#"CREATE TABLE "users__rebuild""#));
+    assert!(migrations[0].sql.contains("idx_users_email"));
+    assert!(!migrations[0].sql.contains("idx_users_legacy"));
+    manager.run_migrations(migrations.clone()).await.unwrap();
+
+    let users = User::find_all(manager.database()).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name, "Ada");
+    assert_eq!(users[0].age, Some(36));
+    assert!(libsql_orm::generate_migration_diff!(manager, User)
+        .await
+        .unwrap()
+        .is_empty());
+
+    manager.rollback_migration(&migrations[0].id).await.unwrap();
+    let columns: Vec<String> = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM pragma_table_info('users')", vec![])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert!(columns.contains(&"legacy".to_string()));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn plan_lists_pending_statements_without_running_them() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let applied = libsql_orm::Migration::embedded(
+        "20240101_create_projects",
+        "CREATE TABLE projects (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    manager.execute_migration(&applied).await.unwrap();
+    let pending = libsql_orm::Migration::embedded(
+        "20240102_create_tasks",
+        "CREATE TABLE tasks (id INTEGER PRIMARY KEY, note TEXT DEFAULT 'a;b');\nCREATE INDEX idx_tasks_note ON tasks (note);",
+        None,
+    );
+
+    let plan = manager.plan(&[applied, pending]).await.unwrap();
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].migration.name, "20240102_create_tasks");
+    assert_eq!(plan[0].statements.len(), 5);
+    assert_eq!(plan[0].statements[0], "BEGIN");
+    assert_eq!(
+        plan[0].statements[1],
+        "CREATE TABLE tasks (id INTEGER PRIMARY KEY, note TEXT DEFAULT 'a;b')"
+    );
+    assert_eq!(
+        plan[0].statements[2],
+        "CREATE INDEX idx_tasks_note ON tasks (note)"
+    );
+    assert!(plan[0].statements[3].starts_with("INSERT INTO migrations"));
+    assert_eq!(plan[0].statements[4], "COMMIT");
+
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'tasks'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_none());
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migration_lock_lets_one_runner_apply_migrations() {
+    let path =
+        std::env::temp_dir().join(format!("libsql-orm-lock-{}.db", libsql_orm::Uuid::new_v4()));
+    let path = path.to_str().unwrap().to_string();
+    let first = MigrationManager::new(Database::new_local(&path).await.unwrap());
+    let second = MigrationManager::new(Database::new_local(&path).await.unwrap());
+    first.init().await.unwrap();
+    second.init().await.unwrap();
+
+    assert!(first.try_lock().await.unwrap());
+    assert!(first.try_lock().await.unwrap());
+    assert!(!second.try_lock().await.unwrap());
+
+    let migration = libsql_orm::Migration::embedded(
+        "20240101_create_projects",
+        "CREATE TABLE projects (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    second
+        .run_migrations(vec![migration.clone()])
+        .await
+        .unwrap();
+    assert!(second.get_executed_migrations().await.unwrap().is_empty());
+    let err = second.execute_migration(&migration).await.unwrap_err();
+    assert!(matches!(err, libsql_orm::Error::Validation(_)), "{err}");
+    assert!(second.get_executed_migrations().await.unwrap().is_empty());
+
+    first.unlock().await.unwrap();
+    second.run_migrations(vec![migration]).await.unwrap();
+    assert_eq!(second.get_executed_migrations().await.unwrap().len(), 1);
+    assert!(first.try_lock().await.unwrap());
+    first.unlock().await.unwrap();
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn stale_migration_locks_expire() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db).with_lock_timeout(std::time::Duration::from_secs(60));
+    manager.init().await.unwrap();
+    manager
+        .database()
+        .execute(
+            "INSERT INTO migrations_lock (id, holder, acquired_at) VALUES (1, 'crashed', '2000-01-01T00:00:00+00:00')",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+    assert!(manager.try_lock().await.unwrap());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migration_lock_is_refreshed_while_migrating() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db)
+        .with_lock_timeout(std::time::Duration::from_secs(100 * 365 * 24 * 60 * 60));
+    manager.init().await.unwrap();
+    assert!(manager.try_lock().await.unwrap());
+    manager
+        .database()
+        .execute(
+            "UPDATE migrations_lock SET acquired_at = '2000-01-01T00:00:00+00:00'",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+    let migration = MigrationBuilder::new("copy_lock")
+        .up("CREATE TABLE lock_seen (name TEXT);
+             INSERT INTO lock_seen SELECT acquired_at FROM migrations_lock;")
+        .build();
+    manager.execute_migration(&migration).await.unwrap();
+    let seen = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM lock_seen", vec![])
+        .await
+        .unwrap();
+    assert_eq!(seen.len(), 1);
+    assert!(!seen[0].name.starts_with("2000-"), "{}", seen[0].name);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn run_migrations_orders_by_version_and_rejects_stale_ones() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let tasks = MigrationBuilder::new("002_create_tasks")
+        .up("CREATE TABLE tasks (id INTEGER PRIMARY KEY, project_id INTEGER REFERENCES projects (id))")
+        .build();
+    let projects = MigrationBuilder::new("001_create_projects")
+        .up("CREATE TABLE projects (id INTEGER PRIMARY KEY)")
+        .build();
+    let labels = MigrationBuilder::new("create_labels")
+        .up("CREATE TABLE labels (id INTEGER PRIMARY KEY)")
+        .version(5)
+        .build();
+    manager
+        .run_migrations(vec![labels, tasks, projects])
+        .await
+        .unwrap();
+
+    let executed = manager.get_executed_migrations().await.unwrap();
+    let order: Vec<(Option<i64>, &str)> = executed
+        .iter()
+        .map(|m| (m.version, m.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (Some(1), "001_create_projects"),
+            (Some(2), "002_create_tasks"),
+            (Some(5), "create_labels"),
+        ]
+    );
+
+    let stale = libsql_orm::Migration::embedded(
+        "003_add_index",
+        "CREATE INDEX idx_tasks_project_id ON tasks (project_id)",
+        None,
+    );
+    let result = manager.run_migrations(vec![stale.clone()]).await;
+    assert!(matches!(result, Err(libsql_orm::Error::Validation(_))));
+
+    let duplicate = libsql_orm::Migration::embedded(
+        "006_other",
+        "CREATE TABLE other (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    let clash = MigrationBuilder::new("also_six")
+        .up("CREATE TABLE clash (id INTEGER PRIMARY KEY)")
+        .version(6)
+        .build();
+    let result = manager.run_migrations(vec![duplicate, clash]).await;
+    assert!(matches!(result, Err(libsql_orm::Error::Validation(_))));
+
+    let manager = manager.allow_out_of_order(true);
+    manager.run_migrations(vec![stale]).await.unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 4);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn baseline_marks_existing_schema_as_applied() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute("CREATE TABLE projects (id INTEGER PRIMARY KEY)", vec![])
+        .await
+        .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migrations = libsql_orm::embed_migrations!("tests/migrations");
+    let marked = manager.baseline(&migrations, 20240101000000).await.unwrap();
+    let names: Vec<&str> = marked.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["20240101000000_create_projects"]);
+    assert!(manager
+        .baseline(&migrations, 20240101000000)
+        .await
+        .unwrap()
+        .is_empty());
+
+    manager.run_migrations(migrations.clone()).await.unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 3);
+    manager.verify(&migrations).await.unwrap();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn dump_schema_round_trips_into_a_fresh_database() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+    manager
+        .run_migrations(libsql_orm::embed_migrations!("tests/migrations"))
+        .await
+        .unwrap();
+
+    let full = manager.database().dump_schema().await.unwrap();
+    assert!(full.contains("migrations_lock"));
+
+    let schema = manager.dump_schema().await.unwrap();
+    assert!(!schema.contains("migrations"));
+    let statements: Vec<&str> = schema.lines().collect();
+    assert_eq!(statements.len(), 3);
+    assert!(statements[0].starts_with("CREATE TABLE projects"));
+    assert!(statements[1].starts_with("CREATE TABLE tasks"));
+    assert!(statements[2].starts_with("CREATE INDEX idx_tasks_project_id"));
+
+    let fresh = Database::new_local(":memory:").await.unwrap();
+    fresh.execute_batch(&schema).await.unwrap();
+    assert_eq!(fresh.dump_schema().await.unwrap(), schema);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rename_templates_apply_and_roll_back() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let create = templates::create_table(
+        "projects",
+        &[("id", "INTEGER PRIMARY KEY"), ("title", "TEXT")],
+    );
+    let rename_column = templates::rename_column("projects", "title", "name");
+    let rename_table = templates::rename_table("projects", "boards");
+    for migration in [&create, &rename_column, &rename_table] {
+        manager.execute_migration(migration).await.unwrap();
+    }
+
+    let columns: Vec<String> = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM pragma_table_info('boards')", vec![])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(columns, vec!["id", "name"]);
+
+    manager.rollback(2).await.unwrap();
+    let columns: Vec<String> = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM pragma_table_info('projects')", vec![])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(columns, vec!["id", "title"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rebuild_table_template_keeps_data_and_indexes() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute_batch(
+        "CREATE TABLE teams (id INTEGER PRIMARY KEY, name TEXT);
+         CREATE TABLE members (id INTEGER PRIMARY KEY, email TEXT, team_id INTEGER, nickname TEXT);
+         CREATE INDEX idx_members_email ON members (email);
+         INSERT INTO teams (id, name) VALUES (1, 'core');
+         INSERT INTO members (email, team_id, nickname) VALUES ('ada@example.com', 1, 'ada');",
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migration = templates::rebuild_table(
+        "members",
+        &[
+            ("id", "INTEGER PRIMARY KEY"),
+            ("email", "TEXT NOT NULL"),
+            ("team_id", "INTEGER"),
+        ],
+        &["FOREIGN KEY (team_id) REFERENCES teams (id)"],
+        &["id", "email", "team_id"],
+        &["CREATE UNIQUE INDEX idx_members_email ON members (email)"],
+    );
+    manager.execute_migration(&migration).await.unwrap();
+
+    let schema = manager.dump_schema().await.unwrap();
+    assert!(schema.contains("CREATE UNIQUE INDEX idx_members_email ON members (email)"));
+    assert!(schema.contains("FOREIGN KEY (team_id) REFERENCES teams (id)"));
+    assert!(!schema.contains("nickname"));
+    assert!(!schema.contains("__rebuild"));
+
+    let rows = manager
+        .database()
+        .query_as::<ColumnName>("SELECT email AS name FROM members", vec![])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, "ada@example.com");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_foreign_key_template_rebuilds_with_constraint() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+         CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER NOT NULL, title TEXT);
+         INSERT INTO users (id, name) VALUES (1, 'Ada');
+         INSERT INTO posts (user_id, title) VALUES (1, 'Hello');",
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let columns = [
+        ("id", "INTEGER PRIMARY KEY"),
+        ("user_id", "INTEGER NOT NULL"),
+        ("title", "TEXT"),
+    ];
+    let migration = templates::add_foreign_key(
+        "posts",
+        &columns,
+        "user_id",
+        "users",
+        "id",
+        &["CREATE INDEX idx_posts_user_id ON posts (user_id)"],
+    );
+    manager.execute_migration(&migration).await.unwrap();
+    let schema = manager.dump_schema().await.unwrap();
+    assert!(schema.contains(r#"FOREIGN KEY ("user_id") REFERENCES "users" ("id")"#));
+    assert!(schema.contains("idx_posts_user_id"));
+
+    manager.rollback_migration(&migration.id).await.unwrap();
+    let schema = manager.dump_schema().await.unwrap();
+    assert!(!schema.contains("FOREIGN KEY"));
+    let titles = manager
+        .database()
+        .query_as::<ColumnName>("SELECT title AS name FROM posts", vec![])
+        .await
+        .unwrap();
+    assert_eq!(titles[0].name, "Hello");
+}
+
+/// Whether inserting an orphaned member fails
+async fn foreign_keys_enforced(db: &Database) -> bool {
+    db.execute("INSERT INTO members (team_id) VALUES (999)", vec![])
+        .await
+        .is_err()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rebuilding_a_referenced_table_suspends_foreign_keys() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE teams (id INTEGER PRIMARY KEY, name TEXT);
+         CREATE TABLE members (id INTEGER PRIMARY KEY, team_id INTEGER REFERENCES teams (id));
+         INSERT INTO teams (id, name) VALUES (1, 'core');
+         INSERT INTO members (team_id) VALUES (1);",
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+    let rebuild = |name: &str, copied: &[&str]| {
+        let mut migration = templates::rebuild_table(
+            "teams",
+            &[
+                ("id", "INTEGER PRIMARY KEY"),
+                ("name", "TEXT NOT NULL DEFAULT ''"),
+            ],
+            &[],
+            copied,
+            &[],
+        );
+        migration.id = name.to_string();
+        migration.name = name.to_string();
+        migration
+    };
+    manager
+        .execute_migration(&rebuild("rebuild_teams", &["id", "name"]))
+        .await
+        .unwrap();
+    assert!(foreign_keys_enforced(manager.database()).await);
+
+    // Not copying the ids leaves members pointing at a missing team
+    manager
+        .database()
+        .execute("INSERT INTO teams (id, name) VALUES (5, 'five')", vec![])
+        .await
+        .unwrap();
+    manager
+        .database()
+        .execute("UPDATE members SET team_id = 5", vec![])
+        .await
+        .unwrap();
+    let err = manager
+        .execute_migration(&rebuild("orphan_members", &["name"]))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, libsql_orm::Error::Validation(message) if message.contains("members -> teams")),
+        "{err:?}"
+    );
+    assert!(foreign_keys_enforced(manager.database()).await);
+    let teams = manager
+        .database()
+        .query_as::<ColumnName>("SELECT name FROM teams ORDER BY id", vec![])
+        .await
+        .unwrap();
+    assert_eq!(teams.len(), 2);
+    assert!(!manager.dump_schema().await.unwrap().contains("__rebuild"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn failed_migration_commits_roll_back() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    db.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE teams (id INTEGER PRIMARY KEY);
+         CREATE TABLE members (id INTEGER PRIMARY KEY,
+             team_id INTEGER REFERENCES teams (id) DEFERRABLE INITIALLY DEFERRED);",
+    )
+    .await
+    .unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let orphan = MigrationBuilder::new("add_orphan_member")
+        .up("INSERT INTO members (team_id) VALUES (5)")
+        .build();
+    let err = manager.execute_migration(&orphan).await.unwrap_err();
+    assert!(err.to_string().contains("add_orphan_member"), "{err}");
+    assert!(!manager.database().in_transaction());
+    assert!(manager.get_executed_migrations().await.unwrap().is_empty());
+    let members = manager
+        .database()
+        .query_as::<ColumnName>("SELECT 'x' AS name FROM members", vec![])
+        .await
+        .unwrap();
+    assert!(members.is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn run_migrations_is_all_or_nothing() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let migrations = vec![
+        libsql_orm::Migration::embedded(
+            "001_create_projects",
+            "CREATE TABLE projects (id INTEGER PRIMARY KEY)",
+            None,
+        ),
+        libsql_orm::Migration::embedded(
+            "002_seed_projects",
+            "INSERT INTO projects (id) VALUES (1)",
+            None,
+        ),
+        libsql_orm::Migration::embedded(
+            "003_broken",
+            "INSERT INTO missing_table (id) VALUES (1)",
+            None,
+        ),
+    ];
+
+    let result = manager.run_migrations(migrations).await;
+    match result {
+        Err(libsql_orm::Error::DatabaseError(message)) => {
+            assert!(message.contains("003_broken"), "{message}");
+        }
+        other => panic!("expected the failing migration to be reported, got {other:?}"),
+    }
+
+    assert!(manager.get_executed_migrations().await.unwrap().is_empty());
+    let mut rows = manager
+        .database()
+        .query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'projects'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(rows.next().await.unwrap().is_none());
+
+    // The lock was released and the database is usable again
+    manager
+        .run_migrations(vec![libsql_orm::Migration::embedded(
+            "001_create_projects",
+            "CREATE TABLE projects (id INTEGER PRIMARY KEY)",
+            None,
+        )])
+        .await
+        .unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migrations_are_keyed_by_name_across_startups() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let startup = || {
+        vec![
+            MigrationBuilder::new("001_create_projects")
+                .up("CREATE TABLE projects (id INTEGER PRIMARY KEY)")
+                .down("DROP TABLE projects")
+                .build(),
+            libsql_orm::generate_migration!(User),
+        ]
+    };
+    assert_eq!(startup()[0].id, "001_create_projects");
+
+    manager.run_migrations(startup()).await.unwrap();
+    manager.run_migrations(startup()).await.unwrap();
+    manager.execute_migration(&startup()[0]).await.unwrap();
+    assert_eq!(manager.get_executed_migrations().await.unwrap().len(), 2);
+
+    // Rows recorded with random ids by earlier versions match by name
+    manager
+        .database()
+        .execute(
+            "INSERT INTO migrations (id, name, sql, created_at, executed_at) \
+             VALUES ('6f1c2f8e-legacy', '002_create_tasks', 'CREATE TABLE tasks (id INTEGER PRIMARY KEY)', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00')",
+            vec![],
+        )
+        .await
+        .unwrap();
+    let tasks = MigrationBuilder::new("002_create_tasks")
+        .up("CREATE TABLE tasks (id INTEGER PRIMARY KEY)")
+        .build();
+    assert!(manager.plan(&[tasks]).await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn status_reports_applied_pending_and_unknown() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let manager = MigrationManager::new(db);
+    manager.init().await.unwrap();
+
+    let from_other_branch = libsql_orm::Migration::embedded(
+        "20231231000000_experiment",
+        "CREATE TABLE experiment (id INTEGER PRIMARY KEY)",
+        None,
+    );
+    manager.execute_migration(&from_other_branch).await.unwrap();
+
+    let registered = libsql_orm::embed_migrations!("tests/migrations");
+    manager.execute_migration(&registered[0]).await.unwrap();
+
+    let status = manager.status(&registered).await.unwrap();
+    let names = |migrations: &[libsql_orm::Migration]| {
+        migrations
+            .iter()
+            .map(|m| m.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(&status.applied),
+        vec!["20240101000000_create_projects"]
+    );
+    assert_eq!(
+        names(&status.pending),
+        vec!["20240102000000_create_tasks", "20240103000000_index_tasks"]
+    );
+    assert_eq!(names(&status.unknown), vec!["20231231000000_experiment"]);
+    assert!(!status.is_up_to_date());
+    assert!(status.applied[0].executed_at.is_some());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn failed_statements_carry_sql_and_redacted_params() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let alice = user("alice", "alice@example.com", Some(30), None, true);
+
+    let err = alice.create(&db).await.unwrap_err();
+    let context = err.context().expect("statement context");
+    assert_eq!(context.table.as_deref(), Some("users"));
+    assert!(context.sql.starts_with(r#"INSERT INTO "users""#));
+    assert!(context.params.contains(&"text(5)".to_string()));
+    assert!(context.params.contains(&"integer".to_string()));
+    let message = err.to_string();
+    assert!(message.contains("[table: users]"), "{message}");
+    assert!(!message.contains("alice"), "{message}");
+    assert!(std::error::Error::source(&err).is_some());
+
+    db.include_param_values_in_errors(true);
+    let err = QueryBuilder::new("users")
+        .r#where(FilterOperator::Single(Filter::eq("name", "alice")))
+        .execute_as::<NameAndEmail>(&db)
+        .await
+        .unwrap_err();
+    let context = err.context().expect("statement context");
+    assert_eq!(context.table.as_deref(), Some("users"));
+    assert_eq!(context.params, vec!["\"alice\"".to_string()]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn validation_rules_run_before_any_sql() {
+    // No signups table: reaching the database would fail with a statement error
+    let db = Database::new_local(":memory:").await.unwrap();
+    let signup = Signup {
+        id: Some(1),
+        username: "a ".to_string(),
+        email: "not-an-email".to_string(),
+        age: Some(7),
+    };
+
+    for result in [
+        signup.create(&db).await,
+        signup.update(&db).await,
+        signup.upsert(&["email"], &db).await,
+    ] {
+        let Err(libsql_orm::Error::InvalidFields(errors)) = result else {
+            panic!("expected validation errors, got {result:?}");
+        };
+        let codes = |field| {
+            errors
+                .get(field)
+                .unwrap()
+                .iter()
+                .map(|e| e.code.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("username"), vec!["length", "custom"]);
+        assert_eq!(codes("email"), vec!["email"]);
+        assert_eq!(
+            errors.get("age").unwrap()[0].message,
+            "must be between 13 and 120"
+        );
+        assert!(errors.get("id").is_none());
+    }
+
+    db.execute(&Signup::migration_sql(), vec![]).await.unwrap();
+    let valid = Signup {
+        id: None,
+        username: "alice".to_string(),
+        email: "alice@example.com".to_string(),
+        age: None,
+    };
+    assert!(valid.validate().is_ok());
+    valid.create(&db).await.unwrap();
+    let invalid = Signup {
+        email: "bob@".to_string(),
+        ..valid.clone()
+    };
+    assert!(Signup::bulk_create(&[valid, invalid], &db).await.is_err());
+    assert_eq!(Signup::count(&db).await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn subscribers_receive_committed_model_changes() {
+    let db = setup_db().await.unwrap();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let subscription = db.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+    let alice = insert_and_get_real(&db, &user("Alice", "alice@example.com", None, None, true))
+        .await
+        .unwrap();
+    let renamed = User {
+        name: "Alicia".to_string(),
+        ..alice.clone()
+    };
+    renamed.update(&db).await.unwrap();
+    // Without a primary key the update fails before any SQL runs
+    let keyless = User {
+        id: None,
+        ..renamed.clone()
+    };
+    assert!(keyless.update(&db).await.is_err());
+    renamed.delete(&db).await.unwrap();
+    User::delete_where(
+        FilterOperator::Single(Filter::eq("email", "nobody@example.com")),
+        &db,
+    )
+    .await
+    .unwrap();
+
+    let seen = events.lock().unwrap().clone();
+    let operations: Vec<_> = seen.iter().map(|e| e.operation).collect();
+    assert_eq!(
+        operations,
+        vec![
+            libsql_orm::ChangeOperation::Insert,
+            libsql_orm::ChangeOperation::Update,
+            libsql_orm::ChangeOperation::Delete,
+        ]
+    );
+    assert!(seen.iter().all(|e| e.table == "users"));
+    assert_eq!(seen[0].primary_key, alice.id);
+    assert_eq!(
+        seen[0].new.as_ref().unwrap()["id"],
+        libsql_orm::Value::Integer(alice.id.unwrap())
+    );
+    assert_eq!(
+        seen[1].new.as_ref().unwrap()["name"],
+        libsql_orm::Value::Text("Alicia".to_string())
+    );
+    assert_eq!(seen[2].primary_key, alice.id);
+    assert!(seen[2].old.is_some() && seen[2].new.is_none());
+
+    assert!(db.unsubscribe(subscription));
+    assert!(!db.unsubscribe(subscription));
+    User::bulk_delete(&[alice.id.unwrap()], &db).await.unwrap();
+    assert_eq!(events.lock().unwrap().len(), 3);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_database_rolls_back_each_block() {
+    let db = TestDatabase::new::<(User, Signup)>().await.unwrap();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(0));
+    let sink = events.clone();
+    db.subscribe(move |_| *sink.lock().unwrap() += 1);
+
+    let counts = db
+        .run_in_rollback(|db| async move {
+            user("Alice", "alice@example.com", None, None, true)
+                .create(db)
+                .await?;
+            Signup {
+                id: None,
+                username: "alice".to_string(),
+                email: "alice@example.com".to_string(),
+                age: None,
+            }
+            .create(db)
+            .await?;
+            // Bulk methods join the open transaction
+            User::bulk_create(&[user("Ann", "ann@example.com", None, None, true)], db).await?;
+            Ok::<_, libsql_orm::Error>((User::count(db).await?, Signup::count(db).await?))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(counts, (2, 1));
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+    assert_eq!(Signup::count(&db).await.unwrap(), 0);
+    assert_eq!(*events.lock().unwrap(), 0);
+
+    let failed = db
+        .run_in_rollback(|db| async move {
+            user("Bob", "bob@example.com", None, None, true)
+                .create(db)
+                .await?;
+            db.execute("INSERT INTO missing VALUES (1)", vec![]).await?;
+            Ok::<_, libsql_orm::Error>(())
+        })
+        .await
+        .unwrap();
+    assert!(failed.is_err());
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+    assert_eq!(*events.lock().unwrap(), 0);
+
+    user("Cy", "cy@example.com", None, None, true)
+        .create(&*db)
+        .await
+        .unwrap();
+    assert_eq!(*events.lock().unwrap(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn mock_database_records_statements_and_returns_scripted_rows() {
+    let db = MockDatabase::new();
+    db.push_rows(vec![libsql_orm::Row::from([
+        ("id".to_string(), libsql_orm::Value::Integer(7)),
+        (
+            "name".to_string(),
+            libsql_orm::Value::Text("Alice".to_string()),
+        ),
+        (
+            "email".to_string(),
+            libsql_orm::Value::Text("alice@example.com".to_string()),
+        ),
+        ("age".to_string(), libsql_orm::Value::Null),
+        ("score".to_string(), libsql_orm::Value::Null),
+        ("is_active".to_string(), libsql_orm::Value::Integer(1)),
+    ])])
+    .push_rows(vec![libsql_orm::Row::from([(
+        "COUNT(*)".to_string(),
+        libsql_orm::Value::Integer(42),
+    )])]);
+
+    let found = User::find_by_id(7, &db).await.unwrap().unwrap();
+    assert_eq!(found.name, "Alice");
+    assert!(found.is_active);
+    assert_eq!(User::count(&db).await.unwrap(), 42);
+    assert_eq!(User::find_all(&db).await.unwrap(), vec![]);
+
+    let statements = db.statements();
+    assert_eq!(statements[0].sql, r#"SELECT * FROM "users" WHERE "id" = ?"#);
+    assert_eq!(statements[0].params, vec![libsql_orm::Value::Integer(7)]);
+    assert_eq!(statements[0].table.as_deref(), Some("users"));
+
+    db.clear();
+    let bob = user("Bob", "bob@example.com", None, None, false);
+    User::bulk_create(std::slice::from_ref(&bob), &db)
+        .await
+        .unwrap();
+    let sql = db.sql();
+    assert_eq!(sql.len(), 3);
+    assert_eq!(sql[0], "BEGIN");
+    assert!(sql[1].starts_with(r#"INSERT INTO "users""#));
+    assert_eq!(sql[2], "COMMIT");
+    assert_eq!(db.events().len(), 1);
+
+    // create reads the new key back through RETURNING
+    db.clear();
+    db.push_rows(vec![libsql_orm::Row::from([(
+        "id".to_string(),
+        libsql_orm::Value::Integer(9),
+    )])]);
+    let created = bob.create(&db).await.unwrap();
+    assert_eq!(created.id, Some(9));
+    assert!(db.sql()[0].ends_with(r#"RETURNING "id""#));
+    assert_eq!(db.events()[0].primary_key, Some(9));
+
+    db.push_query_error(libsql_orm::Error::Busy("database is locked".to_string()));
+    let err = bob.create(&db).await.unwrap_err();
+    assert!(err.is_busy());
+
+    db.clear();
+    let none = QueryBuilder::new("users")
+        .r#where(Filter::eq("is_active", true))
+        .execute::<User>(&db)
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+    assert_eq!(
+        db.sql(),
+        vec![r#"SELECT * FROM "users" WHERE "is_active" = ?"#.to_string()]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn factories_build_unique_defaults_with_overrides() {
+    let db = setup_db().await.unwrap();
+
+    let built = UserFactory::new().build();
+    assert_eq!(built.id, None);
+    assert!(built.name.starts_with("name-"));
+    assert!(built.email.ends_with("@example.com"));
+    assert_eq!(built.age, None);
+    assert!(built.is_active);
+
+    let alice = UserFactory::new()
+        .with(|u| u.name = "Alice".to_string())
+        .create(&db)
+        .await
+        .unwrap();
+    assert_eq!(alice.name, "Alice");
+    assert!(alice.id.is_some());
+
+    let adults = UserFactory::new()
+        .with(|u| u.age = Some(30))
+        .with_sequence(|u, n| u.score = Some(n as f64))
+        .create_many(50, &db)
+        .await
+        .unwrap();
+    assert_eq!(adults.len(), 50);
+    assert!(adults.iter().all(|u| u.age == Some(30)));
+
+    let mut emails: Vec<_> = adults.iter().map(|u| u.email.clone()).collect();
+    emails.push(alice.email.clone());
+    emails.sort();
+    emails.dedup();
+    assert_eq!(emails.len(), 51);
+    assert_eq!(User::count(&db).await.unwrap(), 51);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn fixtures_insert_referenced_rows_first() {
+    let db = setup_db().await.unwrap();
+    create_posts_table(&db).await.unwrap();
+
+    let path =
+        std::env::temp_dir().join(format!("libsql-orm-fixtures-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"{
+            "posts": {
+                "welcome": {"user_id": "$users.alice", "title": "Welcome", "published": 1},
+                "price": {"user_id": "$users.bob", "title": "$$5 off", "published": 0}
+            },
+            "users": {
+                "alice": {"name": "Alice", "email": "alice@example.com", "is_active": true},
+                "bob": {"name": "Bob", "email": "bob@example.com", "age": 41, "is_active": false}
+            }
+        }"#,
+    )
+    .unwrap();
+    let loaded = libsql_orm::fixtures::Fixtures::new()
+        .register::<User>()
+        .load(&db, &path)
+        .await;
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.len(), 4);
+
+    let alice_id = loaded.id("users", "alice").unwrap();
+    let bob = User::find_by_id(loaded.id("users", "bob").unwrap(), &db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(bob.age, Some(41));
+    assert!(!bob.is_active);
+
+    let rows = libsql_orm::Executor::query_statement(
+        &*db,
+        None,
+        "SELECT user_id, title FROM posts WHERE id = ?",
+        vec![loaded.id("posts", "welcome").unwrap().into()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        rows[0].get("user_id"),
+        Some(&libsql_orm::Value::Integer(alice_id))
+    );
+    let price = libsql_orm::Executor::query_statement(
+        &*db,
+        None,
+        "SELECT title FROM posts WHERE id = ?",
+        vec![loaded.id("posts", "price").unwrap().into()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        price[0].get("title"),
+        Some(&libsql_orm::Value::Text("$5 off".to_string()))
+    );
+
+    let err = libsql_orm::fixtures::Fixtures::new()
+        .load_str(
+            &db,
+            r#"{"posts": {"a": {"user_id": "$users.nobody", "title": "x", "published": 0}}}"#,
+            libsql_orm::fixtures::FixtureFormat::Json,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown fixture users.nobody"));
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test(flavor = "current_thread")]
+async fn yaml_fixtures_load_and_reject_cycles() {
+    let db = setup_db().await.unwrap();
+    let fixtures = libsql_orm::fixtures::Fixtures::new().register::<User>();
+
+    let loaded = fixtures
+        .load_str(
+            &db,
+            "users:\n  carol:\n    name: Carol\n    email: carol@example.com\n    is_active: true\n",
+            libsql_orm::fixtures::FixtureFormat::Yaml,
+        )
+        .await
+        .unwrap();
+    let carol = User::find_by_id(loaded.id("users", "carol").unwrap(), &db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(carol.name, "Carol");
+
+    let err = fixtures
+        .load_str(
+            &db,
+            "users:\n  a:\n    name: $users.b\n  b:\n    name: $users.a\n",
+            libsql_orm::fixtures::FixtureFormat::Yaml,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cycle: users.a, users.b"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn generated_sql_matches_snapshots_and_uses_indexes() {
+    let db = setup_db().await.unwrap();
+    db.execute("CREATE INDEX idx_users_email ON users (email)", vec![])
+        .await
+        .unwrap();
+
+    let (sql, params) = QueryBuilder::new("users")
+        .select(["id", "name"])
+        .r#where(Filter::eq("email", "alice@example.com"))
+        .to_sql()
+        .unwrap();
+    libsql_orm::assert_sql_eq!(
+        sql,
+        r#"SELECT "id", "name"
+         FROM "users"
+         WHERE "email" = ?"#
+    );
+    libsql_orm::assert_sql_snapshot!("users_by_email", sql);
+    assert!(libsql_orm::testing::full_table_scans(&db, &sql, params)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let (sql, params) = QueryBuilder::new("users")
+        .r#where(Filter::eq("name", "Alice"))
+        .to_sql()
+        .unwrap();
+    assert_eq!(
+        libsql_orm::testing::full_table_scans(&db, &sql, params)
+            .await
+            .unwrap(),
+        vec!["SCAN users".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn model_methods_run_inside_transactions() {
+    let db = setup_db().await.unwrap();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = events.clone();
+    db.subscribe(move |event| sink.lock().unwrap().push(event.operation));
+
+    let tx = db.begin().await.unwrap();
+    assert!(db.in_transaction());
+    user("Alice", "alice@example.com", None, None, true)
+        .create(&tx)
+        .await
+        .unwrap();
+    User::bulk_create(
+        &[
+            user("Bob", "bob@example.com", None, None, true),
+            user("Carol", "carol@example.com", None, None, false),
+        ],
+        &tx,
+    )
+    .await
+    .unwrap();
+    assert_eq!(User::count(&tx).await.unwrap(), 3);
+    let uncommitted = QueryBuilder::new("users")
+        .execute::<User>(&tx)
+        .await
+        .unwrap();
+    assert_eq!(uncommitted.len(), 3);
+    assert!(events.lock().unwrap().is_empty());
+    tx.rollback().await.unwrap();
+    assert!(!db.in_transaction());
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+    assert!(events.lock().unwrap().is_empty());
+
+    let tx = db.begin().await.unwrap();
+    user("Dave", "dave@example.com", None, None, true)
+        .create(&tx)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![libsql_orm::ChangeOperation::Insert]
+    );
+
+    let shared = std::sync::Arc::new(db.into_inner());
+    assert_eq!(User::count(&shared).await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn dropped_transactions_roll_back_before_the_next_statement() {
+    let db = setup_db().await.unwrap();
+    {
+        let tx = db.begin().await.unwrap();
+        user("Alice", "alice@example.com", None, None, true)
+            .create(&tx)
+            .await
+            .unwrap();
+    }
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+    assert!(!db.in_transaction());
+
+    // A new transaction can start right after a dropped one
+    let tx = db.begin().await.unwrap();
+    drop(tx);
+    let tx = db.begin().await.unwrap();
+    tx.commit().await.unwrap();
+    assert!(!db.in_transaction());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn failed_commits_roll_back() {
+    let db = setup_db().await.unwrap();
+    db.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE teams (id INTEGER PRIMARY KEY);
+         CREATE TABLE members (id INTEGER PRIMARY KEY,
+             team_id INTEGER REFERENCES teams (id) DEFERRABLE INITIALLY DEFERRED);",
+    )
+    .await
+    .unwrap();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(0));
+    let sink = events.clone();
+    db.subscribe(move |_| *sink.lock().unwrap() += 1);
+
+    let tx = db.begin().await.unwrap();
+    user("Alice", "alice@example.com", None, None, true)
+        .create(&tx)
+        .await
+        .unwrap();
+    tx.database()
+        .execute("INSERT INTO members (team_id) VALUES (5)", vec![])
+        .await
+        .unwrap();
+    assert!(tx.commit().await.is_err());
+    assert!(!db.in_transaction());
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+    assert_eq!(*events.lock().unwrap(), 0);
+}
+
+/// Forwards to a turso connection, remembering the SQL it was given
+struct RecordingDriver {
+    conn: turso::Connection,
+    sql: std::sync::Mutex<Vec<String>>,
+}
+
+impl libsql_orm::Driver for RecordingDriver {
+    async fn query(
+        &self,
+        sql: &str,
+        params: Vec<libsql_orm::Value>,
+    ) -> libsql_orm::Result<Vec<libsql_orm::Row>> {
+        self.sql.lock().unwrap().push(sql.to_string());
+        libsql_orm::Driver::query(&self.conn, sql, params).await
+    }
+
+    async fn execute(&self, sql: &str, params: Vec<libsql_orm::Value>) -> libsql_orm::Result<u64> {
+        self.sql.lock().unwrap().push(sql.to_string());
+        libsql_orm::Driver::execute(&self.conn, sql, params).await
+    }
+
+    async fn batch(&self, sql: &str) -> libsql_orm::Result<()> {
+        self.sql.lock().unwrap().push(sql.to_string());
+        libsql_orm::Driver::batch(&self.conn, sql).await
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn models_run_on_custom_drivers() {
+    let db = Database::new_local(":memory:").await.unwrap();
+    let driver = RecordingDriver {
+        conn: db.inner.clone(),
+        sql: std::sync::Mutex::new(Vec::new()),
+    };
+    libsql_orm::Driver::batch(&driver, &User::migration_sql())
+        .await
+        .unwrap();
+    let db = libsql_orm::DriverExecutor::new(driver);
+
+    user("Alice", "alice@example.com", Some(30), None, true)
+        .create(&db)
+        .await
+        .unwrap();
+    User::bulk_create(&[user("Bob", "bob@example.com", None, None, false)], &db)
+        .await
+        .unwrap();
+    let users = User::find_where(FilterOperator::Single(Filter::eq("is_active", true)), &db)
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name, "Alice");
+
+    let err = libsql_orm::Executor::execute_statement(
+        &db,
+        Some("users"),
+        "INSERT INTO missing VALUES (?)",
+        vec![libsql_orm::Value::Integer(1)],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.context().unwrap().params, vec!["integer".to_string()]);
+
+    let sql = db.into_inner().sql.into_inner().unwrap();
+    assert_eq!(sql.len(), 7);
+    assert_eq!(sql[2], "BEGIN");
+    assert_eq!(sql[4], "COMMIT");
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test(flavor = "current_thread")]
+async fn axum_handlers_get_database_and_request_transactions() {
+    use axum::body::Body;
+    use axum::extract::Path;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{middleware, Json, Router};
+    use http_body_util::BodyExt;
+    use libsql_orm::axum::{transaction, Db, Tx};
+    use tower::ServiceExt;
+
+    async fn show_user(Db(db): Db, Path(id): Path<i64>) -> libsql_orm::Result<Json<User>> {
+        User::find_by_id(id, &*db)
+            .await?
+            .map(Json)
+            .ok_or_else(|| libsql_orm::Error::NotFound(format!("User {id}")))
+    }
+
+    async fn create_user(tx: Tx, Json(new): Json<User>) -> libsql_orm::Result<StatusCode> {
+        new.create(&tx).await?;
+        if new.name.is_empty() {
+            return Err(libsql_orm::Error::Validation(
+                "name is required".to_string(),
+            ));
+        }
+        Ok(StatusCode::CREATED)
+    }
+
+    let db = std::sync::Arc::new(setup_db().await.unwrap().into_inner());
+    let app = Router::new()
+        .route("/users/{id}", get(show_user))
+        .route(
+            "/users",
+            post(create_user).layer(middleware::from_fn_with_state(db.clone(), transaction)),
+        )
+        .with_state(db.clone());
+    let post_user = |name: &str| {
+        Request::post("/users")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&user(name, "x@example.com", None, None, true)).unwrap(),
+            ))
+            .unwrap()
+    };
+
+    let created = app.clone().oneshot(post_user("Alice")).await.unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let rejected = app.clone().oneshot(post_user("")).await.unwrap();
+    assert_eq!(rejected.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = rejected.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()["error"],
+        "Validation error: name is required"
+    );
+    assert_eq!(User::count(&*db).await.unwrap(), 1);
+    assert!(!db.in_transaction());
+
+    let missing = app
+        .oneshot(Request::get("/users/99").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test(flavor = "current_thread")]
+async fn axum_request_transactions_run_one_at_a_time() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::post;
+    use axum::{middleware, Router};
+    use libsql_orm::axum::{transaction, Tx};
+    use tower::ServiceExt;
+
+    async fn create_user(tx: Tx, name: String) -> libsql_orm::Result<StatusCode> {
+        user(&name, &format!("{name}@example.com"), None, None, true)
+            .create(&tx)
+            .await?;
+        // Let the other request run while this transaction is open
+        tokio::task::yield_now().await;
+        match name.as_str() {
+            "panic" => panic!("handler panicked"),
+            "reject" => Err(libsql_orm::Error::Validation("rejected".to_string())),
+            _ => Ok(StatusCode::CREATED),
+        }
+    }
+
+    let db = std::sync::Arc::new(setup_db().await.unwrap().into_inner());
+    let app = Router::new()
+        .route("/users", post(create_user))
+        .layer(middleware::from_fn_with_state(db.clone(), transaction))
+        .with_state(db.clone());
+    let post_user = |name: &str| {
+        Request::post("/users")
+            .body(Body::from(name.to_string()))
+            .unwrap()
+    };
+
+    let (rejected, created) = tokio::join!(
+        app.clone().oneshot(post_user("reject")),
+        app.clone().oneshot(post_user("alice")),
+    );
+    assert_eq!(rejected.unwrap().status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(created.unwrap().status(), StatusCode::CREATED);
+    assert_eq!(User::count(&*db).await.unwrap(), 1);
+
+    let panicked = tokio::spawn(app.clone().oneshot(post_user("panic"))).await;
+    assert!(panicked.unwrap_err().is_panic());
+    let created = app.oneshot(post_user("bob")).await.unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    assert_eq!(User::count(&*db).await.unwrap(), 2);
+    assert!(!db.in_transaction());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn exports_stream_csv_and_json_lines() {
+    let db = setup_db().await.unwrap();
+    User::bulk_create(
+        &[
+            user("Smith, Alice", "alice@example.com", Some(30), None, true),
+            user(
+                "Bob \"The Builder\"",
+                "bob@example.com",
+                None,
+                Some(1.5),
+                false,
+            ),
+        ],
+        &db,
+    )
+    .await
+    .unwrap();
+
+    let mut csv = Vec::new();
+    let written = QueryBuilder::new("users")
+        .select(["name", "age", "score", "is_active"])
+        .order_by(Sort::asc("id"))
+        .export_csv(&mut csv, &db)
+        .await
+        .unwrap();
+    assert_eq!(written, 2);
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "name,age,score,is_active\r\n\
+         \"Smith, Alice\",30,,1\r\n\
+         \"Bob \"\"The Builder\"\"\",,1.5,0\r\n"
+    );
+
+    let mut jsonl = Vec::new();
+    let written = User::export_jsonl(
+        Some(FilterOperator::Single(Filter::eq("is_active", false))),
+        &mut jsonl,
+        &db,
+    )
+    .await
+    .unwrap();
+    assert_eq!(written, 1);
+    let lines: Vec<User> = String::from_utf8(jsonl)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].email, "bob@example.com");
+    assert!(!lines[0].is_active);
+
+    UserFactory::new()
+        .create_many(libsql_orm::model::EXPORT_BATCH_SIZE as usize, &db)
+        .await
+        .unwrap();
+    let written = User::export_jsonl(None, std::io::sink(), &db)
+        .await
+        .unwrap();
+    assert_eq!(written, libsql_orm::model::EXPORT_BATCH_SIZE as u64 + 2);
+
+    // Whole tables are read in batches, with the table's column order
+    let mut csv = Vec::new();
+    let written = QueryBuilder::new("users")
+        .order_by(Sort::asc("id"))
+        .export_csv(&mut csv, &db)
+        .await
+        .unwrap();
+    assert_eq!(written, libsql_orm::model::EXPORT_BATCH_SIZE as u64 + 2);
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), written as usize + 1);
+    assert!(csv.starts_with("id,name,email,age,score,is_active\r\n1,"));
+
+    let limited = QueryBuilder::new("users")
+        .order_by(Sort::asc("id"))
+        .limit(3)
+        .offset(1)
+        .export_csv(std::io::sink(), &db)
+        .await
+        .unwrap();
+    assert_eq!(limited, 3);
+
+    let token = libsql_orm::CancellationToken::new();
+    token.cancel();
+    let cancelled = libsql_orm::Executor::with_options(
+        &*db,
+        libsql_orm::QueryOptions::new().cancellation_token(token),
+    );
+    let err = QueryBuilder::new("users")
+        .export_csv(std::io::sink(), &cancelled)
+        .await
+        .unwrap_err();
+    assert!(err.is_cancelled(), "{err}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn csv_import_reports_bad_rows_and_keeps_the_rest() {
+    let db = TestDatabase::new::<(User, Signup)>().await.unwrap();
+    let csv = "name,email,age,score,is_active\n\
+               Alice,alice@example.com,30,,true\n\
+               \"Smith, Bob\",bob@example.com,,2.5,0\n\
+               Carol,carol@example.com,old,,1\n\
+               \"Multi\nline\",dave@example.com,,,1\n\
+               Eve,alice@example.com,31,,false\n\
+               Frank,frank@example.com,1\n";
+    let report = User::import_csv(
+        csv.as_bytes(),
+        &db,
+        libsql_orm::import::ImportOptions {
+            batch_size: 2,
+            upsert_on: Some("email"),
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(report.imported, 4);
+    let failed: Vec<u64> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(failed, vec![4, 8]);
+    assert!(report.errors[0]
+        .error
+        .to_string()
+        .contains("age: expected an integer"));
+
+    let users = User::find_all(&db).await.unwrap();
+    assert_eq!(users.len(), 3);
+    let eve = users
+        .iter()
+        .find(|u| u.email == "alice@example.com")
+        .unwrap();
+    assert_eq!(eve.name, "Eve");
+    assert_eq!(eve.age, Some(31));
+    assert!(!eve.is_active);
+    assert!(users.iter().any(|u| u.name == "Multi\nline" && u.is_active));
+    assert!(!db.in_transaction());
+
+    let report = Signup::import_csv(
+        "username,email,age\nbob,not-an-email,30\njane,jane@example.com,40\n".as_bytes(),
+        &db,
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.imported, 1);
+    assert!(matches!(
+        &report.errors[0].error,
+        libsql_orm::Error::InvalidFields(errors) if errors.get("email").is_some()
+    ));
+}
+
+#[cfg(feature = "sea-query")]
+#[tokio::test(flavor = "current_thread")]
+async fn sea_query_statements_run_with_bound_values() {
+    use libsql_orm::sea_query::{name, SeaQueryExt};
+    use sea_query::{Alias, Condition, Expr, ExprTrait, Order, Query};
+
+    #[derive(FromRow, Deserialize)]
+    struct Inserted {
+        id: i64,
+    }
+
+    let db = TestDatabase::new::<(User,)>().await.unwrap();
+    let insert = Query::insert()
+        .into_table(User::table_name())
+        .columns(["name", "email", "age", "is_active"])
+        .values_panic([
+            "O'Brien".into(),
+            "ob@example.com".into(),
+            41.into(),
+            true.into(),
+        ])
+        .values_panic([
+            "Ann".into(),
+            "ann@example.com".into(),
+            29.into(),
+            false.into(),
+        ])
+        .returning(Query::returning().column("id"))
+        .to_owned();
+    let ids: Vec<Inserted> = db.query_seaquery(&insert).await.unwrap();
+    assert!(ids[0].id < ids[1].id);
+
+    let update = Query::update()
+        .table(User::table_name())
+        .value("score", 1.5)
+        .and_where(Expr::col("age").gt(30))
+        .to_owned();
+    assert_eq!(db.execute_seaquery(&update).await.unwrap(), 1);
+
+    let select = Query::select()
+        .column(sea_query::Asterisk)
+        .from(User::table_name())
+        .and_where(Expr::col("name").eq("O'Brien"))
+        .order_by("id", Order::Asc)
+        .to_owned();
+    let users: Vec<User> = db.query_seaquery(&select).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].score, Some(1.5));
+    assert!(users[0].is_active);
+
+    let (sql, params) = libsql_orm::sea_query::build(&select);
+    assert!(!sql.contains("O'Brien"));
+    assert_eq!(params, vec![libsql_orm::Value::Text("O'Brien".to_string())]);
+
+    let filtered = QueryBuilder::new(User::table_name())
+        .r#where(
+            Condition::any()
+                .add(Expr::col("email").like("ann@%"))
+                .add(Expr::col("age").gte(40)),
+        )
+        .order_by(Sort::asc(name(Alias::new("email"))))
+        .execute_model::<User>(&db)
+        .await
+        .unwrap();
+    let emails: Vec<&str> = filtered.iter().map(|u| u.email.as_str()).collect();
+    assert_eq!(emails, vec!["ann@example.com", "ob@example.com"]);
+
+    let everyone = QueryBuilder::new("users")
+        .r#where(Condition::all())
+        .execute_count(&db)
+        .await
+        .unwrap();
+    assert_eq!(everyone, 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn find_by_ids_loads_existing_records_in_one_query() {
+    let db = TestDatabase::new::<(User,)>().await.unwrap();
+    User::bulk_create(&UserFactory::new().build_many(3), &db)
+        .await
+        .unwrap();
+
+    let mut users = User::find_by_ids(&[3, 1, 42], &db).await.unwrap();
+    users.sort_by_key(|u| u.id);
+    let ids: Vec<Option<i64>> = users.iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![Some(1), Some(3)]);
+    assert!(User::find_by_ids(&[], &db).await.unwrap().is_empty());
+}
+
+#[cfg(feature = "graphql")]
+#[tokio::test(flavor = "current_thread")]
+async fn graphql_connections_and_batched_loaders() {
+    use async_graphql::connection::Connection;
+    use async_graphql::dataloader::DataLoader;
+    use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+    use libsql_orm::graphql::{pagination, ModelLoader, TotalCount};
+    use libsql_orm::{Database, QueryAnalyzer};
+    use std::sync::Arc;
+
+    #[derive(Model, SimpleObject, Debug, Clone, Serialize, Deserialize)]
+    #[table_name("authors")]
+    struct Author {
+        #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
+        id: Option<i64>,
+        name: String,
+    }
+
+    #[derive(Model, SimpleObject, Debug, Clone, Serialize, Deserialize)]
+    #[table_name("books")]
+    #[graphql(complex)]
+    struct Book {
+        #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
+        id: Option<i64>,
+        #[orm_column(type = "INTEGER")]
+        author_id: i64,
+        title: String,
+    }
+
+    #[async_graphql::ComplexObject]
+    impl Book {
+        async fn author(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Author>> {
+            let loader = ctx.data_unchecked::<DataLoader<ModelLoader<Author>>>();
+            Ok(loader.load_one(self.author_id).await?)
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn books(
+            &self,
+            ctx: &Context<'_>,
+            after: Option<String>,
+            first: Option<i32>,
+        ) -> async_graphql::Result<Connection<usize, Book, TotalCount>> {
+            let db = ctx.data_unchecked::<Arc<Database>>();
+            let page = pagination(after.as_deref(), first, 10)?;
+            Ok(Book::find_paginated(&page, &**db).await?.into_connection())
+        }
+    }
+
+    let db = Arc::new(
+        TestDatabase::new::<(Author, Book)>()
+            .await
+            .unwrap()
+            .into_inner(),
+    );
+    for name in ["Le Guin", "Butler"] {
+        Author {
+            id: None,
+            name: name.to_string(),
+        }
+        .create(&*db)
+        .await
+        .unwrap();
+    }
+    for (author_id, title) in [(1, "Earthsea"), (2, "Kindred"), (1, "The Dispossessed")] {
+        Book {
+            id: None,
+            author_id,
+            title: title.to_string(),
+        }
+        .create(&*db)
+        .await
+        .unwrap();
+    }
+    db.enable_query_analyzer(QueryAnalyzer::new().threshold(2));
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(
+            ModelLoader::<Author>::new(db.clone()),
+            tokio::spawn,
+        ))
+        .data(db.clone())
+        .finish();
+    let response = schema
+        .execute(
+            "{ books(first: 2) { totalCount pageInfo { hasNextPage endCursor } \
+               edges { cursor node { title author { name } } } } }",
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    let books = &data["books"];
+    assert_eq!(books["totalCount"], 3);
+    assert_eq!(books["pageInfo"]["hasNextPage"], true);
+    assert_eq!(books["edges"][1]["node"]["author"]["name"], "Butler");
+    assert!(db.repeated_queries().is_empty());
+
+    let cursor = books["pageInfo"]["endCursor"].as_str().unwrap().to_string();
+    let response = schema
+        .execute(format!(
+            "{{ books(first: 2, after: \"{cursor}\") {{ pageInfo {{ hasNextPage hasPreviousPage }} \
+               edges {{ node {{ title }} }} }} }}"
+        ))
+        .await;
+    let data = response.data.into_json().unwrap();
+    assert_eq!(
+        data["books"]["edges"][0]["node"]["title"],
+        "The Dispossessed"
+    );
+    assert_eq!(data["books"]["pageInfo"]["hasNextPage"], false);
+    assert_eq!(data["books"]["pageInfo"]["hasPreviousPage"], true);
+
+    assert!(pagination(Some("not a cursor"), None, 10).is_err());
+    assert!(pagination(None, Some(0), 10).is_err());
+}
+
+#[test]
+fn derived_json_schema_matches_columns_and_rules() {
+    /// A person waiting for an invite
+    #[derive(Model, Clone, Serialize, Deserialize)]
+    #[table_name("invites")]
+    struct Invite {
+        id: Option<i64>,
+        /// Where the invite goes
+        #[orm_validate(email)]
+        email: String,
+        tags: Vec<String>,
+        #[orm_validate(range(min = 0.5))]
+        priority: f64,
+        sent_at: Option<chrono::DateTime<chrono::Utc>>,
+        extra: serde_json::Value,
+    }
+
+    let schema = Invite::json_schema();
+    assert_eq!(schema["title"], "Invite");
+    assert_eq!(schema["description"], "A person waiting for an invite");
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["email", "tags", "priority", "extra"])
+    );
+    let properties = &schema["properties"];
+    assert_eq!(
+        properties["id"],
+        serde_json::json!({"type": ["integer", "null"]})
+    );
+    assert_eq!(
+        properties["email"],
+        serde_json::json!({"description": "Where the invite goes", "type": "string", "format": "email"})
+    );
+    assert_eq!(
+        properties["tags"],
+        serde_json::json!({"type": "array", "items": {"type": "string"}})
+    );
+    assert_eq!(
+        properties["priority"],
+        serde_json::json!({"type": "number", "minimum": 0.5})
+    );
+    assert_eq!(
+        properties["sent_at"],
+        serde_json::json!({"type": ["string", "null"], "format": "date-time"})
+    );
+    assert_eq!(properties["extra"], serde_json::json!({}));
+
+    let signup = Signup::json_schema();
+    assert_eq!(
+        signup["properties"]["username"],
+        serde_json::json!({"type": "string", "minLength": 3, "maxLength": 20})
+    );
+    assert_eq!(
+        signup["properties"]["age"],
+        serde_json::json!({"type": ["integer", "null"], "minimum": 13, "maximum": 120})
+    );
+}
+
+#[cfg(feature = "schemars")]
+libsql_orm::impl_json_schema!(Signup);
+
+#[cfg(feature = "schemars")]
+#[test]
+fn schemars_uses_the_derived_model_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(Vec<Signup>)).unwrap();
+    assert_eq!(schema["items"]["$ref"], "#/$defs/Signup");
+    let signup = &schema["$defs"]["Signup"];
+    assert_eq!(signup["properties"]["email"]["format"], "email");
+    assert!(signup.get("$schema").is_none());
+    assert_eq!(signup["required"], serde_json::json!(["username", "email"]));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn cached_reads_are_invalidated_by_writes() {
+    use libsql_orm::cache::{CacheStats, CachedExecutor};
+    use std::time::Duration;
+
+    let db = TestDatabase::new::<(User, Signup)>()
+        .await
+        .unwrap()
+        .into_inner();
+    let db = CachedExecutor::new(db, 2).ttl::<Signup>(Duration::ZERO);
+    let alice = UserFactory::new().create(&db).await.unwrap();
+    let id = alice.get_primary_key().unwrap();
+
+    User::find_by_id(id, &db).await.unwrap();
+    let cached = User::find_by_id(id, &db).await.unwrap().unwrap();
+    assert_eq!(cached.name, alice.name);
+    assert_eq!(db.stats(), CacheStats { hits: 1, misses: 1 });
+
+    let mut renamed = cached;
+    renamed.name = "Renamed".to_string();
+    renamed.update(&db).await.unwrap();
+    let fresh = User::find_by_id(id, &db).await.unwrap().unwrap();
+    assert_eq!(fresh.name, "Renamed");
+    assert_eq!(db.stats().misses, 2);
+
+    // Least recently used entries make room for new ones
+    User::find_all(&db).await.unwrap();
+    User::count(&db).await.unwrap();
+    assert_eq!(db.store().len(), 2);
+    User::find_by_id(id, &db).await.unwrap();
+    assert_eq!(db.stats().misses, 5);
+
+    // Signups are never cached
+    Signup::find_all(&db).await.unwrap();
+    Signup::find_all(&db).await.unwrap();
+    assert_eq!(db.stats().misses, 5);
+
+    let db = CachedExecutor::new(db.into_inner(), 10).default_ttl(Duration::from_millis(1));
+    User::find_all(&db).await.unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+    User::find_all(&db).await.unwrap();
+    assert_eq!(db.stats(), CacheStats { hits: 0, misses: 2 });
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn bulk_operations_split_past_the_variable_limit() {
+    let db = setup_db().await.unwrap();
+    let users: Vec<User> = (0..3000)
+        .map(|i| {
+            user(
+                &format!("U{i}"),
+                &format!("u{i}@example.com"),
+                Some(i),
+                None,
+                true,
+            )
+        })
+        .collect();
+    User::bulk_create(&users, &db).await.unwrap();
+    assert_eq!(User::count(&db).await.unwrap(), 3000);
+
+    let ids: Vec<i64> = User::find_all(&db)
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|u| u.id)
+        .collect();
+    let mut found = User::find_by_ids(&ids, &db).await.unwrap();
+    found.sort_by_key(|u| u.id);
+    assert_eq!(found.len(), 3000);
+    assert_eq!(found[2999].email, "u2999@example.com");
+
+    let deleted = User::bulk_delete(&ids[..2500], &db).await.unwrap();
+    assert_eq!(deleted, 2500);
+    assert_eq!(User::count(&db).await.unwrap(), 500);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn reserved_words_work_as_table_and_column_names() {
+    #[derive(Model, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[table_name("order")]
+    struct Order {
+        #[orm_column(type = "INTEGER PRIMARY KEY AUTOINCREMENT")]
+        id: Option<i64>,
+        group: String,
+        #[orm_column(type = "INTEGER")]
+        select: i64,
+    }
+
+    let db = TestDatabase::new::<(Order,)>().await.unwrap();
+    let mut order = Order {
+        id: None,
+        group: "books".to_string(),
+        select: 2,
+    }
+    .create(&*db)
+    .await
+    .unwrap();
+
+    order.select = 3;
+    order.update(&*db).await.unwrap();
+    let found = Order::find_where(
+        FilterOperator::Single(Filter::eq(Order::column("group").unwrap(), "books")),
+        &*db,
+    )
+    .await
+    .unwrap();
+    assert_eq!(found, vec![order.clone()]);
+    assert_eq!(Order::count(&*db).await.unwrap(), 1);
+
+    assert!(matches!(
+        Order::column("group; DROP TABLE \"order\""),
+        Err(libsql_orm::Error::Query(_))
+    ));
+
+    order.delete(&*db).await.unwrap();
+    assert_eq!(Order::count(&*db).await.unwrap(), 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn pagination_count_strategies() {
+    use libsql_orm::CountStrategy;
+    use std::time::Duration;
+
+    let db = setup_db().await.unwrap();
+    let users: Vec<User> = (0..30)
+        .map(|i| {
+            user(
+                &format!("u{i}"),
+                &format!("u{i}@x.com"),
+                Some(i),
+                None,
+                i % 2 == 0,
+            )
+        })
+        .collect();
+    User::bulk_create(&users, &*db).await.unwrap();
+
+    let counts = CountStrategy::cached(Duration::from_secs(60));
+    let cached = Pagination::new(1, 10).with_count_strategy(counts.clone());
+    let page = User::find_paginated(&cached, &*db).await.unwrap();
+    assert_eq!(page.pagination.total, Some(30));
+
+    user("late", "late@x.com", None, None, true)
+        .create(&*db)
+        .await
+        .unwrap();
+    let page = User::find_paginated(&cached, &*db).await.unwrap();
+    assert_eq!(page.pagination.total, Some(30));
+    assert_eq!(page.pagination.total_pages, Some(3));
+    if let CountStrategy::Cached(cache) = &counts {
+        cache.clear();
+    }
+    let page = User::find_paginated(&cached, &*db).await.unwrap();
+    assert_eq!(page.pagination.total, Some(31));
+
+    let first = User::find_by_id(1, &*db).await.unwrap().unwrap();
+    first.delete(&*db).await.unwrap();
+    let estimated = Pagination::new(1, 10).with_count_strategy(CountStrategy::Estimated);
+    let page = User::find_paginated(&estimated, &*db).await.unwrap();
+    // max(rowid) still counts the deleted row
+    assert_eq!(page.pagination.total, Some(31));
+    assert!(page.pagination.total_estimated);
+    assert_eq!(page.data.len(), 10);
+
+    // After ANALYZE the estimate comes from sqlite_stat1
+    db.execute_batch("ANALYZE").await.unwrap();
+    let page = User::find_paginated(&estimated, &*db).await.unwrap();
+    assert_eq!(page.pagination.total, Some(30));
+    assert!(page.pagination.total_estimated);
+
+    let filtered = User::find_where_paginated(
+        FilterOperator::Single(Filter::eq("is_active", true)),
+        &estimated,
+        &*db,
+    )
+    .await
+    .unwrap();
+    assert_eq!(filtered.pagination.total, Some(15));
+    assert!(!filtered.pagination.total_estimated);
+
+    let exact = User::find_paginated(&Pagination::new(1, 10), &*db)
+        .await
+        .unwrap();
+    assert_eq!(exact.pagination.total, Some(30));
+    assert!(!exact.pagination.total_estimated);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn query_options_time_out_and_cancel_statements() {
+    use libsql_orm::{CancellationToken, Executor, QueryOptions};
+    use std::time::Duration;
+
+    let db = setup_db().await.unwrap();
+    user("Ann", "ann@x.com", None, None, true)
+        .create(&*db)
+        .await
+        .unwrap();
+    let values: Vec<String> = (0..1000).map(|i| format!("({i})")).collect();
+    db.execute_batch(&format!(
+        "CREATE TABLE nums (x INTEGER); INSERT INTO nums VALUES {}",
+        values.join(", ")
+    ))
+    .await
+    .unwrap();
+    // A billion rows; far more than either limit lets through
+    let endless = "SELECT a.x FROM nums a, nums b, nums c";
+
+    let options = QueryOptions::new().timeout(Duration::from_millis(50));
+    let err = db
+        .query_with_options(endless, vec![], &options)
+        .await
+        .unwrap_err();
+    assert!(err.is_timeout(), "{err}");
+    assert_eq!(err.status_code(), 504);
+    assert!(!err.is_retryable());
+
+    // Cancelled from another thread while rows are being read
+    let token = CancellationToken::new();
+    let canceller = {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        })
+    };
+    let options = QueryOptions::new().cancellation_token(token.clone());
+    let err = db
+        .query_with_options(endless, vec![], &options)
+        .await
+        .unwrap_err();
+    canceller.join().unwrap();
+    assert!(err.is_cancelled(), "{err}");
+
+    // Models and builders take the options through the executor wrapper
+    let cancelled = db.with_options(options);
+    assert!(User::find_all(&cancelled).await.unwrap_err().is_cancelled());
+    let users = QueryBuilder::new("users");
+    assert!(users
+        .execute::<User>(&cancelled)
+        .await
+        .unwrap_err()
+        .is_cancelled());
+    assert!(users
+        .execute_aggregate(&cancelled)
+        .await
+        .unwrap_err()
+        .is_cancelled());
+    assert!(users
+        .execute_paginated::<User>(&cancelled, &Pagination::new(1, 10))
+        .await
+        .unwrap_err()
+        .is_cancelled());
+    assert!(user("Bob", "bob@x.com", None, None, true)
+        .create(&cancelled)
+        .await
+        .unwrap_err()
+        .is_cancelled());
+
+    // The connection is usable after a statement was abandoned
+    let relaxed = db.with_options(QueryOptions::new().timeout(Duration::from_secs(5)));
+    assert_eq!(User::count(&relaxed).await.unwrap(), 1);
+    let rows = QueryBuilder::new("users")
+        .execute_as::<NameAndEmail>(&relaxed)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+/// Cancels `token` when the second `INSERT` starts
+struct CancelOnSecondInsert<'a> {
+    db: &'a Database,
+    token: libsql_orm::CancellationToken,
+    inserts: std::sync::atomic::AtomicUsize,
+}
+
+impl libsql_orm::Executor for CancelOnSecondInsert<'_> {
+    async fn execute_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<libsql_orm::Value>,
+    ) -> libsql_orm::Result<u64> {
+        if sql.starts_with("INSERT")
+            && self
+                .inserts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                == 1
+        {
+            self.token.cancel();
+        }
+        self.db.execute_statement(table, sql, params).await
+    }
+
+    async fn query_statement(
+        &self,
+        table: Option<&str>,
+        sql: &str,
+        params: Vec<libsql_orm::Value>,
+    ) -> libsql_orm::Result<Vec<libsql_orm::Row>> {
+        self.db.query_statement(table, sql, params).await
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.db.in_transaction()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn cancelled_bulk_writes_roll_back() {
+    let db = setup_db().await.unwrap();
+    let token = libsql_orm::CancellationToken::new();
+    let executor = CancelOnSecondInsert {
+        db: &db,
+        token: token.clone(),
+        inserts: Default::default(),
+    };
+    let cancellable = libsql_orm::Executor::with_options(
+        &executor,
+        libsql_orm::QueryOptions::new().cancellation_token(token),
+    );
+
+    // Enough rows for several INSERT chunks
+    let users: Vec<User> = (0..1000)
+        .map(|i| {
+            user(
+                &format!("U{i}"),
+                &format!("u{i}@example.com"),
+                None,
+                None,
+                true,
+            )
+        })
+        .collect();
+    let err = User::bulk_create(&users, &cancellable).await.unwrap_err();
+    assert!(err.is_cancelled(), "{err}");
+    assert!(!db.in_transaction());
+    assert_eq!(User::count(&db).await.unwrap(), 0);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn hostile_strings_are_bound_and_matched_literally() {
+    let db = TestDatabase::new::<(User,)>().await.unwrap();
+    let names = [
+        "'; DROP TABLE users; --",
+        r#"" OR "1"="1"#,
+        "100% pure",
+        "1000 pure",
+        "a_b",
+        "axb",
+        r"back\slash",
+        "what?",
+        "Robert'); DELETE FROM users; --",
+        "ÜBER ß",
+    ];
+    for (i, name) in names.iter().enumerate() {
+        user(name, &format!("u{i}@example.com"), None, None, true)
+            .create(&*db)
+            .await
+            .unwrap();
+    }
+
+    let contains = |needle: &str| {
+        let needle = needle.to_ascii_lowercase();
+        let mut expected: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| name.to_ascii_lowercase().contains(&needle))
+            .collect();
+        expected.sort_unstable();
+        expected
+    };
+    let sorted = |users: Vec<User>| {
+        let mut names: Vec<String> = users.into_iter().map(|u| u.name).collect();
+        names.sort_unstable();
+        names
+    };
+
+    let needles = names
+        .iter()
+        .copied()
+        .chain(["%", "_", "\\", "'", "\"", "?", "0%", "a_"]);
+    for needle in needles {
+        let expected = contains(needle);
+
+        let found = QueryBuilder::new("users")
+            .search("name", needle)
+            .execute_model::<User>(&*db)
+            .await
+            .unwrap();
+        assert_eq!(sorted(found), expected, "search for {needle:?}");
+
+        let search = SearchFilter::new(needle, vec!["name"]);
+        let found = User::search(&search, None, &*db).await.unwrap();
+        assert_eq!(sorted(found.data), expected, "SearchFilter for {needle:?}");
+
+        let hits = User::search_ranked(&search, None, &*db).await.unwrap();
+        assert_eq!(hits.len(), expected.len(), "ranked search for {needle:?}");
+        assert!(hits.iter().all(|hit| hit.score == 1.0));
+
+        let found = User::find_where(Filter::starts_with("name", needle).into(), &*db)
+            .await
+            .unwrap();
+        assert!(found.iter().all(|u| u.name.starts_with(needle)));
+    }
+
+    for name in names {
+        let found = User::find_where(Filter::eq("name", name).into(), &*db)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1, "eq for {name:?}");
+        let found = User::find_where(Filter::in_values("name", vec![name, "none"]).into(), &*db)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1, "in for {name:?}");
+    }
+
+    let page = QueryBuilder::new("users")
+        .order_by(Sort::asc("id"))
+        .limit(3)
+        .offset(8)
+        .execute_model::<User>(&*db)
+        .await
+        .unwrap();
+    let page: Vec<&str> = page.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(page, names[8..]);
+
+    assert_eq!(User::count(&*db).await.unwrap(), names.len() as u64);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn children_aggregate_into_delimited_and_json_columns() {
+    use libsql_orm::de::Delimited;
+    use std::collections::HashMap;
+
+    #[derive(FromRow, Debug, Deserialize)]
+    struct PostWithTags {
+        id: i64,
+        title: String,
+        tag: Delimited<String, '|'>,
+    }
+
+    #[derive(FromRow, Debug, Deserialize)]
+    struct PostWithJson {
+        id: i64,
+        tag: Vec<String>,
+        weight: HashMap<String, i64>,
+    }
+
+    #[derive(FromRow, Debug, Deserialize)]
+    struct PostWeights {
+        weight: Delimited<i64>,
+    }
+
+    let db = setup_db().await.unwrap();
+    db.execute(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    db.execute(
+        "CREATE TABLE tags (post_id INTEGER NOT NULL, tag TEXT NOT NULL, weight INTEGER NOT NULL)",
+        vec![],
+    )
+    .await
+    .unwrap();
+    for (id, title) in [(1, "Rust"), (2, "SQL"), (3, "Drafts")] {
+        InsertBuilder::table("posts")
+            .value("id", id)
+            .value("title", title)
+            .execute(&db)
+            .await
+            .unwrap();
+    }
+    for (post_id, tag, weight) in [(1, "lang", 3), (1, "a|b", 1), (2, "db", 2)] {
+        InsertBuilder::table("tags")
+            .value("post_id", post_id)
+            .value("tag", tag)
+            .value("weight", weight)
+            .execute(&db)
+            .await
+            .unwrap();
+    }
+
+    let tagged = || {
+        QueryBuilder::new("posts")
+            .join(
+                libsql_orm::JoinType::Left,
+                "tags",
+                "tags.post_id = posts.id",
+            )
+            .group_by(vec!["posts.id"])
+            .order_by(Sort::asc("posts.id"))
+    };
+
+    let posts: Vec<PostWithTags> = tagged()
+        .select(["posts.id", "posts.title"])
+        .aggregate_group_concat("tags.tag", "|")
+        .execute_as(&db)
+        .await
+        .unwrap();
+    assert_eq!(posts.len(), 3);
+    assert_eq!((posts[0].id, posts[0].title.as_str()), (1, "Rust"));
+    let mut rust_tags = posts[0].tag.to_vec();
+    rust_tags.sort();
+    // The separator is not escaped, so values containing it split too
+    assert_eq!(rust_tags, ["a", "b", "lang"]);
+    assert_eq!(*posts[1].tag, ["db"]);
+    assert!(posts[2].tag.is_empty());
+
+    let posts: Vec<PostWithJson> = tagged()
+        .select(["posts.id"])
+        .aggregate_json_group_array("tags.tag")
+        .aggregate_json_group_object("tags.tag", "tags.weight")
+        .execute_as(&db)
+        .await
+        .unwrap();
+    let mut rust_tags = posts[0].tag.clone();
+    rust_tags.sort();
+    assert_eq!(rust_tags, ["a|b", "lang"]);
+    assert_eq!(
+        posts[0].weight,
+        HashMap::from([("lang".into(), 3), ("a|b".into(), 1)])
+    );
+    assert_eq!(posts[1].tag, ["db"]);
+    assert!(posts[2].tag.is_empty() && posts[2].weight.is_empty());
+    assert_eq!(posts.iter().map(|p| p.id).collect::<Vec<_>>(), [1, 2, 3]);
+
+    let weights: Vec<PostWeights> = tagged()
+        .select(Vec::<&str>::new())
+        .aggregate_group_concat("tags.weight", ",")
+        .execute_as(&db)
+        .await
+        .unwrap();
+    let mut rust_weights = weights[0].weight.to_vec();
+    rust_weights.sort();
+    assert_eq!(rust_weights, [1, 3]);
+    // A single child comes back as a plain integer
+    assert_eq!(*weights[1].weight, [2]);
+    assert!(weights[2].weight.is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn edge_case_find_all_on_empty_table() {
+    let db = setup_db().await.unwrap();
+    let all = User::find_all(&db).await.unwrap();
+    assert!(all.is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
 async fn edge_case_delete_nonexistent_row() {
     let db = setup_db().await.unwrap();
     let ghost = User {
@@ -614,3 +4292,31 @@ async fn edge_case_delete_nonexistent_row() {
     assert!(deleted);
     assert_eq!(User::count(&db).await.unwrap(), 0);
 }
+
+#[cfg(debug_assertions)]
+#[tokio::test(flavor = "current_thread")]
+async fn query_analyzer_flags_n_plus_one_lookups() {
+    use libsql_orm::QueryAnalyzer;
+
+    let db = setup_db().await.unwrap();
+    let alice = insert_and_get_real(&db, &user("Alice", "alice@example.com", None, None, true))
+        .await
+        .unwrap();
+    db.enable_query_analyzer(QueryAnalyzer::new().threshold(3));
+
+    for _ in 0..4 {
+        User::find_by_id(alice.id.unwrap(), &db).await.unwrap();
+    }
+    User::find_all(&db).await.unwrap();
+
+    let repeated = db.repeated_queries();
+    assert_eq!(repeated.len(), 1);
+    assert_eq!(repeated[0].table.as_deref(), Some("users"));
+    assert_eq!(repeated[0].count, 4);
+
+    db.reset_query_analyzer();
+    assert!(db.repeated_queries().is_empty());
+    db.disable_query_analyzer();
+    User::find_by_id(alice.id.unwrap(), &db).await.unwrap();
+    assert!(db.repeated_queries().is_empty());
+}
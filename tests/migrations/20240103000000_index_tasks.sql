@@ -0,0 +1 @@
+CREATE INDEX idx_tasks_project_id ON tasks (project_id)